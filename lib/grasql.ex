@@ -32,17 +32,18 @@ defmodule GraSQL do
 
   ## Returns
 
-  * `{:ok, operations}` - List of SQL operations if successful
+  * `{:ok, operations}` - List of SQL operations if successful, each tagged with
+    `{:read, :query}` or `{:write, :insert | :update | :delete}` for routing
   * `{:error, reason}` - Error message if generation fails
 
   ## Examples
 
       iex> query = "{ users { id name } }"
       iex> GraSQL.generate_sql(query)
-      {:ok, [{"users", "SELECT id, name FROM users", []}]}
+      {:ok, [{"users", "SELECT id, name FROM users", [], {:read, :query}}]}
 
       iex> GraSQL.generate_sql(query, %{"userId" => 123})
-      {:ok, [{"users", "SELECT id, name FROM users WHERE id = $1", [123]}]}
+      {:ok, [{"users", "SELECT id, name FROM users WHERE id = $1", [123], {:read, :query}}]}
   """
   @spec generate_sql(String.t(), map(), map()) :: {:ok, list()} | {:error, String.t()}
   def generate_sql(query, variables \\ %{}, context \\ %{}) do
@@ -63,10 +64,10 @@ defmodule GraSQL do
 
   # Process SQL operations to include variables
   defp process_operations(operations, variables) do
-    Enum.reduce_while(operations, {:ok, []}, fn {name, sql, params}, {:ok, acc} ->
+    Enum.reduce_while(operations, {:ok, []}, fn {name, sql, params, kind}, {:ok, acc} ->
       case process_parameters(params, variables) do
         {:error, _} = err -> {:halt, err}
-        processed_params -> {:cont, {:ok, [{name, sql, processed_params} | acc]}}
+        processed_params -> {:cont, {:ok, [{name, sql, processed_params, kind} | acc]}}
       end
     end)
     |> case do
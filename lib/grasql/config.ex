@@ -50,6 +50,9 @@ defmodule GraSQL.Config do
   * `:nin` - Does not match any value in a list
   * `:is_null` - Is NULL check
 
+  ## Type casts
+  * `:cast` - Compare a column cast to another type, e.g. `%{_cast: %{String: %{_eq: "1"}}}`
+
   ## JSON operators
   * `:json_contains` - JSON containment check (PostgreSQL `@>`)
   * `:json_contained_in` - JSON contained by check (PostgreSQL `<@`)
@@ -59,6 +62,15 @@ defmodule GraSQL.Config do
   * `:json_path` - JSON field access (PostgreSQL `->`)
   * `:json_path_text` - JSON field access as text (PostgreSQL `->>`)
   * `:is_json` - JSON type validation check
+
+  ## Alternative JSON operator names
+  Some schemas (e.g. Hasura) spell the JSON operators differently. These map to
+  the same SQL operators as their `json_*` counterparts.
+  * `:contains` - Same as `:json_contains`
+  * `:contained_in` - Same as `:json_contained_in`
+  * `:has_key` - Same as `:json_has_key`
+  * `:has_keys_any` - Same as `:json_has_any_keys`
+  * `:has_keys_all` - Same as `:json_has_all_keys`
   """
   # Logical operators
   @type operator ::
@@ -79,6 +91,8 @@ defmodule GraSQL.Config do
           | :in
           | :nin
           | :is_null
+          # Type casts
+          | :cast
           # JSON operators
           | :json_contains
           | :json_contained_in
@@ -88,6 +102,12 @@ defmodule GraSQL.Config do
           | :json_path
           | :json_path_text
           | :is_json
+          # Alternative JSON operator names
+          | :contains
+          | :contained_in
+          | :has_key
+          | :has_keys_any
+          | :has_keys_all
 
   @typedoc """
   Configuration struct for GraSQL.
@@ -97,12 +117,31 @@ defmodule GraSQL.Config do
   ### Naming conventions
   * `aggregate_field_suffix` - Suffix for aggregate field names in GraphQL
   * `primary_key_argument_name` - Parameter name for single entity queries
+  * `by_pk_suffix` - Suffix for single entity query field names (default: "_by_pk")
   * `aggregate_nodes_field_name` - Field name for nodes in aggregate queries (default: "nodes")
+  * `scalar_fields` - JSON/array column names whose sub-selections are JSON path projections rather than relationships (default: [])
+  * `function_fields` - Root fields backed by SQL functions rather than tables, selected as `FROM function(...)` (default: [])
+  * `function_args_argument` - Argument of function fields holding the function's arguments (default: "args")
+  * `field_directives` - Custom field directives, named without the `@`, whose arguments are collected into the resolution request instead of being rejected (default: [])
+  * `relationship_fields` - Field names known to be relationships, typically taken from the schema (default: [])
+  * `require_relationship_selection` - Reject `relationship_fields` selected without a sub-selection instead of treating them as columns (default: false)
+  * `collect_argument_names` - List every argument name the query passes to any field in the `:argument_names` entry of the resolution request, so the schema layer can warn about arguments it doesn't declare (default: false)
+  * `expand_variable_filters` - Extract the relationships inside a `where` passed as a variable, using the `:variables` given to `GraSQL.Native.parse_query/2` (default: false)
+
+  ### Relay connection conventions
+  * `edges_field` - Connection field wrapping a list of edges (default: "edges")
+  * `node_field` - Edge field wrapping the underlying record (default: "node")
+  * `page_info_field` - Pagination metadata field, ignored for SQL (default: "pageInfo")
+
+  Set any of these to `""` to disable the corresponding handling.
 
   ### Mutation naming conventions
   * `insert_prefix` - Prefix for insert mutation fields in GraphQL (default: "insert_")
   * `update_prefix` - Prefix for update mutation fields in GraphQL (default: "update_")
   * `delete_prefix` - Prefix for delete mutation fields in GraphQL (default: "delete_")
+  * `validate_mutation_shape` - Reject inserts without `objects`/`object`, updates without `_set` and deletes without `where` or a primary key (default: false)
+  * `allow_empty_insert` - Generate a statement inserting zero rows for `objects: []` instead of returning an error (default: false)
+  * `mutation_returning_cte` - Generate mutations that return rows as a `WITH mutation AS (...)` CTE selected from afterwards, so relationships in `returning` are read off the written rows (default: false)
 
   ### Operator mappings
   * `operators` - Map of GraphQL operator suffixes for each operator type
@@ -114,6 +153,21 @@ defmodule GraSQL.Config do
 
   ### Performance settings
   * `max_query_depth` - Maximum allowed depth for GraphQL queries
+  * `max_columns_per_table` - Maximum number of columns a single table may reference (`nil` for no limit)
+  * `max_new_symbols_per_query` - Maximum number of previously unseen identifiers a single query may introduce (`nil` for no limit)
+  * `max_operations` - Maximum number of operations a single document may define (`nil` for no limit)
+  * `subscription_mode` - How subscriptions are handled: `:reject` returns an error, `:as_query` analyzes them like queries (default: `:reject`)
+
+  ### SQL generation
+  * `always_include_pk` - Select each table's primary key columns even when the query does not (default: false)
+  * `max_bind_params` - Maximum number of bind parameters per generated statement, e.g. 65535 for PostgreSQL (`nil` for no limit)
+  * `max_relationship_chain` - Maximum number of times one table may be read along a single path of selected relationships, bounding cycles such as `manager { manager { ... } }` (`nil` for no limit)
+  * `max_identifier_length` - Maximum length in bytes of a table, column or alias name in generated SQL, e.g. 63 for PostgreSQL, which silently truncates longer ones; generation fails past it (`nil` for no limit)
+  * `lazy_resolution` - Leave the resolution request out of parse results for cached queries; fetch it with `GraSQL.Native.resolution_request/1` (default: false)
+  * `sql_dialect` - Database to generate SQL for, `:postgres` or `:mysql` (default: `:postgres`)
+  * `sql_keyword_case` - Case of the keywords in generated SQL, `:upper` (`SELECT`) or `:lower` (`select`) (default: `:upper`)
+  * `null_ordering` - Where NULLs sort for `order_by` directions `asc` and `desc`: `:db_default` (no `NULLS` clause), `:nulls_first` or `:nulls_last`; directions like `asc_nulls_first` keep their own placement (default: `:db_default`)
+  * `quote_all_identifiers` - Quote every identifier in generated SQL; when false, only reserved words and names that are not plain lowercase identifiers are quoted (default: true)
 
   ### Schema resolution
   * `schema_resolver` - Module that implements the SchemaResolver behavior
@@ -122,12 +176,29 @@ defmodule GraSQL.Config do
           # Naming conventions
           aggregate_field_suffix: String.t(),
           primary_key_argument_name: String.t(),
+          by_pk_suffix: String.t(),
           aggregate_nodes_field_name: String.t(),
+          scalar_fields: [String.t()],
+          function_fields: [String.t()],
+          function_args_argument: String.t(),
+          field_directives: [String.t()],
+          relationship_fields: [String.t()],
+          require_relationship_selection: boolean(),
+          collect_argument_names: boolean(),
+          expand_variable_filters: boolean(),
+
+          # Relay connection conventions
+          edges_field: String.t(),
+          node_field: String.t(),
+          page_info_field: String.t(),
 
           # Mutation naming conventions
           insert_prefix: String.t(),
           update_prefix: String.t(),
           delete_prefix: String.t(),
+          validate_mutation_shape: boolean(),
+          allow_empty_insert: boolean(),
+          mutation_returning_cte: boolean(),
 
           # Operator mappings
           operators: %{operator => String.t()},
@@ -138,8 +209,23 @@ defmodule GraSQL.Config do
 
           # Performance settings
           max_query_depth: pos_integer(),
+          max_columns_per_table: pos_integer() | nil,
+          max_new_symbols_per_query: pos_integer() | nil,
+          max_operations: pos_integer() | nil,
+          subscription_mode: :reject | :as_query,
           string_interner_capacity: pos_integer(),
 
+          # SQL generation
+          always_include_pk: boolean(),
+          max_bind_params: pos_integer() | nil,
+          max_relationship_chain: pos_integer() | nil,
+          max_identifier_length: pos_integer() | nil,
+          lazy_resolution: boolean(),
+          sql_dialect: :postgres | :mysql,
+          sql_keyword_case: :upper | :lower,
+          null_ordering: :db_default | :nulls_first | :nulls_last,
+          quote_all_identifiers: boolean(),
+
           # Schema resolver
           schema_resolver: module() | nil
         }
@@ -151,12 +237,29 @@ defmodule GraSQL.Config do
     # Naming conventions
     aggregate_field_suffix: "_agg",
     primary_key_argument_name: "id",
+    by_pk_suffix: "_by_pk",
     aggregate_nodes_field_name: "nodes",
+    scalar_fields: [],
+    function_fields: [],
+    function_args_argument: "args",
+    field_directives: [],
+    relationship_fields: [],
+    require_relationship_selection: false,
+    collect_argument_names: false,
+    expand_variable_filters: false,
+
+    # Relay connection conventions
+    edges_field: "edges",
+    node_field: "node",
+    page_info_field: "pageInfo",
 
     # Mutation naming conventions
     insert_prefix: "insert_",
     update_prefix: "update_",
     delete_prefix: "delete_",
+    validate_mutation_shape: false,
+    allow_empty_insert: false,
+    mutation_returning_cte: false,
 
     # Operator mappings - using standard GraphQL operator syntax
     operators: %{
@@ -182,6 +285,9 @@ defmodule GraSQL.Config do
       nin: "_nin",
       is_null: "_is_null",
 
+      # Type casts
+      cast: "_cast",
+
       # JSON operators
       json_contains: "_json_contains",
       json_contained_in: "_json_contained_in",
@@ -190,7 +296,14 @@ defmodule GraSQL.Config do
       json_has_all_keys: "_json_has_all_keys",
       json_path: "_json_path",
       json_path_text: "_json_path_text",
-      is_json: "_is_json"
+      is_json: "_is_json",
+
+      # Alternative JSON operator names
+      contains: "_contains",
+      contained_in: "_contained_in",
+      has_key: "_has_key",
+      has_keys_any: "_has_keys_any",
+      has_keys_all: "_has_keys_all"
     },
 
     # Cache settings
@@ -199,8 +312,23 @@ defmodule GraSQL.Config do
 
     # Performance settings
     max_query_depth: 10,
+    max_columns_per_table: nil,
+    max_new_symbols_per_query: nil,
+    max_operations: nil,
+    subscription_mode: :reject,
     string_interner_capacity: 10_000,
 
+    # SQL generation
+    always_include_pk: false,
+    max_bind_params: nil,
+    max_relationship_chain: nil,
+    max_identifier_length: nil,
+    lazy_resolution: false,
+    sql_dialect: :postgres,
+    sql_keyword_case: :upper,
+    null_ordering: :db_default,
+    quote_all_identifiers: true,
+
     # Schema resolver
     schema_resolver: nil
   ]
@@ -239,6 +367,7 @@ defmodule GraSQL.Config do
       &validate_operators/1,
       &validate_cache_settings/1,
       &validate_performance_settings/1,
+      &validate_sql_generation/1,
       &validate_schema_resolver/1
     ]
 
@@ -328,15 +457,43 @@ defmodule GraSQL.Config do
     |> Map.take([
       :aggregate_field_suffix,
       :primary_key_argument_name,
+      :by_pk_suffix,
       :aggregate_nodes_field_name,
+      :scalar_fields,
+      :function_fields,
+      :function_args_argument,
+      :field_directives,
+      :relationship_fields,
+      :require_relationship_selection,
+      :collect_argument_names,
+      :expand_variable_filters,
+      :edges_field,
+      :node_field,
+      :page_info_field,
       :insert_prefix,
       :update_prefix,
       :delete_prefix,
+      :validate_mutation_shape,
+      :allow_empty_insert,
+      :mutation_returning_cte,
       :operators,
       :query_cache_max_size,
       :query_cache_ttl_seconds,
       :max_query_depth,
-      :string_interner_capacity
+      :max_columns_per_table,
+      :max_new_symbols_per_query,
+      :max_operations,
+      :subscription_mode,
+      :string_interner_capacity,
+      :always_include_pk,
+      :max_bind_params,
+      :max_relationship_chain,
+      :max_identifier_length,
+      :lazy_resolution,
+      :sql_dialect,
+      :sql_keyword_case,
+      :null_ordering,
+      :quote_all_identifiers
     ])
     |> Map.put(:operators, string_operators)
   end
@@ -502,10 +659,29 @@ defmodule GraSQL.Config do
   defp validate_naming_conventions(config) do
     if is_binary(config.aggregate_field_suffix) and
          is_binary(config.primary_key_argument_name) and
+         is_binary(config.by_pk_suffix) and
          is_binary(config.aggregate_nodes_field_name) and
+         is_list(config.scalar_fields) and
+         Enum.all?(config.scalar_fields, &is_binary/1) and
+         is_list(config.function_fields) and
+         Enum.all?(config.function_fields, &is_binary/1) and
+         is_binary(config.function_args_argument) and
+         is_list(config.field_directives) and
+         Enum.all?(config.field_directives, &is_binary/1) and
+         is_list(config.relationship_fields) and
+         Enum.all?(config.relationship_fields, &is_binary/1) and
+         is_boolean(config.require_relationship_selection) and
+         is_boolean(config.collect_argument_names) and
+         is_boolean(config.expand_variable_filters) and
+         is_binary(config.edges_field) and
+         is_binary(config.node_field) and
+         is_binary(config.page_info_field) and
          is_binary(config.insert_prefix) and
          is_binary(config.update_prefix) and
-         is_binary(config.delete_prefix) do
+         is_binary(config.delete_prefix) and
+         is_boolean(config.validate_mutation_shape) and
+         is_boolean(config.allow_empty_insert) and
+         is_boolean(config.mutation_returning_cte) do
       :ok
     else
       {:error, "Naming convention fields must be strings"}
@@ -536,14 +712,59 @@ defmodule GraSQL.Config do
 
   @doc false
   defp validate_performance_settings(config) do
-    if is_integer(config.max_query_depth) and config.max_query_depth > 0 and
-         is_integer(config.string_interner_capacity) and config.string_interner_capacity > 0 do
-      :ok
-    else
-      {:error, "Performance settings must be positive integers"}
+    cond do
+      not (is_integer(config.max_query_depth) and config.max_query_depth > 0 and
+             is_integer(config.string_interner_capacity) and
+             config.string_interner_capacity > 0 and
+             optional_pos_integer?(config.max_columns_per_table) and
+             optional_pos_integer?(config.max_new_symbols_per_query) and
+             optional_pos_integer?(config.max_operations)) ->
+        {:error, "Performance settings must be positive integers"}
+
+      config.subscription_mode not in [:reject, :as_query] ->
+        {:error, "subscription_mode must be :reject or :as_query"}
+
+      true ->
+        :ok
     end
   end
 
+  @doc false
+  defp validate_sql_generation(config) do
+    cond do
+      not (is_boolean(config.always_include_pk) and is_boolean(config.lazy_resolution)) ->
+        {:error, "always_include_pk and lazy_resolution must be booleans"}
+
+      not optional_pos_integer?(config.max_bind_params) ->
+        {:error, "max_bind_params must be a positive integer or nil"}
+
+      not optional_pos_integer?(config.max_relationship_chain) ->
+        {:error, "max_relationship_chain must be a positive integer or nil"}
+
+      not optional_pos_integer?(config.max_identifier_length) ->
+        {:error, "max_identifier_length must be a positive integer or nil"}
+
+      config.sql_dialect not in [:postgres, :mysql] ->
+        {:error, "sql_dialect must be :postgres or :mysql"}
+
+      config.sql_keyword_case not in [:upper, :lower] ->
+        {:error, "sql_keyword_case must be :upper or :lower"}
+
+      config.null_ordering not in [:db_default, :nulls_first, :nulls_last] ->
+        {:error, "null_ordering must be :db_default, :nulls_first or :nulls_last"}
+
+      not is_boolean(config.quote_all_identifiers) ->
+        {:error, "quote_all_identifiers must be a boolean"}
+
+      true ->
+        :ok
+    end
+  end
+
+  @doc false
+  defp optional_pos_integer?(nil), do: true
+  defp optional_pos_integer?(value), do: is_integer(value) and value > 0
+
   @doc false
   defp validate_schema_resolver(config) do
     resolver = config.schema_resolver
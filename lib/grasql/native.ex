@@ -15,11 +15,260 @@ defmodule GraSQL.Native do
     crate: :grasql,
     load_data_fun: {GraSQL.Config, :load}
 
-  def parse_query(query), do: do_parse_query(query)
+  @doc """
+  Parses a query.
 
+  Pass `:insert_prefix`, `:update_prefix` or `:delete_prefix` to override the
+  configured mutation prefixes for this call only.
+
+  Pass `:variables`, a map of the operation's variable values, to have filters
+  given as variables expanded when `expand_variable_filters` is enabled. The
+  values then become part of the query ID.
+
+  Empty queries, including ones with only whitespace or comments, return
+  `{:error, {:empty_query, message}}`.
+
+  Queries that need no SQL, such as `{ __typename }` or other introspection-only
+  documents, return `{:ok, :no_sql, query_id, operation_kind, operation_name}`
+  instead of a resolution request, so they can be answered without a database
+  round-trip.
+
+  With `lazy_resolution` enabled, a query that is already cached returns
+  `{:ok, :cached, query_id, operation_kind, operation_name}` without its
+  resolution request. Use `resolution_request/1` if it is needed after all.
+
+  Besides the paths to resolve, the resolution request ends with
+  `:warnings`, the non-fatal issues met while parsing (such as an unknown
+  filter operator), and `:argument_names`, the sorted names of every
+  argument the query passes when `collect_argument_names` is enabled, so the
+  schema layer can warn about arguments it doesn't declare.
+  """
+  def parse_query(query, opts \\ []) do
+    variables =
+      case Keyword.get(opts, :variables) do
+        nil -> nil
+        variables -> Jason.encode!(variables)
+      end
+
+    query
+    |> do_parse_query(prefixes(opts), variables)
+    |> decode_parse_result()
+  end
+
+  @doc """
+  Checks a query against the configured limits without parsing it for SQL.
+
+  Returns `{:ok, report}`, or the `{:error, reason}` that `parse_query/2` would
+  return. The report is a map with `:operation_kind`, `:operations`, `:depth`,
+  `:complexity` (the number of selected fields) and `:operators` (the filter
+  operators used). Nothing is cached, which makes this cheaper than a full
+  parse for admission control.
+  """
+  def validate_query(query), do: do_validate_query(query)
+
+  @doc """
+  Parses a batch of queries.
+
+  By default every query gets its own `{:ok, ...}` or `{:error, reason}` result,
+  the same one `parse_query/2` returns for it. Pass `fail_fast: true` to abort
+  the whole batch on the first invalid query. The `:insert_prefix`,
+  `:update_prefix` and `:delete_prefix` options apply to every query in the
+  batch; `:variables` is not supported, so filters passed as variables are not
+  expanded.
+  """
+  def parse_queries(queries, opts \\ []) do
+    case do_parse_queries(queries, Keyword.get(opts, :fail_fast, false), prefixes(opts)) do
+      results when is_list(results) -> Enum.map(results, &decode_parse_result/1)
+      error -> error
+    end
+  end
+
+  @doc """
+  Returns the resolution request of a cached query.
+
+  Returns `{:error, :cache_miss}` when the query is no longer cached; parse it
+  again in that case.
+  """
+  def resolution_request(query_id), do: do_resolution_request(query_id)
+
+  @doc """
+  Parses a persisted query, sent by its query ID alone.
+
+  Returns the same result as `parse_query/2` for the query cached under
+  `query_id`. Returns `{:error, :persisted_query_not_found}` when it is no
+  longer cached; ask the client for the full query text in that case.
+  """
+  def parse_persisted(query_id), do: do_parse_persisted(query_id)
+
+  @doc """
+  Returns a read-only outline of a query for editor tooling.
+
+  The result is `{:ok, operations}`, where each operation is a map with `:kind`
+  (`:query`, `:mutation` or `:subscription`), `:name` and `:fields`. Each field
+  is a map with `:name`, `:alias`, `:arguments` (argument names) and `:children`.
+  Unlike `parse_query/2`, nothing is cached and unsupported features such as
+  fragments are tolerated.
+  """
+  def query_outline(query), do: do_query_outline(query)
+
+  @doc """
+  Returns the insert tree of a parsed query, for planning nested inserts.
+
+  The result is `{:ok, nodes}` with one node per insert root field. Each node is
+  a map with `:path`, the field names from the root field down to the table,
+  and `:children`, the nodes of the inserts nested in its rows
+  (`posts: %{data: [...]}`). All of them belong in one transaction. Returns
+  `{:error, :cache_miss}` when the query is no longer cached.
+  """
+  def insert_tree(query_id), do: do_insert_tree(query_id)
+
+  @doc """
+  Returns the query text a cached query was parsed from, for logging and
+  debugging.
+
+  The result is `{:ok, query}` with the text as submitted, which recovers the
+  full query behind a persisted query ID. Returns `{:error, :cache_miss}` when
+  the query is no longer cached.
+  """
+  def original_query(query_id), do: do_original_query(query_id)
+
+  @doc """
+  Generates SQL for a resolved query.
+
+  Returns `{:ok, [{name, sql, params, kind}]}` with one statement per root
+  field. Each parameter is `{0, value}` for a literal or `{1, variable_name}`
+  for a GraphQL variable. `kind` is `{:read, :query}` for statements that only
+  read, and `{:write, :insert | :update | :delete}` for the others, so writes
+  can be routed to the primary and reads to replicas.
+  """
   def generate_sql(resolution_response), do: do_generate_sql(resolution_response)
 
-  def do_parse_query(_query), do: :erlang.nif_error(:nif_not_loaded)
+  @doc """
+  Generates SQL like `generate_sql/1`, as named statements to prepare once and
+  execute many times.
+
+  Returns `{:ok, [{name, statement_name, sql, params, param_types, kind}]}`. The
+  `statement_name` is the same for queries that differ only in their literal
+  values, `param_types` lists the SQL type of each parameter, or `nil` where the
+  database has to infer it, and `kind` is the routing classification described
+  in `generate_sql/1`.
+  """
+  def generate_prepared_sql(resolution_response),
+    do: do_generate_prepared_sql(resolution_response)
+
+  @doc """
+  Stores the resolution response of a parsed query for later SQL generation.
+
+  Afterwards `generate_sql/1` and `generate_prepared_sql/1` accept `query_id`
+  in place of the resolution response, so a repeated query needs no schema
+  resolution. The stored schema is evicted along with the query's cache entry;
+  generating from a `query_id` whose entry is gone returns
+  `{:error, :cache_miss}`, and one without a stored schema returns
+  `{:error, :schema_not_cached}`.
+  """
+  def cache_schema(query_id, resolution_response),
+    do: do_cache_schema(query_id, resolution_response)
+
+  @doc """
+  Generates SQL like `generate_sql/1` together with a description of its plan.
+
+  Meant for displaying or analyzing a query plan without running it. Returns
+  `{:ok, [{name, sql, plan}]}`; no parameters are bound, so the SQL and plan
+  keep their placeholders. Each plan is a map with the `:name` of the field, the
+  `:table` it reads, its `:join` to the parent (`nil` for the root field, or
+  `:belongs_to`, `:has_one`, `:has_many` or `:many_to_many`), its `:filter`,
+  `:order_by`, `:limit` and `:offset`, and the `:children` plans of the
+  relationships selected below it.
+  """
+  def explain_query(query_id, resolution_response),
+    do: do_explain_query(query_id, resolution_response)
+
+  def cache_memory_estimate, do: do_cache_memory_estimate()
+
+  @doc """
+  Returns the native library version and build information.
+
+  The map contains the crate `:version`, the `:query_id_version` of the query ID
+  scheme, the `:path_format_version` of resolution request paths and the list
+  of optional cargo `:features` compiled into the NIF.
+  """
+  def version, do: do_version()
+
+  @doc """
+  Returns the configuration the NIF is running with, or `nil` before initialization.
+
+  The map contains the naming settings (`:aggregate_field_suffix`,
+  `:primary_key_argument_name`, `:by_pk_suffix`, `:aggregate_nodes_field_name`),
+  the mutation prefixes (`:insert_prefix`, `:update_prefix`, `:delete_prefix`)
+  and the limits (`:max_query_depth`, `:query_cache_max_size`,
+  `:query_cache_ttl_seconds`, `:string_interner_capacity`). Useful for checking
+  why a mutation is not classified as expected.
+  """
+  def current_config, do: do_current_config()
+
+  @doc """
+  Returns the most recent parse errors as `{query_prefix, reason}` tuples, oldest first.
+
+  Only the last few errors are kept, and the list is always empty when the NIF
+  is built without the `recent-errors` feature.
+  """
+  def recent_errors, do: do_recent_errors()
+
+  @doc """
+  Returns every interned string, in symbol key order.
+
+  A string's position in the list is the index resolution requests use for it,
+  which makes this useful for debugging symbol mapping issues.
+  """
+  def all_strings, do: do_all_strings()
+
+  # Cache hits carry their resolution request as an encoded term, which is
+  # cheaper to decode here than to rebuild on the Rust side
+  defp decode_parse_result({:ok, query_id, kind, name, request})
+       when is_binary(query_id) and is_binary(request),
+       do: {:ok, query_id, kind, name, :erlang.binary_to_term(request)}
+
+  defp decode_parse_result(result), do: result
+
+  # Per-call mutation prefix overrides, or nil for the configured ones
+  defp prefixes(opts) do
+    case Map.new(Keyword.take(opts, [:insert_prefix, :update_prefix, :delete_prefix])) do
+      prefixes when map_size(prefixes) == 0 -> nil
+      prefixes -> prefixes
+    end
+  end
+
+  def do_parse_query(_query, _prefixes, _variables), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_parse_queries(_queries, _fail_fast, _prefixes), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_validate_query(_query), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_resolution_request(_query_id), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_parse_persisted(_query_id), do: :erlang.nif_error(:nif_not_loaded)
 
   def do_generate_sql(_resolution_response), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_generate_prepared_sql(_resolution_response), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_cache_schema(_query_id, _resolution_response), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_explain_query(_query_id, _resolution_response), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_query_outline(_query), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_insert_tree(_query_id), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_original_query(_query_id), do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_cache_memory_estimate, do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_version, do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_current_config, do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_recent_errors, do: :erlang.nif_error(:nif_not_loaded)
+
+  def do_all_strings, do: :erlang.nif_error(:nif_not_loaded)
 end
@@ -32,14 +32,17 @@ defmodule GraSQL.Schema do
     * `schema` - Database schema name (e.g., "public")
     * `name` - Table name in the database
     * `__typename` - Optional GraphQL __typename value for the table
+    * `primary_key` - Optional primary key column names, selected automatically
+      when `always_include_pk` is enabled
     """
     @type t :: %__MODULE__{
             schema: String.t(),
             name: String.t(),
-            __typename: String.t() | nil
+            __typename: String.t() | nil,
+            primary_key: list(String.t()) | nil
           }
 
-    defstruct [:schema, :name, :__typename]
+    defstruct [:schema, :name, :__typename, :primary_key]
   end
 
   defmodule JoinTable do
@@ -162,11 +165,11 @@ defmodule GraSQL.Schema do
     resolver = get_cached_resolver()
 
     # Extract resolution information
-    {query_id, strings, paths, path_dir, path_types, column_map, operations} =
+    {query_id, strings, paths, path_dir, path_types, entities, column_map, operations} =
       extract_resolution_info(resolution_request)
 
     # Process paths to extract tables and relationships that need resolution
-    tables_to_resolve = extract_tables_to_resolve(strings, paths, path_dir, path_types)
+    tables_to_resolve = extract_tables_to_resolve(strings, path_types, entities)
     relations_to_resolve = extract_relationships_to_resolve(strings, paths, path_dir, path_types)
 
     # Determine optimal concurrency based on available schedulers and workload
@@ -246,15 +249,21 @@ defmodule GraSQL.Schema do
     )
   end
 
+  # Version of the `paths`/`path_dir`/`path_types` encoding this module reads;
+  # must match `encoding::PATH_FORMAT_VERSION` in the native library
+  @path_format_version 1
+
   # Extract resolution information from the request
   @spec extract_resolution_info(tuple()) ::
           {String.t(), list(String.t()), list(integer()), list(integer()), list(integer()),
-           list(tuple()), list(tuple())}
+           list(integer()), list(tuple()), list(tuple())}
   defp extract_resolution_info(resolution_request) do
     # Pattern match the resolution request tuple to extract all required information
     {
       :query_id,
       query_id,
+      :version,
+      version,
       :strings,
       strings,
       :paths,
@@ -263,29 +272,42 @@ defmodule GraSQL.Schema do
       path_dir,
       :path_types,
       path_types,
+      :entities,
+      entities,
       :cols,
       column_map,
       :ops,
-      operations
+      operations,
+      :directives,
+      _directives,
+      :warnings,
+      _warnings,
+      :argument_names,
+      _argument_names
     } = resolution_request
 
-    {query_id, strings, paths, path_dir, path_types, column_map, operations}
+    if version != @path_format_version do
+      raise ArgumentError,
+            "unsupported path format version #{inspect(version)} " <>
+              "(expected #{@path_format_version})"
+    end
+
+    {query_id, strings, paths, path_dir, path_types, entities, column_map, operations}
   end
 
   # Extract tables that need resolution
   @spec extract_tables_to_resolve(
           list(String.t()),
           list(integer()),
-          list(integer()),
           list(integer())
         ) :: list({integer(), String.t()})
-  defp extract_tables_to_resolve(strings, paths, path_dir, path_types) do
+  defp extract_tables_to_resolve(strings, path_types, entities) do
     # Iterate through path_types to find table paths (type 0)
     Enum.with_index(path_types)
     |> Enum.filter(fn {type, _} -> type == 0 end)
     |> Enum.map(fn {_, path_id} ->
-      # Get the table name from the path
-      table_idx = get_path_element(paths, path_dir, path_id, 0)
+      # Resolve the base entity, so `users_by_pk` and `insert_users` both map to `users`
+      table_idx = Enum.at(entities, path_id)
       table_name = Enum.at(strings, table_idx)
 
       {path_id, table_name}
@@ -588,6 +610,9 @@ defmodule GraSQL.Schema do
     # Convert columns to indexed format
     columns_indexed = build_columns_indexed(columns, tables, string_mapping)
 
+    # Primary keys known to the resolver
+    primary_keys_indexed = build_primary_keys_indexed(tables, string_mapping)
+
     # Return the complete response
     %{
       query_id: query_id,
@@ -597,6 +622,7 @@ defmodule GraSQL.Schema do
       joins: joins_indexed,
       path_map: path_map,
       cols: columns_indexed,
+      pks: primary_keys_indexed,
       # Include operations in the response
       ops: operations
     }
@@ -614,7 +640,8 @@ defmodule GraSQL.Schema do
       |> Map.values()
       |> Enum.flat_map(fn table ->
         [table.schema, table.name] ++
-          if table.__typename, do: [table.__typename], else: []
+          if(table.__typename, do: [table.__typename], else: []) ++
+          (table.primary_key || [])
       end)
 
     # Add strings from relationships and join tables
@@ -662,6 +689,18 @@ defmodule GraSQL.Schema do
     |> Enum.into(%{})
   end
 
+  # Convert primary keys to indexed format, using the same table order as build_tables_indexed
+  @spec build_primary_keys_indexed(map(), map()) :: list({integer(), list(integer())})
+  defp build_primary_keys_indexed(tables, string_mapping) do
+    tables
+    |> Enum.sort_by(fn {path_id, _} -> path_id end)
+    |> Enum.with_index()
+    |> Enum.filter(fn {{_, table}, _} -> table.primary_key not in [nil, []] end)
+    |> Enum.map(fn {{_, table}, table_idx} ->
+      {table_idx, Enum.map(table.primary_key, &Map.get(string_mapping, &1))}
+    end)
+  end
+
   # Convert tables to indexed format
   @spec build_tables_indexed(map(), map()) :: list(tuple())
   defp build_tables_indexed(tables, string_mapping) do
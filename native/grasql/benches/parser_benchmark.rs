@@ -1,7 +1,9 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use graphql_query::ast::{ASTContext, Document, ParseNode};
+use grasql::etf::{decode_request, encode_request};
 use grasql::extraction::FieldPathExtractor;
-use grasql::parser::parse_graphql;
+use grasql::parser::{parse_graphql, parse_graphql_with_config};
+use grasql::Config;
 
 // Sample queries for benchmarking
 const SIMPLE_QUERY: &str = "{ users { id name } }";
@@ -399,7 +401,44 @@ fn bench_field_extraction(c: &mut Criterion) {
 
             b.iter(|| {
                 let mut extractor = FieldPathExtractor::new();
-                let _ = extractor.extract(black_box(&document)).unwrap();
+                let _ = extractor.extract(black_box(document)).unwrap();
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Build a query filtering on a boolean expression nested `depth` levels deep,
+/// alternating `_and` and `_or`, with the same relationship compared at every level
+fn nested_boolean_filter_query(depth: usize) -> String {
+    let mut filter = String::from("{ category: { slug: { _eq: \"news\" } } }");
+    for level in 0..depth {
+        let operator = if level % 2 == 0 { "_and" } else { "_or" };
+        filter = format!(
+            "{{ {}: [{{ author: {{ name: {{ _eq: \"level{}\" }} }} }}, {}] }}",
+            operator, level, filter
+        );
+    }
+    format!("{{ posts(where: {}) {{ id }} }}", filter)
+}
+
+// Benchmark field path extraction over deeply nested boolean filters
+fn bench_nested_boolean_filter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested_boolean_filter");
+    let config = Config::default();
+
+    for depth in [4, 8, 16, 32] {
+        let query = nested_boolean_filter_query(depth);
+        group.bench_with_input(BenchmarkId::new("extract", depth), &query, |b, q| {
+            let ctx = ASTContext::new();
+            let document = Document::parse(&ctx, q.as_str()).unwrap();
+
+            b.iter(|| {
+                let mut extractor = FieldPathExtractor::new();
+                let _ = extractor
+                    .extract_with_config(black_box(document), &config)
+                    .unwrap();
             });
         });
     }
@@ -409,6 +448,9 @@ fn bench_field_extraction(c: &mut Criterion) {
 
 // New benchmark for the full parse_graphql function
 fn bench_parse_graphql(c: &mut Criterion) {
+    // parse_graphql reads the global configuration
+    let _ = grasql::types::initialize_for_test();
+
     let mut group = c.benchmark_group("parse_graphql");
 
     // Benchmark all query types
@@ -435,11 +477,31 @@ fn bench_parse_graphql(c: &mut Criterion) {
     group.finish();
 }
 
+// Benchmark the encoded request term that cache hits return in place of a
+// freshly built one: encoding is paid once per cache insert, decoding mirrors
+// the work `binary_to_term` does on the Elixir side
+fn bench_request_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("request_encoding");
+    let (_, request) = parse_graphql_with_config(COMPLEX_QUERY, &Config::default()).unwrap();
+    let encoded = encode_request(&request);
+
+    group.bench_function("encode/complex_query", |b| {
+        b.iter(|| encode_request(black_box(&request)));
+    });
+    group.bench_function("decode/complex_query", |b| {
+        b.iter(|| decode_request(black_box(&encoded)).unwrap());
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_direct_ast_parse,
     bench_query_hashing,
     bench_field_extraction,
-    bench_parse_graphql
+    bench_nested_boolean_filter,
+    bench_parse_graphql,
+    bench_request_encoding
 );
 criterion_main!(benches);
@@ -13,6 +13,13 @@ rustler::atoms! {
     // Error types
     syntax_error,
     cache_miss,
+    schema_not_cached,
+    persisted_query_not_found,
+    empty_query,
+
+    // Parse result markers
+    no_sql,
+    cached,
 
     // Operation kinds
     query,
@@ -20,6 +27,7 @@ rustler::atoms! {
     update_mutation,
     delete_mutation,
     subscription,
+    introspection,
 
     // Resolution request keys
     strings,
@@ -29,13 +37,67 @@ rustler::atoms! {
     query_id,
     path_dir,
     path_types,
+    entities,
     ops,
+    directives,
+    warnings,
+    argument_names,
 
     // Resolution response keys
     tables,
     rels,
     joins,
     path_map,
+    pks,
+
+    // Query outline keys
+    mutation,
+    kind,
+    name,
+    fields,
+    alias,
+    arguments,
+    children,
+
+    // Insert tree keys
+    path,
+
+    // Statement routing
+    read,
+    write,
+
+    // Query plan keys
+    table,
+    join,
+    filter,
+    order_by,
+    limit,
+    offset,
+
+    // Version info keys
+    version,
+    query_id_version,
+    path_format_version,
+    features,
+
+    // Admission report keys
+    operations,
+    depth,
+    complexity,
+    operators,
+
+    // Active config keys
+    aggregate_field_suffix,
+    primary_key_argument_name,
+    by_pk_suffix,
+    aggregate_nodes_field_name,
+    insert_prefix,
+    update_prefix,
+    delete_prefix,
+    max_query_depth,
+    query_cache_max_size,
+    query_cache_ttl_seconds,
+    string_interner_capacity,
 }
 
 /// Convert GraphQLOperationKind to Erlang atom
@@ -47,5 +109,6 @@ pub fn operation_kind_to_atom(kind: GraphQLOperationKind) -> Atom {
         GraphQLOperationKind::UpdateMutation => update_mutation(),
         GraphQLOperationKind::DeleteMutation => delete_mutation(),
         GraphQLOperationKind::Subscription => subscription(),
+        GraphQLOperationKind::Introspection => introspection(),
     }
 }
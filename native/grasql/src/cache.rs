@@ -1,7 +1,15 @@
-use crate::config::CONFIG;
-use crate::types::{CachedQueryInfo, ParsedQueryInfo, ResolutionRequest};
+use crate::config::{MutationPrefixes, CONFIG};
+use crate::etf::encode_request;
+use crate::interning::hold_symbols;
+use crate::types::{
+    ArgValue, CachedQueryInfo, DirectiveEntry, FieldPath, InsertNode, ParsedQueryInfo,
+    ResolutionRequest, ResolutionResponse, SymbolId,
+};
 use moka::sync::Cache;
 use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+use std::sync::Arc;
 use std::time::Duration;
 use xxhash_rust::xxh3::xxh3_64;
 
@@ -26,6 +34,8 @@ fn create_cache_from_config() -> Cache<String, CachedQueryInfo> {
     Cache::builder()
         .max_capacity(max_size)
         .time_to_live(Duration::from_secs(ttl))
+        // A replaced or evicted query takes its stored schema with it
+        .eviction_listener(|query_id: Arc<String>, _, _| SCHEMA_CACHE.invalidate(query_id.as_str()))
         .build()
 }
 
@@ -48,8 +58,28 @@ fn create_cache_from_config() -> Cache<String, CachedQueryInfo> {
 ///
 /// This cache is optimized for high-throughput environments and is a critical
 /// component for achieving 100K+ QPS performance targets.
-pub static QUERY_CACHE: Lazy<Cache<String, CachedQueryInfo>> =
-    Lazy::new(|| create_cache_from_config());
+pub static QUERY_CACHE: Lazy<Cache<String, CachedQueryInfo>> = Lazy::new(create_cache_from_config);
+
+/// Resolved schemas stored by `cache_resolved_schema`, keyed by query ID
+///
+/// Kept apart from `QUERY_CACHE` so storing a schema neither rewrites the
+/// query's entry nor restarts its time to live. Entries are removed when the
+/// query's entry is replaced or evicted, so the cache never holds more
+/// schemas than there are cached queries.
+static SCHEMA_CACHE: Lazy<Cache<String, Arc<ResolutionResponse>>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(QUERY_CACHE.policy().max_capacity().unwrap_or(1000))
+        .build()
+});
+
+/// Version of the query ID scheme
+///
+/// Bumped whenever `generate_query_id` changes the ID it produces for an
+/// existing query, so IDs persisted by one build can be checked against another.
+///
+/// 1. The xxh3 hash of the query text as given
+/// 2. The xxh3 hash of the query text after `normalize_query`
+pub const QUERY_ID_VERSION: u32 = 2;
 
 /// Converts query string to a unique query ID using xxHash algorithm
 ///
@@ -63,12 +93,183 @@ pub static QUERY_CACHE: Lazy<Cache<String, CachedQueryInfo>> =
 /// - Much faster than cryptographic hashes (SHA, MD5)
 /// - Better distribution than simple hashing algorithms
 /// - Very low collision rate for GraphQL queries
+///
+/// Queries are normalized with `normalize_query` before hashing, so queries
+/// that differ only in insignificant whitespace or comments share an ID.
+/// Clients commonly send one query in several formattings (pretty-printed by
+/// tooling, minified by bundlers), and without normalization each would be
+/// parsed and cached separately. Normalizing is a single pass over the text,
+/// far cheaper than the parse a cache miss costs.
 #[inline(always)]
 pub fn generate_query_id(query: &str) -> String {
-    let hash = xxh3_64(query.as_bytes());
+    let hash = xxh3_64(normalize_query(query).as_bytes());
     format!("{:x}", hash)
 }
 
+/// Generate a query ID that also reflects per-call mutation prefix overrides
+///
+/// The same query text classifies differently under different prefixes, so
+/// each override gets its own cache entry. Without an override this is the
+/// same as `generate_query_id`.
+pub fn generate_query_id_with_prefixes(query: &str, prefixes: Option<&MutationPrefixes>) -> String {
+    format!("{:x}", xxh3_64(prefixed_key(query, prefixes).as_bytes()))
+}
+
+/// Generate a query ID that also reflects the variable values a parse used
+///
+/// Expanding variable filters makes the extracted paths depend on the
+/// variable values, so each distinct set of values gets its own cache entry.
+pub fn generate_query_id_with_variables(
+    query: &str,
+    variables: &serde_json::Map<String, serde_json::Value>,
+) -> String {
+    generate_query_id_with_overrides(query, None, Some(variables))
+}
+
+/// Generate a query ID reflecting both a prefix override and variable values
+///
+/// Combines `generate_query_id_with_prefixes` and
+/// `generate_query_id_with_variables` for a parse that may use either or both.
+pub fn generate_query_id_with_overrides(
+    query: &str,
+    prefixes: Option<&MutationPrefixes>,
+    variables: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> String {
+    let variables = match variables {
+        Some(variables) => variables,
+        None => return generate_query_id_with_prefixes(query, prefixes),
+    };
+
+    let mut key = prefixed_key(query, prefixes);
+    // NUL cannot appear in GraphQL source text, so it safely separates the parts
+    key.push('\0');
+    key.push_str(&serde_json::Value::Object(variables.clone()).to_string());
+
+    format!("{:x}", xxh3_64(key.as_bytes()))
+}
+
+/// Normalized query text followed by any prefix override, before hashing
+fn prefixed_key(query: &str, prefixes: Option<&MutationPrefixes>) -> String {
+    let mut key = normalize_query(query);
+    let prefixes = match prefixes {
+        Some(prefixes) if *prefixes != MutationPrefixes::default() => prefixes,
+        _ => return key,
+    };
+
+    for prefix in [
+        &prefixes.insert_prefix,
+        &prefixes.update_prefix,
+        &prefixes.delete_prefix,
+    ] {
+        // NUL cannot appear in a GraphQL name, so it safely separates the parts
+        key.push('\0');
+        match prefix {
+            Some(prefix) => {
+                key.push('=');
+                key.push_str(prefix);
+            }
+            None => key.push('-'),
+        }
+    }
+
+    key
+}
+
+/// Unicode byte order mark, sometimes left at the start of pasted queries
+pub const BYTE_ORDER_MARK: &str = "\u{FEFF}";
+
+/// Normalize a GraphQL query string for use as a cache key
+///
+/// Collapses every run of insignificant whitespace (including commas, which
+/// GraphQL treats as whitespace, and the byte order mark editors may prepend)
+/// into a single space, drops comments, and trims the ends of the query.
+///
+/// String literals are copied verbatim. This includes block strings
+/// (`"""..."""`), whose line breaks and indentation are part of the value,
+/// so two queries that differ only inside a literal never share a cache key.
+pub fn normalize_query(query: &str) -> String {
+    let bytes = query.as_bytes();
+    let mut normalized = String::with_capacity(query.len());
+    let mut pending_space = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\t' | b'\n' | b'\r' | b',' => {
+                pending_space = true;
+                i += 1;
+            }
+            // The UTF-8 encoding of U+FEFF, which GraphQL ignores like whitespace
+            0xEF if bytes[i..].starts_with(BYTE_ORDER_MARK.as_bytes()) => {
+                pending_space = true;
+                i += BYTE_ORDER_MARK.len();
+            }
+            b'#' => {
+                // Comments run to the end of the line and act as whitespace
+                while i < bytes.len() && bytes[i] != b'\n' && bytes[i] != b'\r' {
+                    i += 1;
+                }
+                pending_space = true;
+            }
+            b'"' => {
+                if pending_space && !normalized.is_empty() {
+                    normalized.push(' ');
+                }
+                pending_space = false;
+
+                let end = string_literal_end(bytes, i);
+                normalized.push_str(&query[i..end]);
+                i = end;
+            }
+            _ => {
+                if pending_space && !normalized.is_empty() {
+                    normalized.push(' ');
+                }
+                pending_space = false;
+
+                // Copy the whole UTF-8 character so multi-byte input stays intact
+                let ch_len = query[i..].chars().next().map_or(1, char::len_utf8);
+                normalized.push_str(&query[i..i + ch_len]);
+                i += ch_len;
+            }
+        }
+    }
+
+    normalized
+}
+
+/// Find the byte offset just past the string literal starting at `start`
+///
+/// Handles both regular strings (with backslash escapes) and block strings
+/// (with the escaped `\"""` sequence). Unterminated literals run to the end
+/// of the input; the parser reports those as syntax errors.
+#[inline(always)]
+fn string_literal_end(bytes: &[u8], start: usize) -> usize {
+    if bytes[start..].starts_with(b"\"\"\"") {
+        let mut i = start + 3;
+        while i < bytes.len() {
+            if bytes[i..].starts_with(b"\\\"\"\"") {
+                i += 4;
+            } else if bytes[i..].starts_with(b"\"\"\"") {
+                return i + 3;
+            } else {
+                i += 1;
+            }
+        }
+        bytes.len()
+    } else {
+        let mut i = start + 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'"' => return i + 1,
+                _ => i += 1,
+            }
+        }
+        bytes.len()
+    }
+}
+
 /// Add a parsed query to the cache
 ///
 /// This function converts the ParsedQueryInfo to a thread-safe CachedQueryInfo
@@ -81,6 +282,8 @@ pub fn generate_query_id(query: &str) -> String {
 /// while the AST context exists, which is guaranteed by the Arc wrapping the context.
 #[inline(always)]
 pub fn add_to_cache(query_id: &str, parsed_query_info: ParsedQueryInfo) {
+    let _symbols = hold_symbols();
+
     // Convert ParsedQueryInfo to CachedQueryInfo (thread-safe) version
     let cached_info: CachedQueryInfo = parsed_query_info.into();
     QUERY_CACHE.insert(query_id.to_string(), cached_info);
@@ -98,7 +301,104 @@ pub fn add_to_cache(query_id: &str, parsed_query_info: ParsedQueryInfo) {
 /// - None if the query is not in the cache or has expired
 #[inline(always)]
 pub fn get_from_cache(query_id: &str) -> Option<CachedQueryInfo> {
-    QUERY_CACHE.get(query_id).map(|val| val.clone())
+    QUERY_CACHE.get(query_id)
+}
+
+/// Get cached information for `query`, guarding against query ID collisions
+///
+/// Query IDs come from a non-cryptographic hash, so two different queries
+/// could share one. An entry whose original query differs from `query` once
+/// both are normalized is treated as a miss; parsing `query` again then
+/// replaces the entry. Entries without an original query are trusted.
+#[inline(always)]
+pub fn get_matching_from_cache(query_id: &str, query: &str) -> Option<CachedQueryInfo> {
+    let cached_query_info = QUERY_CACHE.get(query_id)?;
+    match &cached_query_info.original_query {
+        Some(original)
+            if original != query && normalize_query(original) != normalize_query(query) =>
+        {
+            None
+        }
+        _ => Some(cached_query_info),
+    }
+}
+
+/// Get the resolution request of a cached query
+///
+/// Returns None if the query is not cached or was cached without a request.
+#[inline(always)]
+pub fn get_resolution_request(query_id: &str) -> Option<ResolutionRequest> {
+    QUERY_CACHE.get(query_id)?.resolution_request
+}
+
+/// Store the resolved schema of a cached query alongside it
+///
+/// Later SQL generation for the query can then use the stored schema instead
+/// of having Elixir resolve and send it again. The schema is evicted
+/// together with the query's cache entry, whose time to live is left as is.
+/// Returns false if the query named by the response's query ID is not cached.
+pub fn cache_resolved_schema(response: ResolutionResponse) -> bool {
+    let query_id = response.query_id.clone();
+    if !QUERY_CACHE.contains_key(&query_id) {
+        return false;
+    }
+
+    SCHEMA_CACHE.insert(query_id.clone(), Arc::new(response));
+
+    // The query may have been evicted before the schema was stored, in which
+    // case its eviction found nothing to remove
+    if !QUERY_CACHE.contains_key(&query_id) {
+        SCHEMA_CACHE.invalidate(&query_id);
+        return false;
+    }
+    true
+}
+
+/// Get the resolved schema stored for a cached query
+///
+/// Returns None if the query is not cached or has no stored schema.
+#[inline(always)]
+pub fn get_resolved_schema(query_id: &str) -> Option<Arc<ResolutionResponse>> {
+    if !QUERY_CACHE.contains_key(query_id) {
+        return None;
+    }
+    SCHEMA_CACHE.get(query_id)
+}
+
+/// Get the query text a cached query was parsed from
+///
+/// Returns the text as it was submitted, before normalization, so logs can
+/// show the full query behind a query ID. None if the query is not cached.
+#[inline(always)]
+pub fn original_query(query_id: &str) -> Option<String> {
+    QUERY_CACHE.get(query_id)?.original_query
+}
+
+/// Symbol IDs held by the cached queries
+pub(crate) fn cached_symbols() -> HashSet<SymbolId> {
+    QUERY_CACHE.run_pending_tasks();
+    QUERY_CACHE
+        .iter()
+        .flat_map(|(_, info)| info.symbols())
+        .collect()
+}
+
+/// Rewrite the symbol IDs of every cached query through `remap`
+///
+/// `remap` must cover every ID `cached_symbols` returned. Entries are
+/// replaced, restarting their time to live, but keep their stored schemas,
+/// since the path indices a schema refers to don't change.
+pub(crate) fn remap_cached_symbols(remap: &HashMap<SymbolId, SymbolId>) {
+    let entries: Vec<_> = QUERY_CACHE.iter().collect();
+    for (query_id, mut info) in entries {
+        info.remap_symbols(&|id| remap[&id]);
+
+        let schema = SCHEMA_CACHE.get(query_id.as_str());
+        QUERY_CACHE.insert(query_id.to_string(), info);
+        if let Some(schema) = schema {
+            SCHEMA_CACHE.insert(query_id.to_string(), schema);
+        }
+    }
 }
 
 /// Insert a CachedQueryInfo directly into the cache - for testing only
@@ -116,6 +416,18 @@ pub fn insert_raw_for_test(query_id: &str, cached_info: CachedQueryInfo) {
     QUERY_CACHE.insert(query_id.to_string(), cached_info);
 }
 
+/// Clear every entry from the cache - for testing only
+///
+/// Tests share a single global cache, so tests that fill it past capacity use
+/// this to leave room for the tests that follow.
+#[cfg(any(test, feature = "test-utils"))]
+pub fn clear_cache_for_test() {
+    QUERY_CACHE.invalidate_all();
+    QUERY_CACHE.run_pending_tasks();
+    SCHEMA_CACHE.invalidate_all();
+    SCHEMA_CACHE.run_pending_tasks();
+}
+
 /// Add a parsed query to the cache with its resolution request
 ///
 /// This function converts the ParsedQueryInfo to a thread-safe CachedQueryInfo,
@@ -139,11 +451,263 @@ pub fn add_to_cache_with_request(
     parsed_query_info: ParsedQueryInfo,
     resolution_request: ResolutionRequest,
 ) {
+    let _symbols = hold_symbols();
+
     // Convert ParsedQueryInfo to CachedQueryInfo (thread-safe) version
     let mut cached_info: CachedQueryInfo = parsed_query_info.into();
 
-    // Store the ResolutionRequest in the cached info
+    // Store the ResolutionRequest in the cached info, along with its encoded
+    // term so cache hits don't rebuild it
+    cached_info.encoded_request = Some(Arc::new(encode_request(&resolution_request)));
     cached_info.resolution_request = Some(resolution_request);
 
     QUERY_CACHE.insert(query_id.to_string(), cached_info);
 }
+
+/// Approximate the number of bytes held by the query cache
+///
+/// Sums, for every live entry, the cache key, the original query text, the
+/// field paths and column usage tables, the cached ResolutionRequest and its
+/// encoded term, and the
+/// bytes allocated by the entry's AST arena, plus the stored resolved schemas.
+///
+/// # Accuracy
+///
+/// This is an estimate intended for monitoring, not an exact accounting.
+/// Hash table overhead and allocator slack are not included, and interned
+/// strings are shared globally so only their symbol IDs are counted here.
+pub fn memory_estimate() -> usize {
+    let queries: usize = QUERY_CACHE
+        .iter()
+        .map(|(key, info)| key.len() + estimate_entry_size(&info))
+        .sum();
+    let schemas: usize = SCHEMA_CACHE
+        .iter()
+        .map(|(key, schema)| key.len() + estimate_schema_size(&schema))
+        .sum();
+    queries + schemas
+}
+
+/// Approximate the number of bytes held by a single cache entry
+#[inline(always)]
+fn estimate_entry_size(info: &CachedQueryInfo) -> usize {
+    let mut total = size_of::<CachedQueryInfo>();
+
+    total += info.operation_name.as_ref().map_or(0, |name| name.len());
+    total += info.original_query.as_ref().map_or(0, |query| query.len());
+
+    if let Some(field_paths) = &info.field_paths {
+        total += field_paths.iter().map(estimate_path_size).sum::<usize>();
+    }
+
+    if let Some(path_index) = &info.path_index {
+        total += path_index
+            .keys()
+            .map(|path| estimate_path_size(path) + size_of::<usize>())
+            .sum::<usize>();
+    }
+
+    for column_usage in [&info.column_usage, &info.set_columns, &info.filter_columns]
+        .into_iter()
+        .flatten()
+    {
+        total += column_usage
+            .iter()
+            .map(|(path, columns)| estimate_path_size(path) + columns.len() * size_of::<SymbolId>())
+            .sum::<usize>();
+    }
+
+    if let Some(column_arguments) = &info.column_arguments {
+        total += column_arguments
+            .iter()
+            .map(|(path, columns)| {
+                estimate_path_size(path)
+                    + columns
+                        .iter()
+                        .map(|(key, (_, arguments))| {
+                            key.len()
+                                + size_of::<SymbolId>()
+                                + arguments
+                                    .iter()
+                                    .map(|(_, value)| size_of::<SymbolId>() + value.len())
+                                    .sum::<usize>()
+                        })
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+    }
+
+    if let Some(function_args) = &info.function_args {
+        total += function_args
+            .iter()
+            .map(|(path, arguments)| {
+                estimate_path_size(path)
+                    + arguments
+                        .iter()
+                        .map(|(_, value)| size_of::<SymbolId>() + value.len())
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+    }
+
+    if let Some(column_aliases) = &info.column_aliases {
+        total += column_aliases
+            .iter()
+            .map(|(path, columns)| {
+                estimate_path_size(path)
+                    + columns
+                        .values()
+                        .map(|keys| {
+                            size_of::<SymbolId>() + keys.iter().map(String::len).sum::<usize>()
+                        })
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+    }
+
+    if let Some(json_paths) = &info.json_paths {
+        total += json_paths
+            .iter()
+            .map(|(path, columns)| {
+                estimate_path_size(path)
+                    + columns
+                        .values()
+                        .flatten()
+                        .map(|keys| keys.iter().map(String::len).sum::<usize>())
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+    }
+
+    if let Some(args) = &info.args {
+        total += args
+            .iter()
+            .map(|(path, args)| {
+                estimate_path_size(path)
+                    + [&args.limit, &args.offset]
+                        .into_iter()
+                        .flatten()
+                        .map(|value| match value {
+                            ArgValue::Literal(_) => size_of::<i64>(),
+                            ArgValue::Variable(name) => name.len(),
+                        })
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+    }
+
+    if let Some(argument_names) = &info.argument_names {
+        total += argument_names.iter().map(String::len).sum::<usize>();
+    }
+
+    // The arena owns the parsed Document, which is usually the largest part of an entry
+    if let Some(ctx) = &info.ast_context {
+        total += ctx.arena.allocated_bytes();
+    }
+
+    if let Some(request) = &info.resolution_request {
+        total += request.query_id.len();
+        total += request.strings.iter().map(|s| s.len()).sum::<usize>();
+        total += (request.paths.len() + request.path_dir.len()) * size_of::<u32>();
+        total += request.path_types.len();
+        total += request.entities.len() * size_of::<u32>();
+        total += request
+            .cols
+            .iter()
+            .map(|(_, columns)| size_of::<u32>() + columns.len() * size_of::<u32>())
+            .sum::<usize>();
+        total += request.ops.len() * size_of::<(u32, u8)>();
+        total += request
+            .directives
+            .iter()
+            .map(|(_, _, _, arguments)| {
+                size_of::<DirectiveEntry>()
+                    + arguments
+                        .iter()
+                        .map(|(_, value)| size_of::<u32>() + value.len())
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+        total += request.warnings.iter().map(String::len).sum::<usize>();
+        total += request
+            .argument_names
+            .iter()
+            .map(String::len)
+            .sum::<usize>();
+    }
+
+    if let Some(encoded) = &info.encoded_request {
+        total += encoded.len();
+    }
+
+    if let Some(insert_tree) = &info.insert_tree {
+        total += insert_tree
+            .iter()
+            .map(estimate_insert_node_size)
+            .sum::<usize>();
+    }
+
+    total
+}
+
+/// Approximate the number of bytes held by an insert tree node and its children
+fn estimate_insert_node_size(node: &InsertNode) -> usize {
+    size_of::<InsertNode>()
+        + estimate_path_size(&node.path)
+        + node
+            .children
+            .iter()
+            .map(estimate_insert_node_size)
+            .sum::<usize>()
+}
+
+/// Approximate the number of bytes held by a stored resolved schema
+#[inline(always)]
+fn estimate_schema_size(schema: &ResolutionResponse) -> usize {
+    let index_list = |list: &Vec<u32>| size_of::<Vec<u32>>() + list.len() * size_of::<u32>();
+
+    let mut total = size_of::<ResolutionResponse>() + schema.query_id.len();
+    total += schema
+        .strings
+        .iter()
+        .map(|s| size_of::<String>() + s.len())
+        .sum::<usize>();
+    total += schema.tables.len() * size_of::<(u32, u32, u32)>();
+    total += schema
+        .rels
+        .iter()
+        .map(|(_, _, _, _, src_cols, tgt_cols)| {
+            size_of::<(u32, u32, u8, i32)>() + index_list(src_cols) + index_list(tgt_cols)
+        })
+        .sum::<usize>();
+    total += schema
+        .joins
+        .iter()
+        .map(|(_, _, src_cols, tgt_cols)| {
+            size_of::<(u32, u32)>() + index_list(src_cols) + index_list(tgt_cols)
+        })
+        .sum::<usize>();
+    total += schema.path_map.len() * size_of::<(u8, u32)>();
+    total += schema.cols.len() * size_of::<(u32, u32, u32, i32)>();
+    total += schema
+        .pks
+        .iter()
+        .map(|(_, columns)| size_of::<u32>() + index_list(columns))
+        .sum::<usize>();
+    total += schema.ops.len() * size_of::<(u32, u8)>();
+
+    total
+}
+
+/// Approximate the number of bytes held by a FieldPath
+#[inline(always)]
+fn estimate_path_size(path: &FieldPath) -> usize {
+    // Paths longer than the inline capacity spill onto the heap
+    let spilled = if path.len() > 8 {
+        path.len() * size_of::<SymbolId>()
+    } else {
+        0
+    };
+
+    size_of::<FieldPath>() + spilled
+}
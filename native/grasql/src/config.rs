@@ -4,12 +4,16 @@
 /// GraSQL engine, handling settings related to naming conventions, operators,
 /// caching, and performance parameters.
 use once_cell::sync::Lazy;
-use rustler::NifMap;
+use rustler::{Atom, Decoder, Error, NifResult, Term};
 use std::collections::HashMap;
 use std::sync::Mutex;
 
 /// Configuration structure that mirrors the Elixir GraSQL.Config struct
-#[derive(NifMap, Clone, Debug)]
+///
+/// Decoded from an Elixir map with atom keys. Keys missing from the map take
+/// their value from `Config::default()`, so older initializers keep working
+/// when new fields are added.
+#[derive(Clone, Debug)]
 pub struct Config {
     /// Field name suffix for aggregate operations
     pub aggregate_field_suffix: String,
@@ -17,6 +21,9 @@ pub struct Config {
     /// Argument name used for primary key in single record queries
     pub primary_key_argument_name: String,
 
+    /// Field name suffix for single record queries by primary key
+    pub by_pk_suffix: String,
+
     /// Field name for nodes in aggregate queries
     pub aggregate_nodes_field_name: String,
 
@@ -29,7 +36,8 @@ pub struct Config {
     /// Prefix for delete mutation fields in GraphQL
     pub delete_prefix: String,
 
-    /// Operator mappings from GraphQL to SQL
+    /// Operator mappings from canonical operator names (e.g. "eq") to the
+    /// GraphQL operator names used by the schema (e.g. "_eq")
     pub operators: HashMap<String, String>,
 
     /// Maximum number of strings to intern in the string interner
@@ -43,13 +51,366 @@ pub struct Config {
 
     /// Maximum allowed depth for nested GraphQL queries
     pub max_query_depth: usize,
+
+    /// Maximum number of columns a single table may reference (None = unlimited)
+    pub max_columns_per_table: Option<usize>,
+
+    /// Maximum number of new strings a single query may intern (None = unlimited)
+    pub max_new_symbols_per_query: Option<usize>,
+
+    /// Maximum number of operations a single document may define (None = unlimited)
+    pub max_operations: Option<usize>,
+
+    /// Relay connection field wrapping a list of edges (empty to disable)
+    pub edges_field: String,
+
+    /// Relay edge field wrapping the node with the table's fields (empty to disable)
+    pub node_field: String,
+
+    /// Relay pagination metadata field, ignored during extraction (empty to disable)
+    pub page_info_field: String,
+
+    /// Scalar JSON/array columns that may be queried with a sub-selection
+    ///
+    /// Sub-fields of these columns are JSON path projections, not relationships.
+    pub scalar_fields: Vec<String>,
+
+    /// Root fields backed by SQL functions rather than tables
+    pub function_fields: Vec<String>,
+
+    /// Argument of function fields whose keys are the function's arguments
+    pub function_args_argument: String,
+
+    /// Custom field directives collected as annotations instead of rejected
+    ///
+    /// Names without the `@`, e.g. `bind` for `@bind(type: "uuid")`.
+    pub field_directives: Vec<String>,
+
+    /// Field names known to be relationships, supplied from the schema
+    pub relationship_fields: Vec<String>,
+
+    /// Reject fields in `relationship_fields` that have no sub-selection,
+    /// instead of treating them as columns
+    pub require_relationship_selection: bool,
+
+    /// Select each table's primary key columns even when the query does not
+    pub always_include_pk: bool,
+
+    /// Maximum number of bind parameters a single statement may use (None = unlimited)
+    pub max_bind_params: Option<usize>,
+
+    /// Maximum number of times one table may be read along a single path of
+    /// selected relationships (None = unlimited)
+    ///
+    /// Bounds chains cycling back to an ancestor table, such as
+    /// `manager { manager { ... } }` on a self-referential relationship.
+    /// Checked during SQL generation, once tables are resolved.
+    pub max_relationship_chain: Option<usize>,
+
+    /// Maximum length of an identifier in generated SQL, in bytes (None = unlimited)
+    ///
+    /// Databases silently truncate longer identifiers (PostgreSQL at 63
+    /// bytes), so a longer table, column or alias name would refer to
+    /// something else. Generation fails instead.
+    pub max_identifier_length: Option<usize>,
+
+    /// Expand filters passed as variables using the supplied variable values
+    pub expand_variable_filters: bool,
+
+    /// Reject mutations missing the arguments their kind requires
+    pub validate_mutation_shape: bool,
+
+    /// Generate a statement inserting zero rows for `objects: []` instead of
+    /// rejecting it
+    pub allow_empty_insert: bool,
+
+    /// Generate mutations that return rows as `WITH mutation AS (...)
+    /// RETURNING *` followed by a `SELECT` of the returned columns, so
+    /// relationships in the returned selection are read off the written rows
+    pub mutation_returning_cte: bool,
+
+    /// Collect the name of every argument the query passes to any field
+    pub collect_argument_names: bool,
+
+    /// Leave the resolution request out of parse results on cache hits
+    ///
+    /// For clients that keep resolution requests keyed by query ID; they
+    /// fetch one with `do_resolution_request` only when they lack it.
+    pub lazy_resolution: bool,
+
+    /// Database SQL is generated for
+    pub sql_dialect: Dialect,
+
+    /// Letter case of the keywords in generated SQL
+    pub sql_keyword_case: SqlKeywordCase,
+
+    /// Where NULLs sort in `order_by` directions that don't say
+    /// (`asc`/`desc`); `asc_nulls_first` and the like always win
+    pub null_ordering: NullOrdering,
+
+    /// Quote every identifier in generated SQL
+    ///
+    /// When off, only identifiers that need it are quoted: reserved words of
+    /// the SQL dialect and names that are not plain lowercase identifiers.
+    pub quote_all_identifiers: bool,
+
+    /// How subscription operations are handled
+    pub subscription_mode: SubscriptionMode,
+}
+
+/// Database generated SQL targets
+///
+/// Decoded from the atoms `:postgres` and `:mysql`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, rustler::NifUnitEnum)]
+pub enum Dialect {
+    #[default]
+    Postgres,
+    Mysql,
+}
+
+/// Letter case of SQL keywords (`SELECT` or `select`)
+///
+/// Decoded from the atoms `:upper` and `:lower`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, rustler::NifUnitEnum)]
+pub enum SqlKeywordCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+/// Placement of NULLs in generated ORDER BY items
+///
+/// Decoded from the atoms `:db_default`, `:nulls_first` and `:nulls_last`.
+/// Databases disagree on the default (PostgreSQL sorts NULLs last in
+/// ascending order, MySQL first), so pinning it keeps results the same
+/// across them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, rustler::NifUnitEnum)]
+pub enum NullOrdering {
+    /// Emit no `NULLS` clause and leave placement to the database
+    #[default]
+    DbDefault,
+    NullsFirst,
+    NullsLast,
+}
+
+/// How subscription operations are handled
+///
+/// Decoded from the atoms `:reject` and `:as_query`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, rustler::NifUnitEnum)]
+pub enum SubscriptionMode {
+    /// Fail parsing, for deployments that can't serve subscriptions
+    #[default]
+    Reject,
+    /// Analyze subscriptions exactly like queries
+    AsQuery,
+}
+
+impl Default for Config {
+    /// Defaults matching the Elixir GraSQL.Config struct
+    fn default() -> Self {
+        Config {
+            aggregate_field_suffix: "_agg".to_string(),
+            primary_key_argument_name: "id".to_string(),
+            by_pk_suffix: "_by_pk".to_string(),
+            aggregate_nodes_field_name: "nodes".to_string(),
+            insert_prefix: "insert_".to_string(),
+            update_prefix: "update_".to_string(),
+            delete_prefix: "delete_".to_string(),
+            operators: default_operators(),
+            string_interner_capacity: 10_000,
+            query_cache_max_size: 1000,
+            query_cache_ttl_seconds: 600,
+            max_query_depth: 10,
+            max_columns_per_table: None,
+            max_new_symbols_per_query: None,
+            max_operations: None,
+            edges_field: "edges".to_string(),
+            node_field: "node".to_string(),
+            page_info_field: "pageInfo".to_string(),
+            scalar_fields: Vec::new(),
+            function_fields: Vec::new(),
+            function_args_argument: "args".to_string(),
+            field_directives: Vec::new(),
+            relationship_fields: Vec::new(),
+            require_relationship_selection: false,
+            always_include_pk: false,
+            max_bind_params: None,
+            max_relationship_chain: None,
+            max_identifier_length: None,
+            expand_variable_filters: false,
+            validate_mutation_shape: false,
+            allow_empty_insert: false,
+            mutation_returning_cte: false,
+            collect_argument_names: false,
+            lazy_resolution: false,
+            sql_dialect: Dialect::Postgres,
+            sql_keyword_case: SqlKeywordCase::Upper,
+            null_ordering: NullOrdering::DbDefault,
+            quote_all_identifiers: true,
+            subscription_mode: SubscriptionMode::Reject,
+        }
+    }
+}
+
+/// Declares `PartialConfig` and the decoding plumbing for every Config field
+macro_rules! config_fields {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        /// Configuration with every field optional, as received from Elixir
+        ///
+        /// Missing fields are filled in from `Config::default()` by
+        /// `into_config`.
+        #[derive(Clone, Debug, Default)]
+        pub struct PartialConfig {
+            $(pub $field: Option<$ty>,)*
+        }
+
+        impl PartialConfig {
+            /// Build a full configuration, using defaults for missing fields
+            pub fn into_config(self) -> Config {
+                let defaults = Config::default();
+                Config {
+                    $($field: self.$field.unwrap_or(defaults.$field),)*
+                }
+            }
+
+            /// Decode the fields present in an Elixir map term
+            fn decode_map(term: Term) -> NifResult<Self> {
+                if !term.is_map() {
+                    return Err(Error::BadArg);
+                }
+
+                Ok(PartialConfig {
+                    $($field: decode_optional_key(term, stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
+
+config_fields! {
+    aggregate_field_suffix: String,
+    primary_key_argument_name: String,
+    by_pk_suffix: String,
+    aggregate_nodes_field_name: String,
+    insert_prefix: String,
+    update_prefix: String,
+    delete_prefix: String,
+    operators: HashMap<String, String>,
+    string_interner_capacity: usize,
+    query_cache_max_size: usize,
+    query_cache_ttl_seconds: u64,
+    max_query_depth: usize,
+    max_columns_per_table: Option<usize>,
+    max_new_symbols_per_query: Option<usize>,
+    max_operations: Option<usize>,
+    edges_field: String,
+    node_field: String,
+    page_info_field: String,
+    scalar_fields: Vec<String>,
+    function_fields: Vec<String>,
+    function_args_argument: String,
+    field_directives: Vec<String>,
+    relationship_fields: Vec<String>,
+    require_relationship_selection: bool,
+    always_include_pk: bool,
+    max_bind_params: Option<usize>,
+    max_relationship_chain: Option<usize>,
+    max_identifier_length: Option<usize>,
+    expand_variable_filters: bool,
+    validate_mutation_shape: bool,
+    allow_empty_insert: bool,
+    mutation_returning_cte: bool,
+    collect_argument_names: bool,
+    lazy_resolution: bool,
+    sql_dialect: Dialect,
+    sql_keyword_case: SqlKeywordCase,
+    null_ordering: NullOrdering,
+    quote_all_identifiers: bool,
+    subscription_mode: SubscriptionMode,
+}
+
+/// Decode the value stored under an atom key, or None if the key is absent
+fn decode_optional_key<'a, T: Decoder<'a>>(term: Term<'a>, key: &str) -> NifResult<Option<T>> {
+    let key = Atom::from_str(term.get_env(), key)?;
+    match term.map_get(key) {
+        Ok(value) => value.decode().map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Per-call override of the mutation prefixes in `Config`
+///
+/// Lets one node serve schemas with different mutation naming conventions
+/// without reinitializing the global configuration. Prefixes left as `None`
+/// keep their configured value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MutationPrefixes {
+    /// Prefix for insert mutation fields
+    pub insert_prefix: Option<String>,
+
+    /// Prefix for update mutation fields
+    pub update_prefix: Option<String>,
+
+    /// Prefix for delete mutation fields
+    pub delete_prefix: Option<String>,
+}
+
+impl MutationPrefixes {
+    /// Return a copy of `config` with these prefixes applied
+    pub fn apply(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        if let Some(prefix) = &self.insert_prefix {
+            config.insert_prefix = prefix.clone();
+        }
+        if let Some(prefix) = &self.update_prefix {
+            config.update_prefix = prefix.clone();
+        }
+        if let Some(prefix) = &self.delete_prefix {
+            config.delete_prefix = prefix.clone();
+        }
+        config
+    }
+}
+
+impl<'a> Decoder<'a> for MutationPrefixes {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        if !term.is_map() {
+            return Err(Error::BadArg);
+        }
+
+        Ok(MutationPrefixes {
+            insert_prefix: decode_optional_key(term, "insert_prefix")?,
+            update_prefix: decode_optional_key(term, "update_prefix")?,
+            delete_prefix: decode_optional_key(term, "delete_prefix")?,
+        })
+    }
+}
+
+impl<'a> Decoder<'a> for Config {
+    fn decode(term: Term<'a>) -> NifResult<Self> {
+        PartialConfig::decode_map(term).map(PartialConfig::into_config)
+    }
 }
 
 /// Global configuration initialized during GraSQL.init
 pub static CONFIG: Lazy<Mutex<Option<Config>>> = Lazy::new(|| Mutex::new(None));
 
+/// The active configuration, or None before GraSQL is initialized
+///
+/// Returns a copy, so later re-initialization does not affect the result.
+pub fn current() -> Option<Config> {
+    match CONFIG.lock() {
+        Ok(cfg) => cfg.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
 /// Translates a GraphQL operator to SQL operator
+///
+/// Recognizes both the `_json_*` operator names and the Hasura-style
+/// `_has_key`/`_has_keys_any`/`_has_keys_all` variants.
 #[inline(always)]
+#[allow(dead_code)]
 pub fn translate_operator(graphql_op: &str) -> &'static str {
     match graphql_op {
         "_and" => "AND",
@@ -66,14 +427,105 @@ pub fn translate_operator(graphql_op: &str) -> &'static str {
         "_in" => "IN",
         "_nin" => "NOT IN",
         "_is_null" => "IS NULL",
-        "_json_contains" => "@>",
-        "_json_contained_in" => "<@",
-        "_json_has_key" => "?",
-        "_json_has_any_keys" => "?|",
-        "_json_has_all_keys" => "?&",
+        "_cast" => "CAST",
+        "_json_contains" | "_contains" => "@>",
+        "_json_contained_in" | "_contained_in" => "<@",
+        "_json_has_key" | "_has_key" => "?",
+        "_json_has_any_keys" | "_has_keys_any" => "?|",
+        "_json_has_all_keys" | "_has_keys_all" => "?&",
         "_json_path" => "->",
         "_json_path_text" => "->>",
         "_is_json" => "IS JSON",
         _ => "=", // Default to equals if unknown
     }
 }
+
+/// Translates a canonical operator name (a key of `Config.operators`) to SQL
+///
+/// Several canonical names may share one SQL operator, which is how both the
+/// `_json_*` and Hasura-style naming schemes map to the same SQL.
+#[inline(always)]
+pub fn canonical_operator_to_sql(name: &str) -> Option<&'static str> {
+    let sql = match name {
+        "and" => "AND",
+        "or" => "OR",
+        "not" => "NOT",
+        "eq" => "=",
+        "neq" => "<>",
+        "gt" => ">",
+        "lt" => "<",
+        "gte" => ">=",
+        "lte" => "<=",
+        "like" => "LIKE",
+        "ilike" => "ILIKE",
+        "in" => "IN",
+        "nin" => "NOT IN",
+        "is_null" => "IS NULL",
+        "cast" => "CAST",
+        "json_contains" | "contains" => "@>",
+        "json_contained_in" | "contained_in" => "<@",
+        "json_has_key" | "has_key" => "?",
+        "json_has_any_keys" | "has_keys_any" => "?|",
+        "json_has_all_keys" | "has_keys_all" => "?&",
+        "json_path" => "->",
+        "json_path_text" => "->>",
+        "is_json" => "IS JSON",
+        _ => return None,
+    };
+
+    Some(sql)
+}
+
+/// Default operator mappings from canonical names to GraphQL operator names
+///
+/// Mirrors the `operators` default in the Elixir GraSQL.Config struct and
+/// covers both the `_json_*` and Hasura-style JSON operator names.
+pub fn default_operators() -> HashMap<String, String> {
+    [
+        ("and", "_and"),
+        ("or", "_or"),
+        ("not", "_not"),
+        ("eq", "_eq"),
+        ("neq", "_neq"),
+        ("gt", "_gt"),
+        ("lt", "_lt"),
+        ("gte", "_gte"),
+        ("lte", "_lte"),
+        ("like", "_like"),
+        ("ilike", "_ilike"),
+        ("in", "_in"),
+        ("nin", "_nin"),
+        ("is_null", "_is_null"),
+        ("cast", "_cast"),
+        ("json_contains", "_json_contains"),
+        ("json_contained_in", "_json_contained_in"),
+        ("json_has_key", "_json_has_key"),
+        ("json_has_any_keys", "_json_has_any_keys"),
+        ("json_has_all_keys", "_json_has_all_keys"),
+        ("json_path", "_json_path"),
+        ("json_path_text", "_json_path_text"),
+        ("is_json", "_is_json"),
+        ("contains", "_contains"),
+        ("contained_in", "_contained_in"),
+        ("has_key", "_has_key"),
+        ("has_keys_any", "_has_keys_any"),
+        ("has_keys_all", "_has_keys_all"),
+    ]
+    .into_iter()
+    .map(|(name, graphql_op)| (name.to_string(), graphql_op.to_string()))
+    .collect()
+}
+
+impl Config {
+    /// Translates a GraphQL operator name to SQL using the configured mappings
+    ///
+    /// Looks up which canonical operator the schema spells as `graphql_op` and
+    /// returns its SQL operator. Returns None for names that are not configured.
+    #[inline(always)]
+    pub fn translate_operator(&self, graphql_op: &str) -> Option<&'static str> {
+        self.operators
+            .iter()
+            .find(|(_, configured)| configured.as_str() == graphql_op)
+            .and_then(|(name, _)| canonical_operator_to_sql(name))
+    }
+}
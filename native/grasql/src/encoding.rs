@@ -0,0 +1,132 @@
+/// Version of the path encoding
+///
+/// Field paths cross the NIF boundary as three flat arrays, shared by the
+/// parser that produces them, Rust code that reads a `ResolutionRequest`,
+/// and `GraSQL.Schema` on the Elixir side:
+///
+/// - `paths`: each path as its length followed by its segments, where a
+///   segment is an index into the request's string table
+///   (`[len, seg, seg, len, seg, ...]`)
+/// - `path_dir`: the offset of each path in `paths`, indexed by path id
+/// - `path_types`: `PATH_TYPE_TABLE` or `PATH_TYPE_RELATIONSHIP` per path id
+///
+/// Requests carry this version alongside the arrays. Any change to
+/// this layout must bump it, so a consumer built for another layout fails
+/// loudly instead of misreading paths.
+pub const PATH_FORMAT_VERSION: u8 = 1;
+
+/// Path type of a root table path
+pub const PATH_TYPE_TABLE: u8 = 0;
+
+/// Path type of a relationship path
+pub const PATH_TYPE_RELATIONSHIP: u8 = 1;
+
+/// Field paths in wire format
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodedPaths {
+    /// Length-prefixed path segments
+    pub paths: Vec<u32>,
+
+    /// Offset of each path in `paths`
+    pub path_dir: Vec<u32>,
+
+    /// Type of each path
+    pub path_types: Vec<u8>,
+}
+
+/// Encode `(segments, path_type)` pairs, assigning path ids in order
+///
+/// The arrays are sized up front from the iterator's length, assuming paths
+/// of two segments, to save most growth reallocations.
+pub fn encode_paths<I, P>(paths: I) -> EncodedPaths
+where
+    I: IntoIterator<Item = (P, u8)>,
+    P: AsRef<[u32]>,
+{
+    let paths = paths.into_iter();
+    let (count, _) = paths.size_hint();
+    let mut encoded = EncodedPaths {
+        paths: Vec::with_capacity(count * 3),
+        path_dir: Vec::with_capacity(count),
+        path_types: Vec::with_capacity(count),
+    };
+
+    for (segments, path_type) in paths {
+        let segments = segments.as_ref();
+        encoded.path_dir.push(encoded.paths.len() as u32);
+        encoded.paths.push(segments.len() as u32);
+        encoded.paths.extend_from_slice(segments);
+        encoded.path_types.push(path_type);
+    }
+
+    debug_assert_eq!(validate_path_dir(&encoded.paths, &encoded.path_dir), Ok(()));
+    encoded
+}
+
+/// Check that `path_dir` is consistent with `paths`
+///
+/// Offsets must be strictly increasing, as every path takes at least its
+/// length word, and each path must lie entirely within `paths`.
+pub fn validate_path_dir(paths: &[u32], path_dir: &[u32]) -> Result<(), String> {
+    for (path_id, window) in path_dir.windows(2).enumerate() {
+        if window[1] <= window[0] {
+            return Err(format!(
+                "Path {} starts at offset {}, not after path {} at offset {}",
+                path_id + 1,
+                window[1],
+                path_id,
+                window[0]
+            ));
+        }
+    }
+
+    for path_id in 0..path_dir.len() {
+        if decode_path(paths, path_dir, path_id).is_none() {
+            return Err(format!("Path {} is out of bounds", path_id));
+        }
+    }
+
+    Ok(())
+}
+
+/// Segments of one path, or None if `path_id` or its offset is out of range
+#[inline(always)]
+pub fn decode_path<'a>(paths: &'a [u32], path_dir: &[u32], path_id: usize) -> Option<&'a [u32]> {
+    let offset = *path_dir.get(path_id)? as usize;
+    let len = *paths.get(offset)? as usize;
+    paths.get(offset + 1..offset + 1 + len)
+}
+
+/// Decode every path into `(segments, path_type)` pairs, indexed by path id
+///
+/// Fails if the arrays were encoded with another format version or are
+/// inconsistent with each other (see `validate_path_dir`).
+pub fn decode_paths(version: u8, encoded: &EncodedPaths) -> Result<Vec<(Vec<u32>, u8)>, String> {
+    if version != PATH_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported path format version {} (expected {})",
+            version, PATH_FORMAT_VERSION
+        ));
+    }
+
+    if encoded.path_dir.len() != encoded.path_types.len() {
+        return Err(format!(
+            "Path directory has {} entries but {} path types",
+            encoded.path_dir.len(),
+            encoded.path_types.len()
+        ));
+    }
+
+    validate_path_dir(&encoded.paths, &encoded.path_dir)?;
+
+    encoded
+        .path_types
+        .iter()
+        .enumerate()
+        .map(|(path_id, &path_type)| {
+            decode_path(&encoded.paths, &encoded.path_dir, path_id)
+                .map(|segments| (segments.to_vec(), path_type))
+                .ok_or_else(|| format!("Path {} is out of bounds", path_id))
+        })
+        .collect()
+}
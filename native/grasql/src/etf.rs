@@ -0,0 +1,325 @@
+/// External term format module
+///
+/// Encodes a `ResolutionRequest` in Erlang's external term format, as the
+/// same tuple the NIF layer builds for it, so a cached request can be handed
+/// to Elixir as a binary and turned back into the term with
+/// `:erlang.binary_to_term/1`. Building that binary once at cache insertion
+/// spares rebuilding the whole tuple on every cache hit.
+///
+/// Only the term types a request is made of are supported: small atoms,
+/// integers, binaries, tuples and proper lists.
+use crate::types::ResolutionRequest;
+
+/// Version byte opening every encoded term
+const VERSION_MAGIC: u8 = 131;
+
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const SMALL_TUPLE_EXT: u8 = 104;
+const NIL_EXT: u8 = 106;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// Number of elements in an encoded request tuple: twelve keys and their values
+const REQUEST_ARITY: u8 = 24;
+
+/// Encode a resolution request as the term `do_parse_query` returns for it
+pub fn encode_request(request: &ResolutionRequest) -> Vec<u8> {
+    debug_assert_eq!(request.validate(), Ok(()));
+
+    let mut writer = Writer::new();
+    writer.tuple(REQUEST_ARITY);
+
+    writer.atom("query_id");
+    writer.binary(&request.query_id);
+    writer.atom("version");
+    writer.int(request.version as i64);
+    writer.atom("strings");
+    writer.list(&request.strings, |w, s| w.binary(s));
+    writer.atom("paths");
+    writer.list(&request.paths, |w, &n| w.int(n as i64));
+    writer.atom("path_dir");
+    writer.list(&request.path_dir, |w, &n| w.int(n as i64));
+    writer.atom("path_types");
+    writer.list(&request.path_types, |w, &n| w.int(n as i64));
+    writer.atom("entities");
+    writer.list(&request.entities, |w, &n| w.int(n as i64));
+    writer.atom("cols");
+    writer.list(&request.cols, |w, (table_idx, columns)| {
+        w.tuple(2);
+        w.int(*table_idx as i64);
+        w.list(columns, |w, &n| w.int(n as i64));
+    });
+    writer.atom("ops");
+    writer.list(&request.ops, |w, &(field_idx, op_type)| {
+        w.tuple(2);
+        w.int(field_idx as i64);
+        w.int(op_type as i64);
+    });
+    writer.atom("directives");
+    writer.list(
+        &request.directives,
+        |w, (path_id, column_idx, name_idx, arguments)| {
+            w.tuple(4);
+            w.int(*path_id as i64);
+            w.int(*column_idx as i64);
+            w.int(*name_idx as i64);
+            w.list(arguments, |w, (arg_idx, value)| {
+                w.tuple(2);
+                w.int(*arg_idx as i64);
+                w.binary(value);
+            });
+        },
+    );
+    writer.atom("warnings");
+    writer.list(&request.warnings, |w, s| w.binary(s));
+    writer.atom("argument_names");
+    writer.list(&request.argument_names, |w, s| w.binary(s));
+
+    writer.buf
+}
+
+/// Decode a resolution request encoded by `encode_request`
+pub fn decode_request(bytes: &[u8]) -> Result<ResolutionRequest, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.byte()? != VERSION_MAGIC {
+        return Err("Encoded request does not start with the term format version".to_string());
+    }
+    reader.tuple(REQUEST_ARITY)?;
+
+    let mut request = ResolutionRequest::new();
+    reader.key("query_id")?;
+    request.query_id = reader.binary()?;
+    reader.key("version")?;
+    request.version = reader.int_as()?;
+    reader.key("strings")?;
+    request.strings = reader.list(Reader::binary)?;
+    reader.key("paths")?;
+    request.paths = reader.list(Reader::int_as)?;
+    reader.key("path_dir")?;
+    request.path_dir = reader.list(Reader::int_as)?;
+    reader.key("path_types")?;
+    request.path_types = reader.list(Reader::int_as)?;
+    reader.key("entities")?;
+    request.entities = reader.list(Reader::int_as)?;
+    reader.key("cols")?;
+    request.cols = reader.list(|r| {
+        r.tuple(2)?;
+        Ok((r.int_as()?, r.list(Reader::int_as)?))
+    })?;
+    reader.key("ops")?;
+    request.ops = reader.list(|r| {
+        r.tuple(2)?;
+        Ok((r.int_as()?, r.int_as()?))
+    })?;
+    reader.key("directives")?;
+    request.directives = reader.list(|r| {
+        r.tuple(4)?;
+        let path_id = r.int_as()?;
+        let column_idx = r.int_as()?;
+        let name_idx = r.int_as()?;
+        let arguments = r.list(|r| {
+            r.tuple(2)?;
+            Ok((r.int_as()?, r.binary()?))
+        })?;
+        Ok((path_id, column_idx, name_idx, arguments))
+    })?;
+    reader.key("warnings")?;
+    request.warnings = reader.list(Reader::binary)?;
+    reader.key("argument_names")?;
+    request.argument_names = reader.list(Reader::binary)?;
+
+    if reader.pos != bytes.len() {
+        return Err("Trailing bytes after encoded request".to_string());
+    }
+    Ok(request)
+}
+
+/// Appends terms to a buffer that starts with the version byte
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Writer {
+            buf: vec![VERSION_MAGIC],
+        }
+    }
+
+    fn atom(&mut self, name: &str) {
+        self.buf.push(SMALL_ATOM_UTF8_EXT);
+        self.buf.push(name.len() as u8);
+        self.buf.extend_from_slice(name.as_bytes());
+    }
+
+    fn int(&mut self, value: i64) {
+        if (0..=u8::MAX as i64).contains(&value) {
+            self.buf.push(SMALL_INTEGER_EXT);
+            self.buf.push(value as u8);
+        } else if let Ok(value) = i32::try_from(value) {
+            self.buf.push(INTEGER_EXT);
+            self.buf.extend_from_slice(&value.to_be_bytes());
+        } else {
+            let digits = value.unsigned_abs().to_le_bytes();
+            let len = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+            self.buf.push(SMALL_BIG_EXT);
+            self.buf.push(len as u8);
+            self.buf.push((value < 0) as u8);
+            self.buf.extend_from_slice(&digits[..len]);
+        }
+    }
+
+    fn binary(&mut self, value: &str) {
+        self.buf.push(BINARY_EXT);
+        self.buf
+            .extend_from_slice(&(value.len() as u32).to_be_bytes());
+        self.buf.extend_from_slice(value.as_bytes());
+    }
+
+    fn tuple(&mut self, arity: u8) {
+        self.buf.push(SMALL_TUPLE_EXT);
+        self.buf.push(arity);
+    }
+
+    fn list<T>(&mut self, items: &[T], mut write: impl FnMut(&mut Self, &T)) {
+        if !items.is_empty() {
+            self.buf.push(LIST_EXT);
+            self.buf
+                .extend_from_slice(&(items.len() as u32).to_be_bytes());
+            for item in items {
+                write(self, item);
+            }
+        }
+        self.buf.push(NIL_EXT);
+    }
+}
+
+/// Reads the terms written by `Writer` back, failing on anything else
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| "Encoded request is truncated".to_string())?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn tag(&mut self, expected: u8, what: &str) -> Result<(), String> {
+        match self.byte()? {
+            tag if tag == expected => Ok(()),
+            tag => Err(format!(
+                "Expected {} at byte {}, found tag {}",
+                what,
+                self.pos - 1,
+                tag
+            )),
+        }
+    }
+
+    /// Read the atom key of a request field
+    fn key(&mut self, expected: &str) -> Result<(), String> {
+        self.tag(SMALL_ATOM_UTF8_EXT, "an atom")?;
+        let len = self.byte()? as usize;
+        let name = self.take(len)?;
+        if name != expected.as_bytes() {
+            return Err(format!(
+                "Expected key '{}', found '{}'",
+                expected,
+                String::from_utf8_lossy(name)
+            ));
+        }
+        Ok(())
+    }
+
+    fn int(&mut self) -> Result<i64, String> {
+        match self.byte()? {
+            SMALL_INTEGER_EXT => Ok(self.byte()? as i64),
+            INTEGER_EXT => Ok(self.u32()? as i32 as i64),
+            SMALL_BIG_EXT => {
+                let len = self.byte()? as usize;
+                let negative = self.byte()? != 0;
+                if len > 8 {
+                    return Err("Integer out of range".to_string());
+                }
+                let mut digits = [0u8; 8];
+                digits[..len].copy_from_slice(self.take(len)?);
+                let magnitude = i64::try_from(u64::from_le_bytes(digits))
+                    .map_err(|_| "Integer out of range".to_string())?;
+                Ok(if negative { -magnitude } else { magnitude })
+            }
+            tag => Err(format!(
+                "Expected an integer at byte {}, found tag {}",
+                self.pos - 1,
+                tag
+            )),
+        }
+    }
+
+    /// Read an integer into a narrower type, failing when it does not fit
+    fn int_as<T: TryFrom<i64>>(&mut self) -> Result<T, String> {
+        let value = self.int()?;
+        T::try_from(value).map_err(|_| format!("Integer {} out of range", value))
+    }
+
+    fn binary(&mut self) -> Result<String, String> {
+        self.tag(BINARY_EXT, "a binary")?;
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| format!("Invalid UTF-8 in binary: {}", e))
+    }
+
+    fn tuple(&mut self, arity: u8) -> Result<(), String> {
+        self.tag(SMALL_TUPLE_EXT, "a tuple")?;
+        match self.byte()? {
+            found if found == arity => Ok(()),
+            found => Err(format!(
+                "Expected a {}-tuple, found {} elements",
+                arity, found
+            )),
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        match self.byte()? {
+            NIL_EXT => Ok(Vec::new()),
+            LIST_EXT => {
+                let len = self.u32()? as usize;
+                // Cap the reservation by the bytes left, so a bogus length can't exhaust memory
+                let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+                for _ in 0..len {
+                    items.push(read(self)?);
+                }
+                self.tag(NIL_EXT, "the end of a list")?;
+                Ok(items)
+            }
+            tag => Err(format!(
+                "Expected a list at byte {}, found tag {}",
+                self.pos - 1,
+                tag
+            )),
+        }
+    }
+}
@@ -1,8 +1,24 @@
-use crate::interning::intern_str;
-use crate::types::{FieldPath, SymbolId};
-use graphql_query::ast::{Document, Field, ObjectValue, OperationDefinition, Value};
+use crate::config::Config;
+use crate::interning::{intern_str, resolve_str};
+use crate::types::{
+    ArgValue, ColumnAliases, ColumnArguments, ColumnJsonPaths, ColumnUsage, FieldArguments,
+    FieldDirective, FieldDirectives, FieldPath, FunctionArguments, InsertNode, SymbolId,
+};
+use graphql_query::ast::{
+    Document, Field, ObjectValue, OperationDefinition, PrintNode, SelectionSet, Value,
+};
 use graphql_query::visit::{VisitFlow, VisitInfo, VisitNode, Visitor};
-use std::collections::{HashMap, HashSet};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// What a path returned by `FieldPathExtractor::extract_all_paths` refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathKind {
+    /// A table or relationship
+    Entity,
+    /// A leaf column of the entity at the parent path
+    Column,
+}
 
 /// Visitor for extracting field paths from GraphQL AST
 pub struct FieldPathExtractor {
@@ -15,6 +31,69 @@ pub struct FieldPathExtractor {
     /// Map of table paths to column sets
     /// This tracks column usage per table
     column_usage: HashMap<FieldPath, HashSet<SymbolId>>,
+
+    /// Arguments passed to columns (e.g. `avatar(size: 100)`), keyed by table path and column
+    column_arguments: ColumnArguments,
+
+    /// Arguments of function root fields
+    function_args: FunctionArguments,
+
+    /// Annotation directives, keyed by the table or relationship path they appear in
+    directives: FieldDirectives,
+
+    /// Response keys each column is selected under, keyed by table path and column
+    column_aliases: ColumnAliases,
+
+    /// JSON paths projected from scalar columns, keyed by table path and column
+    json_paths: ColumnJsonPaths,
+
+    /// Pagination arguments, keyed by table or relationship path
+    args: FieldArguments,
+
+    /// Every argument name seen, when `Config.collect_argument_names` is set
+    argument_names: BTreeSet<String>,
+
+    /// Columns written by UPDATE mutations through `_set`
+    set_columns: ColumnUsage,
+
+    /// Columns UPDATE mutations match rows on through `where` and `pk_columns`,
+    /// and the key columns `delete_*_by_pk` mutations take as arguments
+    filter_columns: ColumnUsage,
+
+    /// Tables written by each insert root field, with their nested inserts
+    insert_tree: Vec<InsertNode>,
+
+    /// Structural wrapper fields (e.g. Relay `edges`/`node`) whose children
+    /// belong to the enclosing table rather than forming a path of their own
+    wrapper_fields: Vec<SymbolId>,
+
+    /// Fields that are neither columns nor relationships (e.g. Relay `pageInfo`)
+    skipped_fields: Vec<SymbolId>,
+
+    /// Scalar JSON/array columns whose sub-selections are JSON paths
+    scalar_fields: Vec<SymbolId>,
+
+    /// Root fields backed by SQL functions (`Config.function_fields`)
+    function_fields: Vec<SymbolId>,
+
+    /// Directives collected as annotations (`Config.field_directives`)
+    directive_names: Vec<SymbolId>,
+
+    /// Suffix of aggregate fields (`Config.aggregate_field_suffix`)
+    aggregate_field_suffix: String,
+
+    /// Known relationships that must have a sub-selection
+    /// (`Config.relationship_fields`, when `require_relationship_selection` is set)
+    relationship_fields: Vec<SymbolId>,
+
+    /// Whether each field entered by the visitor pushed a path segment
+    visit_stack: Vec<bool>,
+
+    /// Variable values used to expand filters passed as variables
+    variables: JsonMap<String, JsonValue>,
+
+    /// Non-fatal issues met while extracting, in the order first seen
+    warnings: Vec<String>,
 }
 
 impl FieldPathExtractor {
@@ -25,15 +104,263 @@ impl FieldPathExtractor {
             field_paths: HashSet::new(),
             current_path: FieldPath::new(),
             column_usage: HashMap::new(),
+            column_arguments: HashMap::new(),
+            function_args: HashMap::new(),
+            directives: HashMap::new(),
+            column_aliases: HashMap::new(),
+            json_paths: HashMap::new(),
+            args: HashMap::new(),
+            argument_names: BTreeSet::new(),
+            set_columns: HashMap::new(),
+            filter_columns: HashMap::new(),
+            insert_tree: Vec::new(),
+            wrapper_fields: Vec::new(),
+            skipped_fields: Vec::new(),
+            scalar_fields: Vec::new(),
+            function_fields: Vec::new(),
+            directive_names: Vec::new(),
+            aggregate_field_suffix: String::new(),
+            relationship_fields: Vec::new(),
+            visit_stack: Vec::new(),
+            warnings: Vec::new(),
+            variables: JsonMap::new(),
+        }
+    }
+
+    /// Supply variable values for filter expansion
+    ///
+    /// When `Config.expand_variable_filters` is set, a filter given as a
+    /// variable (`where: $filter`) is walked like an inline filter using the
+    /// value supplied here, so the paths it references are extracted too.
+    /// Variables without a value fall back to the default their operation
+    /// declares (`$filter: users_bool_exp = { ... }`), and are left
+    /// unexpanded when it declares none.
+    #[inline(always)]
+    pub fn set_variables(&mut self, variables: JsonMap<String, JsonValue>) {
+        self.variables = variables;
+    }
+
+    /// Take the function arguments collected by the last call to `extract_with_config`
+    ///
+    /// Keys of the `args` object of a root field listed in
+    /// `Config.function_fields` are the function's arguments, so they are kept
+    /// here and never counted as columns.
+    #[inline(always)]
+    pub fn take_function_args(&mut self) -> FunctionArguments {
+        std::mem::take(&mut self.function_args)
+    }
+
+    /// Take the annotation directives collected by the last call to `extract_with_config`
+    ///
+    /// Only directives named in `Config.field_directives` are collected; the
+    /// parser rejects any other directive before extraction.
+    #[inline(always)]
+    pub fn take_directives(&mut self) -> FieldDirectives {
+        std::mem::take(&mut self.directives)
+    }
+
+    /// Take the column arguments collected by the last call to `extract`
+    ///
+    /// Columns keep their place in the column usage returned by `extract`;
+    /// this map only adds the arguments that were passed to them.
+    #[inline(always)]
+    pub fn take_column_arguments(&mut self) -> ColumnArguments {
+        std::mem::take(&mut self.column_arguments)
+    }
+
+    /// Take the column aliases collected by the last call to `extract`
+    ///
+    /// Selecting a column under several aliases (`a: name b: name`) records
+    /// the column once in the column usage. This map keeps every response
+    /// key, so each can be produced from the single underlying column.
+    /// Columns only ever selected under their own name are left out.
+    #[inline(always)]
+    pub fn take_column_aliases(&mut self) -> ColumnAliases {
+        let mut aliases = std::mem::take(&mut self.column_aliases);
+
+        for columns in aliases.values_mut() {
+            columns.retain(|&column, keys| {
+                !matches!(keys.as_slice(), [key] if Some(key.as_str()) == resolve_str(column).as_deref())
+            });
+        }
+        aliases.retain(|_, columns| !columns.is_empty());
+
+        aliases
+    }
+
+    /// Take the JSON paths collected by the last call to `extract`
+    ///
+    /// A field listed in `Config.scalar_fields` is a column even when it has a
+    /// sub-selection. The column is recorded in the column usage as usual;
+    /// this map keeps the JSON paths selected below it.
+    #[inline(always)]
+    pub fn take_json_paths(&mut self) -> ColumnJsonPaths {
+        std::mem::take(&mut self.json_paths)
+    }
+
+    /// Take the pagination arguments collected by the last call to `extract`
+    ///
+    /// Integer literals and variables are kept; variables by name, so SQL
+    /// generation can bind them from the variable map. Other values are left
+    /// for SQL generation to reject.
+    #[inline(always)]
+    pub fn take_args(&mut self) -> FieldArguments {
+        std::mem::take(&mut self.args)
+    }
+
+    /// Take the argument names collected by the last call to `extract_with_config`
+    ///
+    /// Only filled when `Config.collect_argument_names` is set. Covers the
+    /// arguments of every field, recognized (`where`, `limit`, ...) or not,
+    /// so schema tooling can flag the ones its schema doesn't declare.
+    #[inline(always)]
+    pub fn take_argument_names(&mut self) -> BTreeSet<String> {
+        std::mem::take(&mut self.argument_names)
+    }
+
+    /// Take the warnings collected by the last call to `extract_with_config`
+    ///
+    /// Each names something extraction skipped without failing the query,
+    /// such as a filter operator that isn't configured. Repeats of the same
+    /// warning are reported once.
+    #[inline(always)]
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Record a warning unless the same one was already recorded
+    fn warn(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
         }
     }
 
+    /// Take the UPDATE column roles collected by the last call to `extract`
+    ///
+    /// Returns `(set_columns, filter_columns)`. The column usage returned by
+    /// `extract` merges every column an UPDATE touches; these maps keep the
+    /// columns it writes apart from the columns it filters on, so a written
+    /// column never ends up in a predicate or the other way around. A column
+    /// used in both roles appears in both maps. The primary key arguments of
+    /// `delete_*_by_pk` fields are filter columns too.
+    #[inline(always)]
+    pub fn take_update_columns(&mut self) -> (ColumnUsage, ColumnUsage) {
+        (
+            std::mem::take(&mut self.set_columns),
+            std::mem::take(&mut self.filter_columns),
+        )
+    }
+
+    /// Take the insert tree collected by the last call to `extract_with_config`
+    ///
+    /// Holds one node per insert root field, in document order. The columns
+    /// of a nested insert are recorded under its relationship path rather
+    /// than as a column of the parent table.
+    #[inline(always)]
+    pub fn take_insert_tree(&mut self) -> Vec<InsertNode> {
+        std::mem::take(&mut self.insert_tree)
+    }
+
     /// Extract field paths from a GraphQL document
     #[inline(always)]
     pub fn extract(
         &mut self,
         document: &Document,
-    ) -> Result<(HashSet<FieldPath>, HashMap<FieldPath, HashSet<SymbolId>>), String> {
+    ) -> Result<(HashSet<FieldPath>, ColumnUsage), String> {
+        // Get config once for mutation prefixes, operators, and structural field names
+        let config = match crate::config::CONFIG.lock() {
+            Ok(cfg_guard) => match &*cfg_guard {
+                Some(cfg) => cfg.clone(),
+                None => return Err("GraSQL not initialized; missing config".to_string()),
+            },
+            Err(_) => return Err("Failed to acquire config lock".to_string()),
+        };
+
+        self.extract_with_config(document, &config)
+    }
+
+    /// Extract every path in a document, including leaf columns
+    ///
+    /// Returns the table and relationship paths produced by `extract` tagged
+    /// as `PathKind::Entity`, plus a `PathKind::Column` path for each column,
+    /// formed by appending the column to its table's path. This unified view
+    /// suits schema coverage analysis; resolution requests keep using
+    /// `extract`, which leaves columns out of the path set.
+    pub fn extract_all_paths(
+        &mut self,
+        document: &Document,
+    ) -> Result<HashMap<FieldPath, PathKind>, String> {
+        let (field_paths, column_usage) = self.extract(document)?;
+
+        let mut paths: HashMap<FieldPath, PathKind> = field_paths
+            .into_iter()
+            .map(|path| (path, PathKind::Entity))
+            .collect();
+
+        for (table_path, columns) in column_usage {
+            for column in columns {
+                let mut column_path = table_path.clone();
+                column_path.push(column);
+                // A name used both as a column and a relationship stays an entity
+                paths.entry(column_path).or_insert(PathKind::Column);
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// Extract field paths from a GraphQL document using an explicit configuration
+    pub fn extract_with_config(
+        &mut self,
+        document: &Document,
+        config: &Config,
+    ) -> Result<(HashSet<FieldPath>, ColumnUsage), String> {
+        // Empty names disable the corresponding structural handling
+        self.wrapper_fields = [&config.edges_field, &config.node_field]
+            .into_iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| intern_str(name))
+            .collect();
+        self.skipped_fields = [&config.page_info_field]
+            .into_iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| intern_str(name))
+            .collect();
+        self.scalar_fields = config
+            .scalar_fields
+            .iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| intern_str(name))
+            .collect();
+        self.function_fields = config
+            .function_fields
+            .iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| intern_str(name))
+            .collect();
+        self.aggregate_field_suffix = config.aggregate_field_suffix.clone();
+        self.directive_names = config
+            .field_directives
+            .iter()
+            .filter(|name| !name.is_empty())
+            .map(|name| intern_str(name))
+            .collect();
+        self.relationship_fields = if config.require_relationship_selection {
+            config
+                .relationship_fields
+                .iter()
+                .filter(|name| !name.is_empty())
+                .map(|name| intern_str(name))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Variables the caller supplied; each operation adds its own defaults
+        let supplied = config
+            .expand_variable_filters
+            .then(|| std::mem::take(&mut self.variables));
+
         // Process all operations in the document
         let mut has_operation = false;
 
@@ -41,6 +368,18 @@ impl FieldPathExtractor {
             if let graphql_query::ast::Definition::Operation(operation) = definition {
                 has_operation = true;
 
+                // An omitted filter variable is expanded from its declared default
+                if let Some(supplied) = &supplied {
+                    self.variables = supplied.clone();
+                    for variable in &operation.variable_definitions.children {
+                        if !matches!(variable.default_value, Value::Null) {
+                            self.variables
+                                .entry(variable.variable.name.to_string())
+                                .or_insert_with(|| const_value_to_json(&variable.default_value));
+                        }
+                    }
+                }
+
                 // Create empty context for visit
                 let mut ctx = ();
 
@@ -48,7 +387,7 @@ impl FieldPathExtractor {
                 operation.selection_set.visit(&mut ctx, self);
 
                 // Extract tables/relationships from filters
-                self.extract_filter_paths(operation)?;
+                self.extract_filter_paths(operation, config)?;
 
                 // Extract columns from selection sets
                 self.extract_columns_from_selection_sets(operation)?;
@@ -68,14 +407,18 @@ impl FieldPathExtractor {
 
     /// Extract tables/relationships from filter expressions
     #[inline(always)]
-    fn extract_filter_paths(&mut self, operation: &OperationDefinition) -> Result<(), String> {
+    fn extract_filter_paths(
+        &mut self,
+        operation: &OperationDefinition,
+        config: &Config,
+    ) -> Result<(), String> {
         for selection in &operation.selection_set.selections {
             if let Some(field) = selection.field() {
                 // Start with empty path for root fields
                 self.current_path.clear();
 
                 // Process field arguments recursively
-                self.process_field_arguments(field)?;
+                self.process_field_arguments(field, config)?;
             }
         }
 
@@ -112,39 +455,297 @@ impl FieldPathExtractor {
         if !field.selection_set.is_empty() {
             // Store this path as a table/relationship
             self.field_paths.insert(self.current_path.clone());
+            self.record_directives(None, field);
 
             // Process child fields (columns or nested relationships)
-            for selection in &field.selection_set.selections {
-                if let Some(child_field) = selection.field() {
-                    if child_field.selection_set.is_empty() {
-                        // This is a column
-                        let column_id = intern_str(child_field.name);
-
-                        // Get or create the column set for this table
-                        let columns = self
-                            .column_usage
-                            .entry(self.current_path.clone())
-                            .or_insert_with(HashSet::new);
-
-                        // Add this column to the set
-                        columns.insert(column_id);
-                    } else {
-                        // This is a nested relationship, process recursively
-                        self.process_field_and_columns(child_field)?;
+            self.process_selection_columns(&field.selection_set)?;
+        }
+
+        // Remove field from path before returning
+        self.current_path.pop();
+
+        Ok(())
+    }
+
+    /// Process the child fields of the current table as columns or nested relationships
+    #[inline(always)]
+    fn process_selection_columns(&mut self, selection_set: &SelectionSet) -> Result<(), String> {
+        for selection in &selection_set.selections {
+            if let Some(child_field) = selection.field() {
+                let child_id = intern_str(child_field.name);
+
+                if self.is_skipped(child_id) {
+                    continue;
+                }
+
+                if self.is_wrapper(child_id) {
+                    // Children of a structural wrapper belong to the current table
+                    self.process_selection_columns(&child_field.selection_set)?;
+                } else if child_field.selection_set.is_empty() || self.is_scalar(child_id) {
+                    // A known relationship without a sub-selection is under-specified,
+                    // not a column of the same name
+                    if child_field.selection_set.is_empty()
+                        && self.relationship_fields.contains(&child_id)
+                    {
+                        return Err(format!(
+                            "Relationship '{}' must select at least one field",
+                            child_field.name
+                        ));
+                    }
+
+                    // This is a column
+                    let column_id = child_id;
+
+                    // Get or create the column set for this table
+                    let columns = self
+                        .column_usage
+                        .entry(self.current_path.clone())
+                        .or_default();
+
+                    // Add this column to the set
+                    columns.insert(column_id);
+
+                    // Aliases of one column collapse in the set; keep each response key
+                    self.record_column_key(column_id, child_field);
+                    self.record_directives(Some(column_id), child_field);
+
+                    // A scalar field with arguments is still a column; only a
+                    // sub-selection makes a field a relationship. Record its arguments.
+                    if !child_field.arguments.is_empty() {
+                        self.record_column_arguments(column_id, child_field);
+                    }
+
+                    // A sub-selection on a scalar column projects JSON paths
+                    if !child_field.selection_set.is_empty() {
+                        self.record_json_paths(column_id, child_field);
+                    }
+                } else {
+                    if child_field.name == AGGREGATE_SELECTION_FIELD && self.in_aggregate_field() {
+                        self.record_aggregated_columns(child_field);
                     }
+
+                    // This is a nested relationship, process recursively
+                    self.process_field_and_columns(child_field)?;
                 }
             }
         }
 
-        // Remove field from path before returning
-        self.current_path.pop();
+        Ok(())
+    }
+
+    /// Check whether a field is a structural wrapper (e.g. Relay `edges`/`node`)
+    ///
+    /// Root fields are never wrappers, so a Relay root `node(id:)` query still
+    /// resolves as a table.
+    #[inline(always)]
+    fn is_wrapper(&self, field_id: SymbolId) -> bool {
+        !self.current_path.is_empty() && self.wrapper_fields.contains(&field_id)
+    }
+
+    /// Check whether a field should be ignored entirely (e.g. Relay `pageInfo`)
+    #[inline(always)]
+    fn is_skipped(&self, field_id: SymbolId) -> bool {
+        !self.current_path.is_empty() && self.skipped_fields.contains(&field_id)
+    }
 
+    /// Check whether a field is a scalar JSON/array column (`Config.scalar_fields`)
+    ///
+    /// Root fields are never scalar columns.
+    #[inline(always)]
+    fn is_scalar(&self, field_id: SymbolId) -> bool {
+        !self.current_path.is_empty() && self.scalar_fields.contains(&field_id)
+    }
+
+    /// Check whether the current table is an aggregate field (e.g. `users_aggregate`)
+    #[inline(always)]
+    fn in_aggregate_field(&self) -> bool {
+        let suffix = self.aggregate_field_suffix.as_str();
+        self.current_path
+            .last()
+            .and_then(|&field_id| resolve_str(field_id))
+            .is_some_and(|name| {
+                !suffix.is_empty() && name.len() > suffix.len() && name.ends_with(suffix)
+            })
+    }
+
+    /// Record the columns aggregate functions are applied to as columns of the current table
+    ///
+    /// In `aggregate { sum { age } max { score } }` the functions wrap columns
+    /// of the aggregated table, so `age` and `score` are read from it even
+    /// though they are never selected directly.
+    fn record_aggregated_columns(&mut self, aggregate: &Field) {
+        let columns = self
+            .column_usage
+            .entry(self.current_path.clone())
+            .or_default();
+
+        let functions = aggregate
+            .selection_set
+            .selections
+            .iter()
+            .filter_map(|s| s.field());
+        for function in functions {
+            for column in function
+                .selection_set
+                .selections
+                .iter()
+                .filter_map(|s| s.field())
+            {
+                if column.selection_set.is_empty() {
+                    columns.insert(intern_str(column.name));
+                }
+            }
+        }
+    }
+
+    /// Record the JSON paths selected below a scalar column of the current table
+    #[inline(always)]
+    fn record_json_paths(&mut self, column_id: SymbolId, column: &Field) {
+        let mut found = Vec::new();
+        collect_json_paths(&column.selection_set, &mut Vec::new(), &mut found);
+
+        let paths = self
+            .json_paths
+            .entry(self.current_path.clone())
+            .or_default()
+            .entry(column_id)
+            .or_default();
+
+        for path in found {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+
+    /// Record the arguments a function field passes to its function
+    fn record_function_args(&mut self, value: &Value) -> Result<(), String> {
+        let obj = match value {
+            Value::Object(obj) => obj,
+            _ => return Err("Function arguments must be an object".to_string()),
+        };
+
+        let arguments = self
+            .function_args
+            .entry(self.current_path.clone())
+            .or_default();
+        for arg in &obj.children {
+            arguments.push((intern_str(arg.name), arg.value.print()));
+        }
         Ok(())
     }
 
+    /// Record the annotation directives of a field of the current table
+    ///
+    /// `column` is None for the table or relationship field itself.
+    #[inline(always)]
+    fn record_directives(&mut self, column: Option<SymbolId>, field: &Field) {
+        for directive in &field.directives.children {
+            let name = intern_str(directive.name);
+            if !self.directive_names.contains(&name) {
+                continue;
+            }
+
+            let arguments = directive
+                .arguments
+                .children
+                .iter()
+                .map(|arg| (intern_str(arg.name), arg.value.print()))
+                .collect();
+            let directive = FieldDirective {
+                column,
+                name,
+                arguments,
+            };
+
+            // A field selected more than once repeats its directives
+            let directives = self
+                .directives
+                .entry(self.current_path.clone())
+                .or_default();
+            if !directives.contains(&directive) {
+                directives.push(directive);
+            }
+        }
+    }
+
+    /// Record the arguments passed to a column of the current table under its response key
+    #[inline(always)]
+    fn record_column_arguments(&mut self, column_id: SymbolId, column: &Field) {
+        let key = column.alias.unwrap_or(column.name);
+        let arguments = column
+            .arguments
+            .children
+            .iter()
+            .map(|arg| (intern_str(arg.name), arg.value.print()))
+            .collect();
+
+        // A key selected more than once passes the same arguments each time
+        self.column_arguments
+            .entry(self.current_path.clone())
+            .or_default()
+            .insert(key.to_string(), (column_id, arguments));
+    }
+
+    /// Record the argument names of a field when `Config.collect_argument_names` is set
+    #[inline(always)]
+    fn record_argument_names(&mut self, field: &Field, config: &Config) {
+        if !config.collect_argument_names {
+            return;
+        }
+        for arg in &field.arguments.children {
+            if !self.argument_names.contains(arg.name) {
+                self.argument_names.insert(arg.name.to_string());
+            }
+        }
+    }
+
+    /// Record a `limit` or `offset` argument of the current field
+    ///
+    /// `limit: 0` is kept as a literal 0, meaning no rows; negative literals
+    /// are rejected.
+    #[inline(always)]
+    fn record_pagination_arg(&mut self, name: &str, value: &Value) -> Result<(), String> {
+        let value = match value {
+            Value::Int(int) => match int.value.parse::<i64>() {
+                Ok(value) if value < 0 => {
+                    return Err(format!("'{}' must not be negative, got {}", name, value))
+                }
+                Ok(value) => ArgValue::Literal(value),
+                Err(_) => return Ok(()),
+            },
+            Value::Variable(variable) => ArgValue::Variable(variable.name.to_string()),
+            _ => return Ok(()),
+        };
+
+        let args = self.args.entry(self.current_path.clone()).or_default();
+        if name == "limit" {
+            args.limit = Some(value);
+        } else {
+            args.offset = Some(value);
+        }
+        Ok(())
+    }
+
+    /// Record the response key a column is selected under
+    #[inline(always)]
+    fn record_column_key(&mut self, column_id: SymbolId, column: &Field) {
+        let key = column.alias.unwrap_or(column.name);
+        let keys = self
+            .column_aliases
+            .entry(self.current_path.clone())
+            .or_default()
+            .entry(column_id)
+            .or_default();
+
+        if !keys.iter().any(|existing| existing == key) {
+            keys.push(key.to_string());
+        }
+    }
+
     /// Process arguments of a field to extract filter paths
     #[inline(always)]
-    fn process_field_arguments(&mut self, field: &Field) -> Result<(), String> {
+    fn process_field_arguments(&mut self, field: &Field, config: &Config) -> Result<(), String> {
         // Add current field to path
         let field_id = intern_str(field.name);
         self.current_path.push(field_id);
@@ -154,44 +755,114 @@ impl FieldPathExtractor {
             self.field_paths.insert(self.current_path.clone());
         }
 
-        // Get config to check for mutation prefixes
-        let config = match crate::config::CONFIG.lock() {
-            Ok(cfg_guard) => match &*cfg_guard {
-                Some(cfg) => cfg.clone(),
-                None => return Err("GraSQL not initialized; missing config".to_string()),
-            },
-            Err(_) => return Err("Failed to acquire config lock".to_string()),
-        };
+        self.record_argument_names(field, config);
+        check_pagination_style(field)?;
 
-        // Process arguments depending on operation type
+        // Process arguments depending on operation type. Arguments not
+        // recognized here (e.g. a custom `bulk: [{ ... }]`) name no paths or
+        // columns, whatever their value, and are left out of extraction.
         for arg in &field.arguments.children {
             if arg.name == "where" {
                 // Extract paths from "where" condition (for queries and mutations)
-                self.extract_filter_paths_from_value(&arg.value)?;
+                self.extract_filter_paths_from_value(&arg.value, config)?;
             } else if field.name.starts_with(&config.insert_prefix)
                 && (arg.name == "objects" || arg.name == "object")
             {
                 // Extract column information from INSERT mutation objects
-                self.extract_mutation_objects(&arg.value, arg.name == "object")?;
+                let mut insert = InsertNode::new(self.current_path.clone());
+                self.extract_mutation_objects(&arg.value, arg.name == "object", &mut insert)?;
+                self.insert_tree.push(insert);
+            } else if field.name.starts_with(&config.insert_prefix) && arg.name == "on_conflict" {
+                // Extract the columns an upsert overwrites
+                self.extract_on_conflict(&arg.value);
             } else if field.name.starts_with(&config.update_prefix) && arg.name == "_set" {
                 // Extract column information from UPDATE mutation _set parameter
                 self.extract_update_set(&arg.value)?;
+            } else if field.name.starts_with(&config.update_prefix) && arg.name == "pk_columns" {
+                // Primary key columns identify the row to update
+                if let Value::Object(obj) = &arg.value {
+                    let path = self.current_path.clone();
+                    for key in &obj.children {
+                        self.record_filter_column(&path, key.name);
+                    }
+                }
+            } else if field.name.starts_with(&config.delete_prefix)
+                && field.name.ends_with(&config.by_pk_suffix)
+                && !matches!(arg.value, Value::Object(_) | Value::List(_))
+            {
+                // Each scalar argument is one primary key column of the deleted row
+                let path = self.current_path.clone();
+                self.record_filter_column(&path, arg.name);
             }
-        }
 
-        // Process nested fields recursively
-        for selection in &field.selection_set.selections {
-            if let Some(nested_field) = selection.field() {
-                self.process_field_arguments(nested_field)?;
+            if arg.name == config.function_args_argument
+                && self.current_path.len() == 1
+                && self.function_fields.contains(&field_id)
+            {
+                self.record_function_args(&arg.value)?;
+            }
+
+            if arg.name == "limit" || arg.name == "offset" {
+                self.record_pagination_arg(arg.name, &arg.value)?;
+            } else if arg.name == "order_by" {
+                // Sorting by a related column needs that relationship resolved
+                self.extract_order_by_paths(&arg.value, config);
+            }
+
+            // UPDATE predicates are tracked apart from the written columns
+            if field.name.starts_with(&config.update_prefix) && arg.name == "where" {
+                for (path, column) in self.filter_columns_of(&arg.value, config) {
+                    self.filter_columns.entry(path).or_default().insert(column);
+                }
+            }
+
+            // Aggregates select no columns of the rows they count, so the
+            // columns their filter compares are the columns they use
+            if arg.name == "where" && is_aggregate_field(field.name, config) {
+                for (path, column) in self.filter_columns_of(&arg.value, config) {
+                    self.column_usage.entry(path).or_default().insert(column);
+                }
             }
         }
 
+        // Process nested fields recursively
+        self.process_selection_arguments(&field.selection_set, config)?;
+
         // Remove field from path before returning
         self.current_path.pop();
 
         Ok(())
     }
 
+    /// Process the arguments of every nested field in a selection set
+    #[inline(always)]
+    fn process_selection_arguments(
+        &mut self,
+        selection_set: &SelectionSet,
+        config: &Config,
+    ) -> Result<(), String> {
+        for selection in &selection_set.selections {
+            if let Some(nested_field) = selection.field() {
+                let nested_id = intern_str(nested_field.name);
+
+                // Scalar columns carry no filters below them
+                if self.is_skipped(nested_id) || self.is_scalar(nested_id) {
+                    self.record_argument_names(nested_field, config);
+                    continue;
+                }
+
+                if self.is_wrapper(nested_id) {
+                    // Fields under a structural wrapper belong to the current table
+                    self.process_selection_arguments(&nested_field.selection_set, config)?;
+                } else {
+                    self.process_field_arguments(nested_field, config)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Extract mutation object fields for INSERT operations
     ///
     /// This method processes the "objects" or "object" parameter in INSERT mutations and
@@ -202,6 +873,7 @@ impl FieldPathExtractor {
     ///
     /// * `value` - The Value of the objects parameter, either an Object, List of Objects, or Variable
     /// * `is_single_object` - Whether this is an "object" parameter (true) or "objects" parameter (false)
+    /// * `insert` - Node of the current table, which nested inserts are added to
     ///
     /// # Returns
     ///
@@ -224,11 +896,12 @@ impl FieldPathExtractor {
         &mut self,
         value: &Value,
         is_single_object: bool,
+        insert: &mut InsertNode,
     ) -> Result<(), String> {
         match value {
             Value::Object(obj) => {
                 // Extract columns from this object
-                self.extract_object_columns(obj)?;
+                self.extract_object_columns(obj, insert)?;
                 // Make sure this path is marked as a table/relationship
                 self.field_paths.insert(self.current_path.clone());
                 Ok(())
@@ -240,7 +913,7 @@ impl FieldPathExtractor {
 
                 // Process each item in the list (batch case)
                 for item in &list.children {
-                    self.extract_mutation_objects(item, true)?;
+                    self.extract_mutation_objects(item, true, insert)?;
                 }
                 // Make sure this path is marked as a table/relationship
                 self.field_paths.insert(self.current_path.clone());
@@ -263,35 +936,80 @@ impl FieldPathExtractor {
     /// Extract columns from an object value
     ///
     /// Extracts each field name in the object as a column and adds it to
-    /// the column_usage map for the current table path.
+    /// the column_usage map for the current table path. A field holding a
+    /// nested insert (`posts: { data: [...] }`) is a relationship instead:
+    /// its rows are extracted under the relationship path and it is added to
+    /// `insert` as a child.
     ///
     /// # Arguments
     ///
     /// * `obj` - The ObjectValue to extract columns from
+    /// * `insert` - Node of the current table
     ///
     /// # Returns
     ///
     /// * `Ok(())` if processing was successful
     /// * `Err(String)` with an error message if an error occurred
-    fn extract_object_columns(&mut self, obj: &ObjectValue) -> Result<(), String> {
+    fn extract_object_columns(
+        &mut self,
+        obj: &ObjectValue,
+        insert: &mut InsertNode,
+    ) -> Result<(), String> {
         for field in &obj.children {
             let column_id = intern_str(field.name);
 
+            if let Some(data) = nested_insert_data(&field.value) {
+                self.current_path.push(column_id);
+                let child = insert.child_mut(self.current_path.clone());
+                let result = self.extract_mutation_objects(data, false, child);
+                self.current_path.pop();
+                result?;
+                continue;
+            }
+
             // Get or create the column set for the current table
             let columns = self
                 .column_usage
                 .entry(self.current_path.clone())
-                .or_insert_with(HashSet::new);
+                .or_default();
 
             // Add this column to the set
             columns.insert(column_id);
-
-            // TODO: Recursive handling of nested objects if needed
-            // This would require understanding the schema structure
         }
         Ok(())
     }
 
+    /// Extract the update columns of an `on_conflict` upsert clause
+    ///
+    /// Each entry of `update_columns` is a column of the current table. The
+    /// `constraint` names a unique constraint rather than a column, so it is
+    /// left for SQL generation to emit verbatim and is not resolved.
+    fn extract_on_conflict(&mut self, value: &Value) {
+        let Value::Object(obj) = value else {
+            return;
+        };
+
+        let update_columns = obj
+            .children
+            .iter()
+            .find(|field| field.name == "update_columns")
+            .map(|field| &field.value);
+
+        let names: Vec<&str> = match update_columns {
+            Some(Value::List(list)) => list.children.iter().filter_map(enum_or_string).collect(),
+            Some(value) => enum_or_string(value).into_iter().collect(),
+            None => Vec::new(),
+        };
+
+        if !names.is_empty() {
+            let columns = self
+                .column_usage
+                .entry(self.current_path.clone())
+                .or_default();
+            columns.extend(names.into_iter().map(intern_str));
+        }
+    }
+
     /// Extract columns from _set parameter in UPDATE mutations
     ///
     /// This method processes the "_set" parameter in UPDATE mutations and
@@ -332,10 +1050,15 @@ impl FieldPathExtractor {
                     let columns = self
                         .column_usage
                         .entry(self.current_path.clone())
-                        .or_insert_with(HashSet::new);
+                        .or_default();
 
                     // Add this column to the set
                     columns.insert(column_id);
+
+                    self.set_columns
+                        .entry(self.current_path.clone())
+                        .or_default()
+                        .insert(column_id);
                 }
                 // Make sure this path is marked as a table/relationship
                 self.field_paths.insert(self.current_path.clone());
@@ -358,29 +1081,65 @@ impl FieldPathExtractor {
         }
     }
 
+    /// Collect the `(table path, column)` pairs a `where` filter compares
+    ///
+    /// Paths start at the current field, so columns of a relationship filter
+    /// are keyed by the relationship's path.
+    fn filter_columns_of(&self, value: &Value, config: &Config) -> Vec<(FieldPath, SymbolId)> {
+        let mut found = Vec::new();
+        collect_filter_columns(value, &mut self.current_path.clone(), config, &mut found);
+        found
+    }
+
+    /// Record a column an UPDATE matches rows on
+    #[inline(always)]
+    fn record_filter_column(&mut self, path: &FieldPath, column: &str) {
+        self.filter_columns
+            .entry(path.clone())
+            .or_default()
+            .insert(intern_str(column));
+    }
+
     /// Extract filter paths from a value (recursively for objects)
     #[inline(always)]
-    fn extract_filter_paths_from_value(&mut self, value: &Value) -> Result<(), String> {
+    fn extract_filter_paths_from_value(
+        &mut self,
+        value: &Value,
+        config: &Config,
+    ) -> Result<(), String> {
         match value {
             Value::Object(obj) => {
                 for field in &obj.children {
                     if field.name.starts_with('_') {
-                        // Special handling for _and and _or operators
-                        if field.name == "_and" || field.name == "_or" {
-                            // These operators typically contain arrays of conditions
-                            if let Value::List(list) = &field.value {
-                                // Process each item in the list
-                                for item in &list.children {
-                                    self.extract_filter_paths_from_value(item)?;
+                        // Logical operators are recognized through the configured
+                        // operator names, so any naming scheme the schema uses works
+                        match config.translate_operator(field.name) {
+                            Some("AND") | Some("OR") => {
+                                // These operators typically contain arrays of conditions
+                                if let Value::List(list) = &field.value {
+                                    // Process each item in the list
+                                    for item in &list.children {
+                                        self.extract_filter_paths_from_value(item, config)?;
+                                    }
                                 }
                             }
+                            Some("NOT") => {
+                                // Negation wraps a single condition object
+                                self.extract_filter_paths_from_value(&field.value, config)?;
+                            }
+                            Some(_) => {}
+                            // Names no path either, but is likely a typo
+                            None => self.warn(unknown_operator_warning(field.name)),
                         }
-                        // Skip other operator fields that start with underscore
                         continue;
                     }
 
                     // Add field to path
                     let field_id = intern_str(field.name);
+                    if self.is_scalar(field_id) {
+                        // Operators on a scalar column, not a relationship filter
+                        continue;
+                    }
                     self.current_path.push(field_id);
 
                     // Add to our set if this is a nested object (potential relationship)
@@ -391,7 +1150,7 @@ impl FieldPathExtractor {
                     }
 
                     // Recursively process nested objects
-                    self.extract_filter_paths_from_value(&field.value)?;
+                    self.extract_filter_paths_from_value(&field.value, config)?;
 
                     // Remove field from path
                     self.current_path.pop();
@@ -400,22 +1159,150 @@ impl FieldPathExtractor {
             Value::List(list) => {
                 // Process each item in the list
                 for item in &list.children {
-                    self.extract_filter_paths_from_value(item)?;
+                    self.extract_filter_paths_from_value(item, config)?;
+                }
+            }
+            Value::Variable(var) if config.expand_variable_filters => {
+                // Walk the supplied filter as if it had been written inline
+                if let Some(filter) = self.variables.get(var.name).cloned() {
+                    self.extract_filter_paths_from_json(&filter, config);
                 }
             }
-            _ => {} // Ignore other value types
+            // Operand values, including enums such as `_eq: ACTIVE`, name no
+            // paths; the column they compare was recorded by the caller
+            Value::Enum(_)
+            | Value::String(_)
+            | Value::Int(_)
+            | Value::Float(_)
+            | Value::Boolean(_)
+            | Value::Null
+            | Value::Variable(_) => {}
         }
 
         Ok(())
     }
+
+    /// Extract the relationship paths an `order_by` sorts through
+    ///
+    /// Columns map to a direction (`name: asc`); a nested object
+    /// (`profile: { name: asc }`) sorts by a related table's column. An
+    /// aggregate key (`posts_agg: { count: desc }`) sorts by an aggregate of
+    /// the relationship named without the suffix; its keys are aggregate
+    /// functions, not relationships.
+    fn extract_order_by_paths(&mut self, value: &Value, config: &Config) {
+        match value {
+            Value::Object(obj) => {
+                for field in &obj.children {
+                    if let Value::Object(_) = field.value {
+                        if is_aggregate_field(field.name, config) {
+                            let relationship = &field.name
+                                [..field.name.len() - config.aggregate_field_suffix.len()];
+                            self.current_path.push(intern_str(relationship));
+                            self.field_paths.insert(self.current_path.clone());
+                            self.record_order_aggregate_columns(&field.value);
+                            self.current_path.pop();
+                            continue;
+                        }
+
+                        self.current_path.push(intern_str(field.name));
+                        self.field_paths.insert(self.current_path.clone());
+                        self.extract_order_by_paths(&field.value, config);
+                        self.current_path.pop();
+                    }
+                }
+            }
+            Value::List(list) => {
+                for item in &list.children {
+                    self.extract_order_by_paths(item, config);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the columns an aggregate `order_by` applies functions to
+    ///
+    /// In `posts_agg: { max: { views: desc } }`, `views` is read from the
+    /// related table just as if `aggregate { max { views } }` were selected.
+    fn record_order_aggregate_columns(&mut self, value: &Value) {
+        if let Value::Object(functions) = value {
+            for function in &functions.children {
+                if let Value::Object(columns) = &function.value {
+                    let usage = self
+                        .column_usage
+                        .entry(self.current_path.clone())
+                        .or_default();
+                    for column in &columns.children {
+                        usage.insert(intern_str(column.name));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Extract filter paths from a filter supplied as a variable value
+    ///
+    /// Mirrors `extract_filter_paths_from_value` for JSON input.
+    fn extract_filter_paths_from_json(&mut self, value: &JsonValue, config: &Config) {
+        match value {
+            JsonValue::Object(obj) => {
+                for (name, value) in obj {
+                    if name.starts_with('_') {
+                        match config.translate_operator(name) {
+                            Some("AND" | "OR" | "NOT") => {
+                                self.extract_filter_paths_from_json(value, config)
+                            }
+                            Some(_) => {}
+                            None => self.warn(unknown_operator_warning(name)),
+                        }
+                        continue;
+                    }
+
+                    self.current_path.push(intern_str(name));
+                    if value.is_object() {
+                        self.field_paths.insert(self.current_path.clone());
+                    }
+                    self.extract_filter_paths_from_json(value, config);
+                    self.current_path.pop();
+                }
+            }
+            JsonValue::Array(items) => {
+                for item in items {
+                    self.extract_filter_paths_from_json(item, config);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for FieldPathExtractor {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> Visitor<'a> for FieldPathExtractor {
     #[inline(always)]
     fn enter_field(&mut self, _ctx: &mut (), field: &'a Field<'a>, _info: &VisitInfo) -> VisitFlow {
-        // Add field to current path
         let field_id = intern_str(field.name);
+
+        // Skipped fields and scalar columns are not traversed, so leave_field
+        // is not called for them
+        if self.is_skipped(field_id) || self.is_scalar(field_id) {
+            return VisitFlow::Skip;
+        }
+
+        // Structural wrappers add nothing to the path
+        if self.is_wrapper(field_id) {
+            self.visit_stack.push(false);
+            return VisitFlow::Next;
+        }
+
+        // Add field to current path
         self.current_path.push(field_id);
+        self.visit_stack.push(true);
 
         // Only add this path to our set if it has a selection set
         // (indicating it's a table/relationship, not a column)
@@ -433,13 +1320,308 @@ impl<'a> Visitor<'a> for FieldPathExtractor {
         _field: &'a Field<'a>,
         _info: &VisitInfo,
     ) -> VisitFlow {
-        // Remove from path before returning
-        self.current_path.pop();
+        // Remove from path before returning, unless this was a structural wrapper
+        if self.visit_stack.pop().unwrap_or(true) {
+            self.current_path.pop();
+        }
 
         VisitFlow::Next
     }
 }
 
+/// Warning for a filter key that looks like an operator but isn't configured
+fn unknown_operator_warning(name: &str) -> String {
+    format!("Ignored unknown filter operator '{}'", name)
+}
+
+/// Convert a constant GraphQL value, such as a variable default, to JSON
+///
+/// Enums become strings. Variables, which constants can't contain, and
+/// numbers JSON can't represent become null.
+fn const_value_to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::Null | Value::Variable(_) => JsonValue::Null,
+        Value::Boolean(boolean) => JsonValue::Bool(boolean.value),
+        Value::Int(int) => int
+            .value
+            .parse::<i64>()
+            .map_or(JsonValue::Null, JsonValue::from),
+        Value::Float(float) => float
+            .value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map_or(JsonValue::Null, JsonValue::Number),
+        Value::String(string) => JsonValue::String(string.value.to_string()),
+        Value::Enum(enum_value) => JsonValue::String(enum_value.value.to_string()),
+        Value::List(list) => list.children.iter().map(const_value_to_json).collect(),
+        Value::Object(object) => object
+            .children
+            .iter()
+            .map(|field| (field.name.to_string(), const_value_to_json(&field.value)))
+            .collect(),
+    }
+}
+
+/// Collect the leaf key paths of a JSON projection, in selection order
+fn collect_json_paths(
+    selection_set: &SelectionSet,
+    prefix: &mut Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+) {
+    for selection in &selection_set.selections {
+        if let Some(field) = selection.field() {
+            prefix.push(field.name.to_string());
+            if field.selection_set.is_empty() {
+                paths.push(prefix.clone());
+            } else {
+                collect_json_paths(&field.selection_set, prefix, paths);
+            }
+            prefix.pop();
+        }
+    }
+}
+
+/// Check that no table selects more columns than the configured limit
+///
+/// Returns an error naming the first offending table path (e.g. "users.posts")
+/// along with its column count.
+#[inline(always)]
+pub fn check_column_limit(column_usage: &ColumnUsage, limit: usize) -> Result<(), String> {
+    for (path, columns) in column_usage {
+        if columns.len() > limit {
+            return Err(format!(
+                "Table '{}' selects {} columns, exceeding the limit of {}",
+                path_to_string(path),
+                columns.len(),
+                limit
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract the paths of a plain single-table query without the full walk
+///
+/// Covers the common `{ users { id name } }` shape: a lone query with one
+/// root field whose children are all plain columns, none of them aliased or
+/// carrying arguments or directives. Returns `None` for anything else, which
+/// is left to `FieldPathExtractor::extract_with_config`. When it returns
+/// paths, they are the ones the full extraction would have produced, and the
+/// extractor's other outputs (aliases, arguments, directives, ...) would all
+/// have been empty.
+pub fn extract_simple_selection(
+    document: &Document,
+    config: &Config,
+) -> Option<(HashSet<FieldPath>, ColumnUsage)> {
+    let operation = match document.definitions.as_slice() {
+        [graphql_query::ast::Definition::Operation(operation)]
+            if operation.operation == graphql_query::ast::OperationKind::Query =>
+        {
+            operation
+        }
+        _ => return None,
+    };
+
+    let root = match operation.selection_set.selections.as_slice() {
+        [selection] => selection.field()?,
+        _ => return None,
+    };
+    if !is_plain_field(root) || root.selection_set.is_empty() {
+        return None;
+    }
+
+    // Aggregate and function root fields collect more than their columns
+    let suffix = config.aggregate_field_suffix.as_str();
+    if (!suffix.is_empty() && root.name.len() > suffix.len() && root.name.ends_with(suffix))
+        || config.function_fields.iter().any(|name| name == root.name)
+    {
+        return None;
+    }
+
+    // Structural fields and known relationships need the full walk
+    let structural = [
+        &config.edges_field,
+        &config.node_field,
+        &config.page_info_field,
+    ];
+    let mut columns = HashSet::with_capacity(root.selection_set.selections.len());
+    for selection in &root.selection_set.selections {
+        let column = selection.field()?;
+        if !is_plain_field(column)
+            || !column.selection_set.is_empty()
+            || structural.iter().any(|name| *name == column.name)
+            || (config.require_relationship_selection
+                && config
+                    .relationship_fields
+                    .iter()
+                    .any(|name| name == column.name))
+        {
+            return None;
+        }
+        columns.insert(intern_str(column.name));
+    }
+
+    let mut path = FieldPath::new();
+    path.push(intern_str(root.name));
+    let mut column_usage = HashMap::with_capacity(1);
+    column_usage.insert(path.clone(), columns);
+    Some((HashSet::from([path]), column_usage))
+}
+
+/// Whether a field has no alias, arguments or directives
+#[inline(always)]
+fn is_plain_field(field: &Field) -> bool {
+    field.alias.is_none()
+        && field.arguments.children.is_empty()
+        && field.directives.children.is_empty()
+}
+
+/// Field of a nested insert holding the rows to insert
+pub const NESTED_INSERT_DATA_FIELD: &str = "data";
+
+/// Rows of a nested insert, if `value` is one (`{ data: ..., on_conflict: ... }`)
+#[inline(always)]
+pub fn nested_insert_data<'a, 'b>(value: &'b Value<'a>) -> Option<&'b Value<'a>> {
+    match value {
+        Value::Object(obj) => obj
+            .children
+            .iter()
+            .find(|field| field.name == NESTED_INSERT_DATA_FIELD)
+            .map(|field| &field.value),
+        _ => None,
+    }
+}
+
+/// Field of an aggregate field selecting the aggregate functions (`count`, `sum { ... }`, ...)
+const AGGREGATE_SELECTION_FIELD: &str = "aggregate";
+
+/// Arguments of offset-style pagination
+const OFFSET_PAGINATION_ARGS: [&str; 2] = ["limit", "offset"];
+
+/// Arguments of cursor-style (Relay) pagination
+const CURSOR_PAGINATION_ARGS: [&str; 4] = ["first", "after", "last", "before"];
+
+/// Check that a field does not mix offset and cursor pagination arguments
+///
+/// `limit: 10, first: 5` has no consistent meaning, so the first pair of
+/// conflicting arguments is reported instead of guessing which one wins.
+fn check_pagination_style(field: &Field) -> Result<(), String> {
+    let find = |names: &[&str]| {
+        field
+            .arguments
+            .children
+            .iter()
+            .map(|arg| arg.name)
+            .find(|name| names.contains(name))
+    };
+
+    match (find(&OFFSET_PAGINATION_ARGS), find(&CURSOR_PAGINATION_ARGS)) {
+        (Some(offset), Some(cursor)) => Err(format!(
+            "Field '{}' mixes offset pagination ('{}') with cursor pagination ('{}')",
+            field.name, offset, cursor
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Render a field path as dot-separated field names for error messages
+#[inline(always)]
+pub fn path_to_string(path: &FieldPath) -> String {
+    path.iter()
+        .map(|&symbol_id| resolve_str(symbol_id).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Collect the columns a filter compares, keyed by the path of their table
+///
+/// An object whose keys are all comparison operators, or a bare literal
+/// (`active: true`, shorthand for `_eq`), is a column predicate; any other
+/// object is a relationship filter whose columns belong to the related table.
+fn collect_filter_columns(
+    value: &Value,
+    path: &mut FieldPath,
+    config: &Config,
+    found: &mut Vec<(FieldPath, SymbolId)>,
+) {
+    match value {
+        Value::Object(obj) => {
+            for field in &obj.children {
+                match config.translate_operator(field.name) {
+                    Some("AND") | Some("OR") | Some("NOT") => {
+                        collect_filter_columns(&field.value, path, config, found);
+                    }
+                    Some(_) => {}
+                    None if is_column_predicate(&field.value, config) => {
+                        found.push((path.clone(), intern_str(field.name)));
+                    }
+                    None => {
+                        path.push(intern_str(field.name));
+                        collect_filter_columns(&field.value, path, config, found);
+                        path.pop();
+                    }
+                }
+            }
+        }
+        Value::List(list) => {
+            for item in &list.children {
+                collect_filter_columns(item, path, config, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether a field is an aggregate field (ends with `Config.aggregate_field_suffix`)
+#[inline(always)]
+pub(crate) fn is_aggregate_field(name: &str, config: &Config) -> bool {
+    !config.aggregate_field_suffix.is_empty()
+        && name.len() > config.aggregate_field_suffix.len()
+        && name.ends_with(&config.aggregate_field_suffix)
+}
+
+/// Whether a filter value compares a column (every key is a comparison operator,
+/// or the value is shorthand for `_eq`)
+fn is_column_predicate(value: &Value, config: &Config) -> bool {
+    match value {
+        Value::Object(obj) => obj.children.iter().all(|field| {
+            !matches!(
+                config.translate_operator(field.name),
+                None | Some("AND") | Some("OR") | Some("NOT")
+            )
+        }),
+        value => is_shorthand_operand(value),
+    }
+}
+
+/// Whether a filter value is a bare literal standing in for `{ _eq: value }`
+///
+/// Variables are excluded: a variable may hold a whole relationship filter.
+#[inline(always)]
+pub(crate) fn is_shorthand_operand(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Boolean(_)
+            | Value::Int(_)
+            | Value::Float(_)
+            | Value::String(_)
+            | Value::Enum(_)
+            | Value::Null
+    )
+}
+
+/// Read a name given as an enum value or a string
+#[inline(always)]
+fn enum_or_string<'a>(value: &Value<'a>) -> Option<&'a str> {
+    match value {
+        Value::Enum(name) => Some(name.value),
+        Value::String(name) => Some(name.value),
+        _ => None,
+    }
+}
+
 /// Builds an index for O(1) path lookups in Phase 3
 #[inline(always)]
 pub fn build_path_index(field_paths: &HashSet<FieldPath>) -> HashMap<FieldPath, usize> {
@@ -452,6 +1634,16 @@ pub fn build_path_index(field_paths: &HashSet<FieldPath>) -> HashMap<FieldPath,
     index
 }
 
+/// Add a path to an index built by `build_path_index`, returning its id
+///
+/// Ids stay dense: a new path gets the next free id, and a path already in
+/// the index keeps the id it has. Existing entries are never renumbered.
+#[inline(always)]
+pub fn insert_path(index: &mut HashMap<FieldPath, usize>, path: FieldPath) -> usize {
+    let next_id = index.len();
+    *index.entry(path).or_insert(next_id)
+}
+
 /// Convert a set of FieldPaths with SymbolIds to indices for Elixir
 #[inline(always)]
 pub fn convert_paths_to_indices(
@@ -487,7 +1679,7 @@ pub fn convert_column_usage_to_indices(
     field_paths: &HashSet<FieldPath>,
     symbol_to_index: &HashMap<SymbolId, u32>,
 ) -> HashMap<u32, HashSet<String>> {
-    let mut result = HashMap::new();
+    let mut result = HashMap::with_capacity(column_usage.len());
 
     // Create a map from FieldPath to index
     let mut path_to_index = HashMap::with_capacity(field_paths.len());
@@ -547,7 +1739,7 @@ mod tests {
         let document = Document::parse(&ctx, query).unwrap();
 
         let mut extractor = FieldPathExtractor::new();
-        let (field_paths, _column_usage) = extractor.extract(&document).unwrap();
+        let (field_paths, _column_usage) = extractor.extract(document).unwrap();
 
         // Should only have "users" path since it's the only table
         assert_eq!(field_paths.len(), 1);
@@ -569,7 +1761,7 @@ mod tests {
         let document = Document::parse(&ctx, query).unwrap();
 
         let mut extractor = FieldPathExtractor::new();
-        let (field_paths, _column_usage) = extractor.extract(&document).unwrap();
+        let (field_paths, _column_usage) = extractor.extract(document).unwrap();
 
         // Should have "users", "users.profile", and "users.posts" paths
         assert_eq!(field_paths.len(), 3);
@@ -604,7 +1796,7 @@ mod tests {
         let document = Document::parse(&ctx, query).unwrap();
 
         let mut extractor = FieldPathExtractor::new();
-        let (field_paths, _column_usage) = extractor.extract(&document).unwrap();
+        let (field_paths, _column_usage) = extractor.extract(document).unwrap();
 
         // Should have "users" and "users.profile" paths
         assert_eq!(field_paths.len(), 2);
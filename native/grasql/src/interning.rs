@@ -3,12 +3,55 @@
 /// This module provides efficient string interning capabilities using the lasso crate.
 /// String interning reduces memory usage by storing each unique string only once,
 /// and representing strings as small integer IDs in the rest of the application.
-use lasso::{Capacity, Rodeo, Spur};
+use lasso::{Capacity, Key, Rodeo, Spur};
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
 
 /// Global string interner
-pub static STRING_INTERNER: Lazy<Mutex<Rodeo>> = Lazy::new(|| {
+pub static STRING_INTERNER: Lazy<Mutex<Rodeo>> = Lazy::new(|| Mutex::new(new_interner()));
+
+/// Held for reading while symbol IDs are in use and for writing by `compact`
+static SYMBOLS_IN_USE: RwLock<()> = RwLock::new(());
+
+thread_local! {
+    /// Number of `SymbolGuard`s alive on this thread
+    static GUARD_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Keeps `compact` from running while symbol IDs are in use
+///
+/// Parsing, caching and SQL generation hold one from the moment they intern
+/// a string until they are done with its ID. Guards nest: only the outermost
+/// one on a thread takes the lock, so a guarded function may call another.
+/// Callers keeping a parse result to cache it later hold one across both.
+pub struct SymbolGuard {
+    _read: Option<RwLockReadGuard<'static, ()>>,
+}
+
+/// Guard the symbol IDs used until the returned guard is dropped
+pub fn hold_symbols() -> SymbolGuard {
+    let depth = GUARD_DEPTH.with(|depth| depth.replace(depth.get() + 1));
+    if depth > 0 {
+        return SymbolGuard { _read: None };
+    }
+
+    let read = match SYMBOLS_IN_USE.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    SymbolGuard { _read: Some(read) }
+}
+
+impl Drop for SymbolGuard {
+    fn drop(&mut self) {
+        GUARD_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Create an empty interner sized from the configuration
+fn new_interner() -> Rodeo {
     // Get capacity from configuration
     let capacity_size = match crate::config::CONFIG.lock() {
         Ok(cfg) => match &*cfg {
@@ -21,8 +64,8 @@ pub static STRING_INTERNER: Lazy<Mutex<Rodeo>> = Lazy::new(|| {
     // Create a proper Capacity instance for the interner
     let capacity = Capacity::for_strings(capacity_size);
 
-    Mutex::new(Rodeo::with_capacity(capacity))
-});
+    Rodeo::with_capacity(capacity)
+}
 
 /// Interns a string and returns its symbol ID
 #[inline(always)]
@@ -45,7 +88,28 @@ pub fn resolve_str(id: Spur) -> Option<String> {
     }
 }
 
-/// Gets all interned strings
+/// Returns the position of a symbol's string in `get_all_strings`
+///
+/// The interner hands out keys sequentially and only removes strings in
+/// `compact`, so a key's integer value is its position in the string list.
+/// Indices stay valid for any later call to `get_all_strings` until the
+/// interner is compacted; after that, indices obtained before are invalid.
+/// Resolution requests carry the string table they index, so they stay valid.
+#[inline(always)]
+pub fn symbol_index(id: Spur) -> u32 {
+    id.into_usize() as u32
+}
+
+/// Returns the number of strings currently interned
+#[inline(always)]
+pub fn interned_count() -> usize {
+    match STRING_INTERNER.lock() {
+        Ok(interner) => interner.len(),
+        Err(poisoned) => poisoned.into_inner().len(),
+    }
+}
+
+/// Gets all interned strings, in symbol key order
 #[inline(always)]
 pub fn get_all_strings() -> Vec<String> {
     match STRING_INTERNER.lock() {
@@ -57,3 +121,50 @@ pub fn get_all_strings() -> Vec<String> {
             .collect(),
     }
 }
+
+/// Rebuilds the interner keeping only the symbols in `retain` and those
+/// cached queries hold
+///
+/// Clearing part of the query cache leaves the interner holding strings no
+/// cached query refers to any more; compaction reclaims them without a full
+/// reset. Retained strings keep their relative order but get new, dense
+/// symbol IDs, returned as a map from old to new ID. Cached queries are
+/// rewritten to the new IDs; their resolution requests carry their own string
+/// tables and stay valid. Every other symbol ID becomes invalid, so callers
+/// must rewrite any IDs they still hold through the map.
+///
+/// Compaction waits for every `SymbolGuard` to be dropped, so parses and SQL
+/// generation in progress finish with the IDs they started with, and fails
+/// on a thread holding one itself.
+pub fn compact(retain: &HashSet<Spur>) -> Result<HashMap<Spur, Spur>, String> {
+    if GUARD_DEPTH.with(Cell::get) > 0 {
+        return Err("Cannot compact the interner while this thread holds symbols".to_string());
+    }
+
+    let _exclusive = match SYMBOLS_IN_USE.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+
+    let mut retained = crate::cache::cached_symbols();
+    retained.extend(retain);
+
+    let mut interner = match STRING_INTERNER.lock() {
+        Ok(interner) => interner,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+
+    let mut compacted = new_interner();
+    let mut remap = HashMap::with_capacity(retained.len());
+    for (old, string) in interner.iter() {
+        if retained.contains(&old) {
+            remap.insert(old, compacted.get_or_intern(string));
+        }
+    }
+
+    *interner = compacted;
+    drop(interner);
+
+    crate::cache::remap_cached_symbols(&remap);
+    Ok(remap)
+}
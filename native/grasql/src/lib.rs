@@ -10,25 +10,61 @@
 mod atoms;
 mod cache;
 mod config;
+pub mod encoding;
+pub mod etf;
 pub mod extraction;
 pub mod interning;
+pub mod naming;
 mod nif;
+pub mod outline;
 pub mod parser;
-mod sql;
+pub mod sql;
+mod telemetry;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 pub mod types;
 
 // Re-exports for public API
-pub use config::Config;
-pub use extraction::{build_path_index, convert_paths_to_indices, FieldPathExtractor};
-pub use interning::{get_all_strings, intern_str, resolve_str};
+pub use config::{
+    current as current_config, Config, Dialect, MutationPrefixes, NullOrdering, PartialConfig,
+    SqlKeywordCase, SubscriptionMode,
+};
+pub use extraction::{
+    build_path_index, convert_paths_to_indices, insert_path, FieldPathExtractor, PathKind,
+};
+pub use interning::{get_all_strings, intern_str, resolve_str, symbol_index};
 pub use types::{CachedQueryInfo, GraphQLOperationKind, ParsedQueryInfo};
 
 // Re-export from cache module for public API
-pub use cache::{add_to_cache, generate_query_id, get_from_cache};
+pub use cache::{
+    add_to_cache, add_to_cache_with_request, cache_resolved_schema, generate_query_id,
+    generate_query_id_with_overrides, generate_query_id_with_prefixes,
+    generate_query_id_with_variables, get_from_cache, get_matching_from_cache,
+    get_resolution_request, get_resolved_schema, memory_estimate, normalize_query, original_query,
+    QUERY_ID_VERSION,
+};
+
+/// Version of this crate, as declared in Cargo.toml
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Optional cargo features compiled into this build
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "test-utils") {
+        features.push("test-utils");
+    }
+    if cfg!(feature = "recent-errors") {
+        features.push("recent-errors");
+    }
+    if cfg!(feature = "tracing") {
+        features.push("tracing");
+    }
+    features
+}
 
 // Re-export test helpers (available for both internal and integration tests)
 #[cfg(any(test, feature = "test-utils"))]
-pub use cache::insert_raw_for_test;
+pub use cache::{clear_cache_for_test, insert_raw_for_test};
 
 // Module initialization
 fn load(_env: rustler::Env, opts: rustler::Term) -> bool {
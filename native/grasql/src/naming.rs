@@ -0,0 +1,39 @@
+/// Naming conventions module
+///
+/// GraphQL schemas expose a table through several root fields, such as
+/// `users`, `users_by_pk`, `users_aggregate` and `insert_users`. This module
+/// maps those field names back to the entity they target.
+use crate::config::Config;
+
+/// Derive the base entity name a GraphQL field targets
+///
+/// Strips at most one configured mutation prefix (`insert_`, `update_`,
+/// `delete_`) and then at most one configured suffix (`_by_pk`, or the
+/// aggregate suffix). An affix is only stripped when something remains, so
+/// a field named exactly like a prefix or suffix is returned unchanged.
+///
+/// # Example
+///
+/// With the default configuration, `update_users_by_pk`, `users_aggregate`
+/// and `users` all yield `users`.
+pub fn base_entity_name<'a>(field: &'a str, config: &Config) -> &'a str {
+    let prefixes = [
+        &config.insert_prefix,
+        &config.update_prefix,
+        &config.delete_prefix,
+    ];
+    let name = prefixes
+        .iter()
+        .filter(|prefix| !prefix.is_empty())
+        .find_map(|prefix| field.strip_prefix(prefix.as_str()))
+        .filter(|name| !name.is_empty())
+        .unwrap_or(field);
+
+    let suffixes = [&config.by_pk_suffix, &config.aggregate_field_suffix];
+    suffixes
+        .iter()
+        .filter(|suffix| !suffix.is_empty())
+        .find_map(|suffix| name.strip_suffix(suffix.as_str()))
+        .filter(|name| !name.is_empty())
+        .unwrap_or(name)
+}
@@ -3,22 +3,50 @@
 /// This module provides the NIFs (Native Implemented Functions) that are exposed to Elixir.
 /// These functions are the bridge between Elixir and the Rust implementation of GraSQL.
 use crate::atoms;
-use crate::cache::{add_to_cache_with_request, generate_query_id, get_from_cache};
-use crate::config::CONFIG;
-use crate::parser::parse_graphql;
-use crate::types::ResolutionRequest;
-
-use rustler::{Encoder, Env, Error, NifResult, Term};
+use crate::cache::{
+    add_to_cache_with_request, cache_resolved_schema, generate_query_id_with_overrides,
+    get_from_cache, get_matching_from_cache, get_resolution_request, get_resolved_schema,
+    memory_estimate, original_query, QUERY_ID_VERSION,
+};
+use crate::config::{current, MutationPrefixes, CONFIG};
+use crate::encoding::PATH_FORMAT_VERSION;
+use crate::interning::{get_all_strings, hold_symbols, resolve_str};
+use crate::outline::{query_outline, FieldOutline};
+use crate::parser::{
+    admission, is_blank_query, parse_batch, parse_graphql_with_overrides, parse_persisted,
+    recent_errors, EMPTY_QUERY_ERROR,
+};
+use crate::sql::{
+    explain_sql, generate_prepared_sql, generate_sql, ParamValue, PlanNode, SqlParam, StatementKind,
+};
+use crate::types::{
+    BatchMode, CachedQueryInfo, GraphQLOperationKind, InsertNode, RelationshipEntry,
+    ResolutionRequest, ResolutionResponse,
+};
+
+use graphql_query::ast::OperationKind;
+use rustler::{Encoder, Env, Error, NifResult, OwnedBinary, Term};
+use std::sync::Arc;
 
 /// Parse a GraphQL query string
 ///
 /// This function parses a GraphQL query string and returns information about the
 /// operation kind, name, and a unique query ID that can be used for SQL generation.
 /// It also returns a resolution request with field paths for schema resolution.
+///
+/// `prefixes`, when not nil, overrides the configured mutation prefixes for
+/// this parse only. `variables`, when not nil, is the JSON text of the
+/// operation's variable values, used to expand filters passed as variables
+/// when `expand_variable_filters` is enabled.
 #[rustler::nif]
-pub fn do_parse_query(env: Env<'_>, query: String) -> rustler::NifResult<Term<'_>> {
+pub fn do_parse_query(
+    env: Env<'_>,
+    query: String,
+    prefixes: Option<MutationPrefixes>,
+    variables: Option<String>,
+) -> rustler::NifResult<Term<'_>> {
     // Get the current configuration
-    let _config = match CONFIG.lock() {
+    let config = match CONFIG.lock() {
         Ok(cfg) => match &*cfg {
             Some(c) => c.clone(),
             None => return Err(Error::Term(Box::new("GraSQL not initialized"))),
@@ -26,49 +54,49 @@ pub fn do_parse_query(env: Env<'_>, query: String) -> rustler::NifResult<Term<'_
         Err(_) => return Err(Error::Term(Box::new("Failed to acquire config lock"))),
     };
 
-    // Generate a unique ID for this query
-    let query_id = generate_query_id(&query);
+    if is_blank_query(&query) {
+        return Err(Error::Term(Box::new(empty_query_error())));
+    }
 
-    // Check if we have this query in cache
-    if let Some(cached_query_info) = get_from_cache(&query_id) {
-        // Cache hit - return the cached parsed query info
-        let operation_kind = atoms::operation_kind_to_atom(cached_query_info.operation_kind);
+    let variables = match variables {
+        Some(variables) if config.expand_variable_filters => {
+            match serde_json::from_str::<serde_json::Value>(&variables) {
+                Ok(serde_json::Value::Object(variables)) => Some(variables),
+                _ => {
+                    return Err(Error::Term(Box::new(
+                        "Variables must be a JSON object".to_string(),
+                    )))
+                }
+            }
+        }
+        _ => None,
+    };
 
-        // Use cached ResolutionRequest - it should always be available
-        debug_assert!(
-            cached_query_info.resolution_request.is_some(),
-            "ResolutionRequest not found in cache - cache invariant violated"
-        );
+    // The parsed query is cached with the symbols it was parsed with
+    let _symbols = hold_symbols();
 
-        // Convert resolution request to Elixir term
-        let resolution_term = match convert_resolution_request_to_elixir(
-            env,
-            cached_query_info
-                .resolution_request
-                .as_ref()
-                .expect("ResolutionRequest missing from cache"),
-        ) {
-            Ok(term) => term,
-            Err(e) => return Err(e),
-        };
+    // Generate a unique ID for this query
+    let query_id = generate_query_id_with_overrides(&query, prefixes.as_ref(), variables.as_ref());
 
-        // Return the result with resolution request
-        let result = (
-            atoms::ok(),
-            query_id.clone(),
-            operation_kind,
-            cached_query_info.operation_name.clone().unwrap_or_default(),
-            resolution_term,
-        );
+    // Check if we have this query in cache
+    if let Some(cached_query_info) = get_matching_from_cache(&query_id, &query) {
+        // Lazy clients already hold the request, so only confirm the hit
+        if config.lazy_resolution && cached_query_info.operation_kind.requires_sql() {
+            let kind = atoms::operation_kind_to_atom(cached_query_info.operation_kind);
+            let name = cached_query_info.operation_name.unwrap_or_default();
+            return Ok((atoms::ok(), atoms::cached(), query_id, kind, name).encode(env));
+        }
 
-        return Ok(result.encode(env));
+        // Cache hit - return the cached parsed query info
+        return encode_parse_result(env, &cached_query_info);
     }
 
     // Parse the query
-    let (parsed_query_info, resolution_request) = match parse_graphql(&query) {
-        Ok((info, req)) => (info, req),
-        Err(e) => return Err(Error::Term(Box::new(e))),
-    };
+    let (parsed_query_info, resolution_request) =
+        match parse_graphql_with_overrides(&query, prefixes.as_ref(), variables.as_ref()) {
+            Ok((info, req)) => (info, req),
+            Err(e) => return Err(Error::Term(Box::new(e))),
+        };
 
     // Add to cache with resolution request
     add_to_cache_with_request(
@@ -77,25 +105,216 @@ pub fn do_parse_query(env: Env<'_>, query: String) -> rustler::NifResult<Term<'_
         resolution_request.clone(),
     );
 
-    // Return the operation info
-    let operation_kind = atoms::operation_kind_to_atom(parsed_query_info.operation_kind);
+    encode_parse_result_parts(
+        env,
+        parsed_query_info.operation_kind,
+        parsed_query_info.operation_name.as_deref(),
+        &resolution_request,
+    )
+}
+
+/// Check a GraphQL query against the configured guards
+///
+/// Runs parsing and every configured limit like `do_parse_query`, but builds
+/// no resolution request and caches nothing. Returns `{:ok, report}`, where
+/// the report is a map with `operation_kind`, `operations`, `depth`,
+/// `complexity` (the number of selected fields) and `operators` (the filter
+/// operators used), or the same `{:error, reason}` a parse would.
+#[rustler::nif]
+pub fn do_validate_query(env: Env<'_>, query: String) -> rustler::NifResult<Term<'_>> {
+    let config = match CONFIG.lock() {
+        Ok(cfg) => match &*cfg {
+            Some(c) => c.clone(),
+            None => return Err(Error::Term(Box::new("GraSQL not initialized"))),
+        },
+        Err(_) => return Err(Error::Term(Box::new("Failed to acquire config lock"))),
+    };
+
+    if is_blank_query(&query) {
+        return Err(Error::Term(Box::new(empty_query_error())));
+    }
 
-    // Convert resolution request to Elixir term
-    let resolution_term = match convert_resolution_request_to_elixir(env, &resolution_request) {
-        Ok(term) => term,
-        Err(e) => return Err(e),
+    let report = match admission::check(&query, &config) {
+        Ok(report) => report,
+        Err(e) => return Err(Error::Term(Box::new(e.reason))),
     };
 
-    // Return the result with resolution request
-    let result = (
-        atoms::ok(),
-        query_id,
-        operation_kind,
-        parsed_query_info.operation_name.unwrap_or_default(),
-        resolution_term,
-    );
+    let keys = [
+        atoms::operation_kind().encode(env),
+        atoms::operations().encode(env),
+        atoms::depth().encode(env),
+        atoms::complexity().encode(env),
+        atoms::operators().encode(env),
+    ];
+    let values = [
+        atoms::operation_kind_to_atom(report.operation_kind).encode(env),
+        report.operations.encode(env),
+        report.depth.encode(env),
+        report.complexity.encode(env),
+        report.operators.iter().collect::<Vec<_>>().encode(env),
+    ];
+
+    Ok((atoms::ok(), Term::map_from_arrays(env, &keys, &values)?).encode(env))
+}
+
+/// Return the resolution request of a cached query
+///
+/// Clients running with `lazy_resolution` call this when a parse result was
+/// `{:ok, :cached, ...}` but they no longer hold the request. Returns
+/// `{:error, :cache_miss}` when the query is not cached.
+#[rustler::nif]
+pub fn do_resolution_request(env: Env<'_>, query_id: String) -> rustler::NifResult<Term<'_>> {
+    match get_resolution_request(&query_id) {
+        Some(request) => convert_resolution_request_to_elixir(env, &request),
+        None => Err(Error::Term(Box::new(atoms::cache_miss()))),
+    }
+}
 
-    Ok(result.encode(env))
+/// Parse a persisted query, identified only by its query ID
+///
+/// Returns the same result as `do_parse_query` for the query cached under
+/// `query_id`. Returns `{:error, :persisted_query_not_found}` when it is not
+/// cached, so the client can be asked for the full query text.
+#[rustler::nif]
+pub fn do_parse_persisted(env: Env<'_>, query_id: String) -> rustler::NifResult<Term<'_>> {
+    match parse_persisted(&query_id) {
+        Some(Ok((cached_query_info, request))) => encode_parse_result_parts(
+            env,
+            cached_query_info.operation_kind,
+            cached_query_info.operation_name.as_deref(),
+            &request,
+        ),
+        Some(Err(e)) => Err(Error::Term(Box::new(e))),
+        None => Err(Error::Term(Box::new(atoms::persisted_query_not_found()))),
+    }
+}
+
+/// Parse a batch of GraphQL query strings
+///
+/// Returns a list with one entry per query, in order. Successful entries have
+/// the same shape as the result of `do_parse_query` with the same `prefixes`,
+/// including the `{:ok, :cached, ...}` confirmation of cache hits under
+/// `lazy_resolution`; failed entries are `{:error, reason}`. Batches take no
+/// variable values. When `fail_fast` is true the first failing query aborts
+/// the whole batch with an error instead, and no query is cached.
+#[rustler::nif]
+pub fn do_parse_queries(
+    env: Env<'_>,
+    queries: Vec<String>,
+    fail_fast: bool,
+    prefixes: Option<MutationPrefixes>,
+) -> rustler::NifResult<Term<'_>> {
+    let lazy_resolution = match CONFIG.lock() {
+        Ok(cfg) => match &*cfg {
+            Some(c) => c.lazy_resolution,
+            None => return Err(Error::Term(Box::new("GraSQL not initialized"))),
+        },
+        Err(_) => return Err(Error::Term(Box::new("Failed to acquire config lock"))),
+    };
+
+    let mode = if fail_fast {
+        BatchMode::FailFast
+    } else {
+        BatchMode::PerItem
+    };
+
+    let query_refs: Vec<&str> = queries.iter().map(String::as_str).collect();
+    let results = match parse_batch(&query_refs, mode, prefixes.as_ref()) {
+        Ok(results) => results,
+        Err(e) => return Err(Error::Term(Box::new(e))),
+    };
+
+    let mut terms = Vec::with_capacity(results.len());
+    for (query, result) in queries.iter().zip(results) {
+        let term = match result {
+            // Lazy clients already hold the request, so only confirm the hit
+            Ok((cached_query_info, true))
+                if lazy_resolution && cached_query_info.operation_kind.requires_sql() =>
+            {
+                let query_id = generate_query_id_with_overrides(query, prefixes.as_ref(), None);
+                let kind = atoms::operation_kind_to_atom(cached_query_info.operation_kind);
+                let name = cached_query_info.operation_name.unwrap_or_default();
+                (atoms::ok(), atoms::cached(), query_id, kind, name).encode(env)
+            }
+            Ok((cached_query_info, _)) => encode_parse_result(env, &cached_query_info)?,
+            Err(_) if is_blank_query(query) => (atoms::error(), empty_query_error()).encode(env),
+            Err(e) => (atoms::error(), e).encode(env),
+        };
+        terms.push(term);
+    }
+
+    Ok(terms.encode(env))
+}
+
+/// Encode a parsed query as `{:ok, query_id, operation_kind, operation_name, resolution_request}`
+///
+/// Cached queries carry their request already encoded (see `etf::encode_request`),
+/// which is returned as a binary for `GraSQL.Native` to decode instead of
+/// building the request term again.
+#[inline(always)]
+fn encode_parse_result<'a>(
+    env: Env<'a>,
+    cached_query_info: &CachedQueryInfo,
+) -> NifResult<Term<'a>> {
+    if let (Some(request), Some(encoded)) = (
+        &cached_query_info.resolution_request,
+        &cached_query_info.encoded_request,
+    ) {
+        if cached_query_info.operation_kind.requires_sql() {
+            let mut binary = OwnedBinary::new(encoded.len())
+                .ok_or_else(|| Error::Term(Box::new("Failed to allocate binary")))?;
+            binary.as_mut_slice().copy_from_slice(encoded);
+
+            let kind = atoms::operation_kind_to_atom(cached_query_info.operation_kind);
+            let name = cached_query_info
+                .operation_name
+                .as_deref()
+                .unwrap_or_default();
+            let query_id = request.query_id.as_str();
+            return Ok((atoms::ok(), query_id, kind, name, binary.release(env)).encode(env));
+        }
+    }
+
+    let resolution_request = cached_query_info
+        .resolution_request
+        .as_ref()
+        .ok_or_else(|| Error::Term(Box::new("ResolutionRequest missing from parse result")))?;
+
+    encode_parse_result_parts(
+        env,
+        cached_query_info.operation_kind,
+        cached_query_info.operation_name.as_deref(),
+        resolution_request,
+    )
+}
+
+/// Encode the parse result tuple from its parts
+///
+/// Operations that need no SQL (see `GraphQLOperationKind::requires_sql`)
+/// are marked `{:ok, :no_sql, query_id, operation_kind, operation_name}` so
+/// Elixir can answer them without resolving the schema or hitting the database.
+fn encode_parse_result_parts<'a>(
+    env: Env<'a>,
+    operation_kind: GraphQLOperationKind,
+    operation_name: Option<&str>,
+    resolution_request: &ResolutionRequest,
+) -> NifResult<Term<'a>> {
+    let query_id = resolution_request.query_id.as_str();
+    let kind = atoms::operation_kind_to_atom(operation_kind);
+    let name = operation_name.unwrap_or_default();
+
+    if !operation_kind.requires_sql() {
+        return Ok((atoms::ok(), atoms::no_sql(), query_id, kind, name).encode(env));
+    }
+
+    let resolution_term = convert_resolution_request_to_elixir(env, resolution_request)?;
+    Ok((atoms::ok(), query_id, kind, name, resolution_term).encode(env))
+}
+
+/// Structured reason for blank queries: `{:empty_query, message}`
+#[inline(always)]
+fn empty_query_error() -> (rustler::Atom, &'static str) {
+    (atoms::empty_query(), EMPTY_QUERY_ERROR)
 }
 
 /// Convert ResolutionRequest to Elixir terms
@@ -104,12 +323,17 @@ fn convert_resolution_request_to_elixir<'a>(
     env: Env<'a>,
     request: &ResolutionRequest,
 ) -> NifResult<Term<'a>> {
+    debug_assert_eq!(request.validate(), Ok(()));
+
     // Create a map with atom keys for each field in the ResolutionRequest
     // This will be easier to pattern match in Elixir
     // Create individual terms
     let query_id_atom = atoms::query_id().encode(env);
     let query_id_term = request.query_id.encode(env);
 
+    let version_atom = atoms::version().encode(env);
+    let version_term = request.version.encode(env);
+
     let strings_atom = atoms::strings().encode(env);
     let strings_term = request.strings.encode(env);
 
@@ -122,18 +346,32 @@ fn convert_resolution_request_to_elixir<'a>(
     let path_types_atom = atoms::path_types().encode(env);
     let path_types_term = request.path_types.encode(env);
 
+    let entities_atom = atoms::entities().encode(env);
+    let entities_term = request.entities.encode(env);
+
     let cols_atom = atoms::cols().encode(env);
     let cols_term = request.cols.encode(env);
 
     let ops_atom = atoms::ops().encode(env);
     let ops_term = request.ops.encode(env);
 
-    // Create a 14-element tuple with key-value pairs
+    let directives_atom = atoms::directives().encode(env);
+    let directives_term = request.directives.encode(env);
+
+    let warnings_atom = atoms::warnings().encode(env);
+    let warnings_term = request.warnings.encode(env);
+
+    let argument_names_atom = atoms::argument_names().encode(env);
+    let argument_names_term = request.argument_names.encode(env);
+
+    // Create a 24-element tuple with key-value pairs
     Ok(rustler::types::tuple::make_tuple(
         env,
         &[
             query_id_atom,
             query_id_term,
+            version_atom,
+            version_term,
             strings_atom,
             strings_term,
             paths_atom,
@@ -142,10 +380,18 @@ fn convert_resolution_request_to_elixir<'a>(
             path_dir_term,
             path_types_atom,
             path_types_term,
+            entities_atom,
+            entities_term,
             cols_atom,
             cols_term,
             ops_atom,
             ops_term,
+            directives_atom,
+            directives_term,
+            warnings_atom,
+            warnings_term,
+            argument_names_atom,
+            argument_names_term,
         ],
     ))
 }
@@ -153,33 +399,463 @@ fn convert_resolution_request_to_elixir<'a>(
 /// Generate SQL from a parsed GraphQL query
 ///
 /// This function generates SQL from a previously parsed GraphQL query,
-/// identified by its query ID. It also takes variables that can be used
-/// in the query and resolved schema information.
+/// identified by the query ID in the resolved schema information.
+///
+/// `resolution_response` may also be just the query ID of a query whose
+/// resolved schema was stored with `do_cache_schema`.
+///
+/// Returns `{:ok, [{name, sql, params, kind}]}` with one statement per root
+/// field. Each parameter is `{0, value}` for a literal or `{1, variable_name}`
+/// for a GraphQL variable, in placeholder order. `kind` classifies the
+/// statement for routing, as `{:read, :query}` or `{:write, operation}` with
+/// operation `:insert`, `:update` or `:delete`.
 #[rustler::nif]
 pub fn do_generate_sql<'a>(
     env: Env<'a>,
     resolution_response: Term<'a>,
 ) -> rustler::NifResult<Term<'a>> {
-    // Get the current configuration
-    let _config = match CONFIG.lock() {
-        Ok(cfg) => match &*cfg {
-            Some(c) => c.clone(),
-            None => return Err(Error::Term(Box::new("GraSQL not initialized"))),
-        },
-        Err(_) => return Err(Error::Term(Box::new("Failed to acquire config lock"))),
+    let (cached_query_info, response) = resolve_for_sql(env, resolution_response)?;
+
+    let statements = match generate_sql(&cached_query_info, &response) {
+        Ok(statements) => statements,
+        Err(e) => return Err(Error::Term(Box::new(e))),
     };
 
-    // Decode ResolutionResponse from Elixir term
+    let operations: Vec<Term<'a>> = statements
+        .iter()
+        .map(|statement| {
+            let params: Vec<Term<'a>> = statement
+                .params
+                .iter()
+                .map(|param| encode_sql_param(env, param))
+                .collect();
+            (
+                statement.name.as_str(),
+                statement.sql.as_str(),
+                params,
+                encode_statement_kind(env, statement.kind),
+            )
+                .encode(env)
+        })
+        .collect();
+
+    Ok((atoms::ok(), operations).encode(env))
+}
+
+/// Generate named SQL statement templates from a parsed GraphQL query
+///
+/// Like `do_generate_sql`, but for drivers that prepare each statement once
+/// and execute it many times. Returns
+/// `{:ok, [{name, statement_name, sql, params, param_types, kind}]}`, where
+/// `statement_name` is the same for structurally identical queries, each
+/// parameter type is a SQL type name, or `nil` where the database has to
+/// infer it, and `kind` is the routing classification of `do_generate_sql`.
+#[rustler::nif]
+pub fn do_generate_prepared_sql<'a>(
+    env: Env<'a>,
+    resolution_response: Term<'a>,
+) -> rustler::NifResult<Term<'a>> {
+    let (cached_query_info, response) = resolve_for_sql(env, resolution_response)?;
+
+    let statements = match generate_prepared_sql(&cached_query_info, &response) {
+        Ok(statements) => statements,
+        Err(e) => return Err(Error::Term(Box::new(e))),
+    };
+
+    let operations: Vec<Term<'a>> = statements
+        .iter()
+        .map(|prepared| {
+            let params: Vec<Term<'a>> = prepared
+                .statement
+                .params
+                .iter()
+                .map(|param| encode_sql_param(env, param))
+                .collect();
+            (
+                prepared.statement.name.as_str(),
+                prepared.name.as_str(),
+                prepared.statement.sql.as_str(),
+                params,
+                &prepared.param_types,
+                encode_statement_kind(env, prepared.statement.kind),
+            )
+                .encode(env)
+        })
+        .collect();
+
+    Ok((atoms::ok(), operations).encode(env))
+}
+
+/// Generate SQL from a parsed GraphQL query along with a description of its plan
+///
+/// For plan inspection and cost estimation. `resolution_response` is resolved
+/// like in `do_generate_sql`, and must be for `query_id`. Returns
+/// `{:ok, [{name, sql, plan}]}` with one statement per root field. Nothing is
+/// bound: the SQL and plan keep their placeholders. Each plan is a map with
+/// `name`, `table`, `join` (`nil` for the root field, otherwise the
+/// relationship type), `filter`, `order_by`, `limit`, `offset` and
+/// `children`, the plans of the relationships selected below it.
+#[rustler::nif]
+pub fn do_explain_query<'a>(
+    env: Env<'a>,
+    query_id: String,
+    resolution_response: Term<'a>,
+) -> NifResult<Term<'a>> {
+    let (cached_query_info, response) = resolve_for_sql(env, resolution_response)?;
+    if response.query_id != query_id {
+        return Err(Error::Term(Box::new(format!(
+            "Resolution response is for query '{}', not '{}'",
+            response.query_id, query_id
+        ))));
+    }
+
+    let statements = match explain_sql(&cached_query_info, &response) {
+        Ok(statements) => statements,
+        Err(e) => return Err(Error::Term(Box::new(e))),
+    };
+
+    let operations = statements
+        .iter()
+        .map(|explained| {
+            Ok((
+                explained.statement.name.as_str(),
+                explained.statement.sql.as_str(),
+                encode_plan_node(env, &explained.plan)?,
+            )
+                .encode(env))
+        })
+        .collect::<NifResult<Vec<Term<'a>>>>()?;
+
+    Ok((atoms::ok(), operations).encode(env))
+}
+
+/// Encode a plan node and its children as a map
+fn encode_plan_node<'a>(env: Env<'a>, node: &PlanNode) -> NifResult<Term<'a>> {
+    let children = node
+        .children
+        .iter()
+        .map(|child| encode_plan_node(env, child))
+        .collect::<NifResult<Vec<_>>>()?;
+
+    let keys = [
+        atoms::name().encode(env),
+        atoms::table().encode(env),
+        atoms::join().encode(env),
+        atoms::filter().encode(env),
+        atoms::order_by().encode(env),
+        atoms::limit().encode(env),
+        atoms::offset().encode(env),
+        atoms::children().encode(env),
+    ];
+    let values = [
+        node.name.encode(env),
+        node.table.encode(env),
+        node.join.encode(env),
+        node.filter.encode(env),
+        node.order_by.encode(env),
+        node.limit.encode(env),
+        node.offset.encode(env),
+        children.encode(env),
+    ];
+    Term::map_from_arrays(env, &keys, &values)
+}
+
+/// Look up the cached query and resolved schema to generate SQL from
+///
+/// `resolution` is either a resolution response or the ID of a query whose
+/// schema was stored with `do_cache_schema`. Returns `{:error, :cache_miss}`
+/// when the query is no longer cached and `{:error, :schema_not_cached}` when
+/// it has no stored schema.
+fn resolve_for_sql<'a>(
+    env: Env<'a>,
+    resolution: Term<'a>,
+) -> NifResult<(CachedQueryInfo, Arc<ResolutionResponse>)> {
+    if resolution.is_binary() {
+        let query_id: String = resolution.decode()?;
+        let cached_query_info = match get_from_cache(&query_id) {
+            Some(info) => info,
+            None => return Err(Error::Term(Box::new(atoms::cache_miss()))),
+        };
+        return match get_resolved_schema(&query_id) {
+            Some(schema) => Ok((cached_query_info, schema)),
+            None => Err(Error::Term(Box::new(atoms::schema_not_cached()))),
+        };
+    }
+
+    let response = decode_resolution_response(env, resolution)?;
+
+    // The parsed query must still be cached under the response's query ID
+    match get_from_cache(&response.query_id) {
+        Some(info) => Ok((info, Arc::new(response))),
+        None => Err(Error::Term(Box::new(atoms::cache_miss()))),
+    }
+}
+
+/// Store the resolved schema of a parsed query for later SQL generation
+///
+/// After this, `do_generate_sql` and `do_generate_prepared_sql` accept the
+/// query ID in place of the resolution response. The schema is evicted along
+/// with the query's cache entry. Returns `:ok`, or `{:error, :cache_miss}`
+/// when the query is not cached.
+#[rustler::nif]
+pub fn do_cache_schema<'a>(
+    env: Env<'a>,
+    query_id: String,
+    resolution_response: Term<'a>,
+) -> NifResult<Term<'a>> {
     let response = decode_resolution_response(env, resolution_response)?;
+    if response.query_id != query_id {
+        return Err(Error::Term(Box::new(format!(
+            "Resolution response is for query '{}', not '{}'",
+            response.query_id, query_id
+        ))));
+    }
+
+    if cache_resolved_schema(response) {
+        Ok(atoms::ok().encode(env))
+    } else {
+        Err(Error::Term(Box::new(atoms::cache_miss())))
+    }
+}
+
+/// Encode a statement kind as `{:read, :query}` or `{:write, kind}`
+fn encode_statement_kind(env: Env<'_>, kind: StatementKind) -> Term<'_> {
+    let access = if kind.is_write() {
+        atoms::write()
+    } else {
+        atoms::read()
+    };
+    (access, kind).encode(env)
+}
+
+/// Encode a bind parameter as `{0, value}` or `{1, variable_name}`
+fn encode_sql_param<'a>(env: Env<'a>, param: &SqlParam) -> Term<'a> {
+    match param {
+        SqlParam::Literal(value) => (0, encode_param_value(env, value)).encode(env),
+        SqlParam::Variable(name) => (1, name.as_str()).encode(env),
+    }
+}
+
+/// Encode a literal parameter value as the equivalent Elixir term
+fn encode_param_value<'a>(env: Env<'a>, value: &ParamValue) -> Term<'a> {
+    match value {
+        ParamValue::Null => rustler::types::atom::nil().encode(env),
+        ParamValue::Boolean(value) => value.encode(env),
+        ParamValue::Int(value) => value.encode(env),
+        ParamValue::Float(value) => value.encode(env),
+        ParamValue::String(value) => value.encode(env),
+        ParamValue::List(items) => items
+            .iter()
+            .map(|item| encode_param_value(env, item))
+            .collect::<Vec<_>>()
+            .encode(env),
+        ParamValue::Object(fields) => {
+            let mut map = Term::map_new(env);
+            for (key, value) in fields {
+                map = map
+                    .map_put(key.as_str(), encode_param_value(env, value))
+                    .unwrap_or(map);
+            }
+            map
+        }
+    }
+}
+
+/// Estimate the memory held by the query cache
+///
+/// Returns an approximate byte count for all cached queries, including the
+/// AST arenas they keep alive. See `cache::memory_estimate` for what is counted.
+#[rustler::nif]
+pub fn do_cache_memory_estimate() -> usize {
+    memory_estimate()
+}
 
-    // Generate SQL using the cached query info and resolution response
-    // Note: This is a stub implementation - will be replaced in Phase 3
-    let _ = response; // Use the response to avoid unused variable warning
+/// Report the library version and build features
+///
+/// Returns a map with the crate `version`, the `query_id_version` of the query
+/// ID scheme, the `path_format_version` of resolution request paths and the
+/// list of optional cargo `features` compiled in.
+#[rustler::nif]
+pub fn do_version(env: Env<'_>) -> NifResult<Term<'_>> {
+    let keys = [
+        atoms::version().encode(env),
+        atoms::query_id_version().encode(env),
+        atoms::path_format_version().encode(env),
+        atoms::features().encode(env),
+    ];
+    let values = [
+        crate::VERSION.encode(env),
+        QUERY_ID_VERSION.encode(env),
+        PATH_FORMAT_VERSION.encode(env),
+        crate::enabled_features().encode(env),
+    ];
+
+    Term::map_from_arrays(env, &keys, &values)
+}
+
+/// Report the active configuration
+///
+/// Returns a map with the naming conventions, mutation prefixes and limits
+/// the parser is using, or nil when GraSQL is not initialized.
+#[rustler::nif]
+pub fn do_current_config(env: Env<'_>) -> NifResult<Term<'_>> {
+    let config = match current() {
+        Some(config) => config,
+        None => return Ok(rustler::types::atom::nil().encode(env)),
+    };
 
-    // Create an empty list for parameters
-    let params: Vec<Term<'a>> = Vec::new();
+    let keys = [
+        atoms::aggregate_field_suffix().encode(env),
+        atoms::primary_key_argument_name().encode(env),
+        atoms::by_pk_suffix().encode(env),
+        atoms::aggregate_nodes_field_name().encode(env),
+        atoms::insert_prefix().encode(env),
+        atoms::update_prefix().encode(env),
+        atoms::delete_prefix().encode(env),
+        atoms::max_query_depth().encode(env),
+        atoms::query_cache_max_size().encode(env),
+        atoms::query_cache_ttl_seconds().encode(env),
+        atoms::string_interner_capacity().encode(env),
+    ];
+    let values = [
+        config.aggregate_field_suffix.encode(env),
+        config.primary_key_argument_name.encode(env),
+        config.by_pk_suffix.encode(env),
+        config.aggregate_nodes_field_name.encode(env),
+        config.insert_prefix.encode(env),
+        config.update_prefix.encode(env),
+        config.delete_prefix.encode(env),
+        config.max_query_depth.encode(env),
+        config.query_cache_max_size.encode(env),
+        config.query_cache_ttl_seconds.encode(env),
+        config.string_interner_capacity.encode(env),
+    ];
+
+    Term::map_from_arrays(env, &keys, &values)
+}
 
-    Ok((atoms::ok(), "SELECT 1", params).encode(env))
+/// Outline a GraphQL document for editor tooling
+///
+/// Returns `{:ok, operations}` where each operation is a map with `kind`
+/// (`:query`, `:mutation` or `:subscription`), `name` and `fields`. Each field
+/// is a map with `name`, `alias`, `arguments` (a list of argument names) and
+/// `children`. Nothing is cached and no configuration is needed.
+#[rustler::nif]
+pub fn do_query_outline(env: Env<'_>, query: String) -> NifResult<Term<'_>> {
+    let operations = query_outline(&query).map_err(|e| Error::Term(Box::new(e)))?;
+
+    let terms = operations
+        .iter()
+        .map(|operation| {
+            let kind = match operation.kind {
+                OperationKind::Query => atoms::query(),
+                OperationKind::Mutation => atoms::mutation(),
+                OperationKind::Subscription => atoms::subscription(),
+            };
+            let keys = [
+                atoms::kind().encode(env),
+                atoms::name().encode(env),
+                atoms::fields().encode(env),
+            ];
+            let values = [
+                kind.encode(env),
+                operation.name.encode(env),
+                encode_field_outlines(env, &operation.fields)?,
+            ];
+            Term::map_from_arrays(env, &keys, &values)
+        })
+        .collect::<NifResult<Vec<_>>>()?;
+
+    Ok((atoms::ok(), terms).encode(env))
+}
+
+/// Return the insert tree of a cached query, for planning nested inserts
+///
+/// Returns `{:ok, nodes}` with one node per insert root field. Each node is a
+/// map with `path`, the field names from the root field to the table, and
+/// `children`, the nodes of the inserts nested in its rows. Returns
+/// `{:error, :cache_miss}` when the query is not cached.
+#[rustler::nif]
+pub fn do_insert_tree(env: Env<'_>, query_id: String) -> NifResult<Term<'_>> {
+    let cached_query_info = match get_from_cache(&query_id) {
+        Some(info) => info,
+        None => return Err(Error::Term(Box::new(atoms::cache_miss()))),
+    };
+
+    let nodes = cached_query_info.insert_tree.as_deref().unwrap_or_default();
+    Ok((atoms::ok(), encode_insert_nodes(env, nodes)?).encode(env))
+}
+
+/// Get the query text a cached query was parsed from, for logging and debugging
+///
+/// Returns `{:ok, query}`, or `{:error, :cache_miss}` when the query is not cached.
+#[rustler::nif]
+pub fn do_original_query(env: Env<'_>, query_id: String) -> NifResult<Term<'_>> {
+    match original_query(&query_id) {
+        Some(query) => Ok((atoms::ok(), query).encode(env)),
+        None => Err(Error::Term(Box::new(atoms::cache_miss()))),
+    }
+}
+
+/// Encode insert tree nodes as a list of maps
+fn encode_insert_nodes<'a>(env: Env<'a>, nodes: &[InsertNode]) -> NifResult<Term<'a>> {
+    let terms = nodes
+        .iter()
+        .map(|node| {
+            let path: Vec<String> = node
+                .path
+                .iter()
+                .map(|&symbol| resolve_str(symbol).unwrap_or_default())
+                .collect();
+            let keys = [atoms::path().encode(env), atoms::children().encode(env)];
+            let values = [path.encode(env), encode_insert_nodes(env, &node.children)?];
+            Term::map_from_arrays(env, &keys, &values)
+        })
+        .collect::<NifResult<Vec<_>>>()?;
+
+    Ok(terms.encode(env))
+}
+
+/// Encode outlined fields as a list of maps
+fn encode_field_outlines<'a>(env: Env<'a>, fields: &[FieldOutline]) -> NifResult<Term<'a>> {
+    let terms = fields
+        .iter()
+        .map(|field| {
+            let keys = [
+                atoms::name().encode(env),
+                atoms::alias().encode(env),
+                atoms::arguments().encode(env),
+                atoms::children().encode(env),
+            ];
+            let values = [
+                field.name.encode(env),
+                field.alias.encode(env),
+                field.arguments.encode(env),
+                encode_field_outlines(env, &field.children)?,
+            ];
+            Term::map_from_arrays(env, &keys, &values)
+        })
+        .collect::<NifResult<Vec<_>>>()?;
+
+    Ok(terms.encode(env))
+}
+
+/// Return the most recent parse errors as `[{query_prefix, error}]`, oldest first
+///
+/// Empty when the `recent-errors` feature is disabled.
+#[rustler::nif]
+pub fn do_recent_errors() -> Vec<(String, String)> {
+    recent_errors()
+}
+
+/// Return every interned string, in symbol key order
+///
+/// The position of a string in the list is its symbol index, the same index
+/// resolution requests use in `paths`, `cols` and `ops`. Meant for debugging
+/// symbol mapping issues; the list grows with every new identifier.
+#[rustler::nif]
+pub fn do_all_strings() -> Vec<String> {
+    get_all_strings()
 }
 
 /// Decode ResolutionResponse from Elixir term
@@ -193,13 +869,18 @@ fn decode_resolution_response<'a>(
     let tables: Vec<(u32, u32, u32)> = term.map_get(atoms::tables())?.decode()?;
 
     // Decode relationships with source and target column arrays
-    let rels: Vec<(u32, u32, u8, i32, Vec<u32>, Vec<u32>)> =
-        term.map_get(atoms::rels())?.decode()?;
+    let rels: Vec<RelationshipEntry> = term.map_get(atoms::rels())?.decode()?;
 
     let joins: Vec<(u32, u32, Vec<u32>, Vec<u32>)> = term.map_get(atoms::joins())?.decode()?;
     let path_map: Vec<(u8, u32)> = term.map_get(atoms::path_map())?.decode()?;
     let cols: Vec<(u32, u32, u32, i32)> = term.map_get(atoms::cols())?.decode()?;
 
+    // Primary keys are optional; resolvers that don't know them omit the key
+    let pks: Vec<(u32, Vec<u32>)> = match term.map_get(atoms::pks()) {
+        Ok(value) => value.decode()?,
+        Err(_) => Vec::new(),
+    };
+
     // Decode operations
     let ops: Vec<(u32, u8)> = term.map_get(atoms::ops())?.decode()?;
 
@@ -211,6 +892,7 @@ fn decode_resolution_response<'a>(
         joins,
         path_map,
         cols,
+        pks,
         ops,
     })
 }
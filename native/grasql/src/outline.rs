@@ -0,0 +1,96 @@
+/// Query outline module
+///
+/// Builds a compact, read-only summary of a GraphQL document for editor
+/// tooling such as a language server: every operation with its kind and name,
+/// and the tree of fields it selects with their argument names. Unlike the
+/// resolution request, an outline involves no configuration, interning or
+/// caching, and unsupported features such as fragments don't make it fail.
+use crate::parser::strip_bom;
+use graphql_query::ast::{
+    ASTContext, Definition, Document, Field, OperationKind, ParseNode, Selection, SelectionSet,
+};
+
+/// Summary of one operation definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationOutline {
+    /// Whether this is a query, mutation or subscription
+    pub kind: OperationKind,
+
+    /// Operation name, if the operation is named
+    pub name: Option<String>,
+
+    /// Root fields, in selection order
+    pub fields: Vec<FieldOutline>,
+}
+
+/// Summary of a selected field and its sub-selection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldOutline {
+    /// Field name
+    pub name: String,
+
+    /// Response alias, if the field is aliased
+    pub alias: Option<String>,
+
+    /// Names of the arguments passed to the field, in source order
+    pub arguments: Vec<String>,
+
+    /// Selected sub-fields, in selection order
+    pub children: Vec<FieldOutline>,
+}
+
+/// Outline every operation of a GraphQL document
+///
+/// Fragment definitions are left out, and fields selected through inline
+/// fragments are listed under the field that contains the fragment.
+/// Fragment spreads are not expanded.
+pub fn query_outline(query: &str) -> Result<Vec<OperationOutline>, String> {
+    let ctx = ASTContext::new();
+    let document = Document::parse(&ctx, strip_bom(query))
+        .map_err(|e| format!("Failed to parse GraphQL query: {}", e))?;
+
+    Ok(document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(op) => Some(OperationOutline {
+                kind: op.operation,
+                name: op.name.as_ref().map(|name| name.name.to_string()),
+                fields: outline_selection(&op.selection_set),
+            }),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Outline the fields of a selection set
+fn outline_selection(selection_set: &SelectionSet) -> Vec<FieldOutline> {
+    let mut fields = Vec::new();
+
+    for selection in selection_set.selections.iter() {
+        match selection {
+            Selection::Field(field) => fields.push(outline_field(field)),
+            Selection::InlineFragment(fragment) => {
+                fields.extend(outline_selection(&fragment.selection_set))
+            }
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+
+    fields
+}
+
+/// Outline a single field
+fn outline_field(field: &Field) -> FieldOutline {
+    FieldOutline {
+        name: field.name.to_string(),
+        alias: field.alias.map(str::to_string),
+        arguments: field
+            .arguments
+            .children
+            .iter()
+            .map(|arg| arg.name.to_string())
+            .collect(),
+        children: outline_selection(&field.selection_set),
+    }
+}
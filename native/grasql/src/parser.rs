@@ -2,20 +2,51 @@
 ///
 /// This module provides functionality for parsing GraphQL queries and
 /// extracting necessary information for SQL generation.
-use crate::cache::generate_query_id;
-use crate::extraction::{build_path_index, FieldPathExtractor};
-use crate::interning::{get_all_strings, intern_str};
-use crate::types::{GraphQLOperationKind, ParsedQueryInfo, ResolutionRequest};
-use graphql_query::ast::{ASTContext, Definition, Document, Field, ParseNode, Selection};
-use std::collections::HashMap;
+pub mod admission;
+
+use self::admission::{AdmissionError, Policy};
+use crate::cache::{
+    add_to_cache_with_request, generate_query_id, generate_query_id_with_overrides,
+    generate_query_id_with_variables, get_from_cache, get_matching_from_cache, normalize_query,
+    BYTE_ORDER_MARK,
+};
+use crate::config::{Config, MutationPrefixes, SubscriptionMode};
+use crate::encoding::{
+    encode_paths, EncodedPaths, PATH_FORMAT_VERSION, PATH_TYPE_RELATIONSHIP, PATH_TYPE_TABLE,
+};
+use crate::extraction::{
+    build_path_index, check_column_limit, extract_simple_selection, FieldPathExtractor,
+};
+use crate::interning::{
+    get_all_strings, hold_symbols, intern_str, interned_count, resolve_str, symbol_index,
+};
+use crate::naming::base_entity_name;
+use crate::telemetry::ParseSpan;
+use crate::types::{
+    BatchItem, BatchMode, CachedQueryInfo, ColumnAliases, ColumnArguments, ColumnJsonPaths,
+    ColumnUsage, DirectiveEntry, FieldArguments, FieldDirectives, FieldPath, FunctionArguments,
+    GraphQLOperationKind, InsertNode, ParsedQueryInfo, ResolutionRequest, SymbolId,
+};
+use graphql_query::ast::{
+    ASTContext, Definition, Document, Field, OperationDefinition, ParseNode, PrintNode, Selection,
+    SelectionSet, Type, Value,
+};
+#[cfg(feature = "recent-errors")]
+use once_cell::sync::Lazy;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+#[cfg(feature = "recent-errors")]
+use std::collections::VecDeque;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::mem;
 use std::sync::Arc;
+#[cfg(feature = "recent-errors")]
+use std::sync::Mutex;
 
 /// Determine the specific operation kind, including mutation type
 #[inline(always)]
 fn determine_operation_kind(
     document: &Document,
-    config: &crate::config::Config,
+    config: &Config,
 ) -> Result<GraphQLOperationKind, String> {
     // Find all operation definitions and determine the primary operation kind
     let mut has_operation = false;
@@ -27,9 +58,9 @@ fn determine_operation_kind(
 
             // If it's a mutation, determine specific type
             if let graphql_query::ast::OperationKind::Mutation = op.operation {
-                // Check if we have selections first
+                // A mutation without root fields has nothing to execute
                 if op.selection_set.selections.is_empty() {
-                    continue; // Skip empty operations
+                    return Err(EMPTY_MUTATION_ERROR.to_string());
                 }
 
                 // Look at first selection name to determine mutation type
@@ -52,8 +83,20 @@ fn determine_operation_kind(
                     }
                 }
             } else {
-                // For non-mutation operations, convert directly
-                let kind = op.operation.into();
+                // For non-mutation operations, convert directly; subscriptions
+                // follow `Config.subscription_mode`
+                let kind = match (op.operation, config.subscription_mode) {
+                    (graphql_query::ast::OperationKind::Subscription, SubscriptionMode::Reject) => {
+                        return Err(String::from(
+                            "Subscriptions are not supported; set subscription_mode to :as_query to analyze them as queries",
+                        ));
+                    }
+                    (
+                        graphql_query::ast::OperationKind::Subscription,
+                        SubscriptionMode::AsQuery,
+                    ) => GraphQLOperationKind::Query,
+                    (operation, _) => operation.into(),
+                };
 
                 // If we find a mutation, prioritize it over query/subscription
                 if matches!(
@@ -78,27 +121,115 @@ fn determine_operation_kind(
     Ok(primary_kind)
 }
 
+/// Error returned for queries with no content
+pub const EMPTY_QUERY_ERROR: &str = "Query is empty";
+
+/// Error returned for mutations that select no root fields (`mutation { }`)
+pub const EMPTY_MUTATION_ERROR: &str =
+    "Mutation has an empty selection set; select at least one mutation field";
+
+/// Describe a syntax error returned by the GraphQL parser
+///
+/// The parser reports an empty selection set as an invalid selection at the
+/// closing brace. For a mutation with no fields at all this says so plainly.
+pub(crate) fn describe_parse_error(query: &str, error: impl std::fmt::Display) -> String {
+    if is_empty_mutation(query) {
+        EMPTY_MUTATION_ERROR.to_string()
+    } else {
+        format!("Failed to parse GraphQL query: {}", error)
+    }
+}
+
+/// Check whether a query is a single mutation whose selection set is empty
+fn is_empty_mutation(query: &str) -> bool {
+    let normalized = normalize_query(query);
+    let rest = match normalized.strip_prefix("mutation") {
+        Some(rest) if rest.starts_with([' ', '{', '(']) => rest,
+        _ => return false,
+    };
+    // Only a name and variable definitions come before the first brace
+    rest.find('{')
+        .is_some_and(|open| matches!(&rest[open..], "{}" | "{ }"))
+}
+
+/// Check whether a query has no content besides whitespace, commas and comments
+#[inline(always)]
+pub fn is_blank_query(query: &str) -> bool {
+    normalize_query(query).is_empty()
+}
+
+/// Remove a leading byte order mark, which the GraphQL lexer doesn't accept
+#[inline(always)]
+pub fn strip_bom(query: &str) -> &str {
+    query.strip_prefix(BYTE_ORDER_MARK).unwrap_or(query)
+}
+
+/// Introspection root fields defined by the GraphQL specification
+const INTROSPECTION_FIELDS: [&str; 3] = ["__schema", "__type", "__typename"];
+
+/// Check whether every root field of every operation is an introspection field
+#[inline(always)]
+fn is_introspection_query(document: &Document) -> bool {
+    let mut has_root_field = false;
+
+    for definition in document.definitions.iter() {
+        if let Definition::Operation(op) = definition {
+            if op.operation != graphql_query::ast::OperationKind::Query {
+                return false;
+            }
+
+            for selection in op.selection_set.selections.iter() {
+                match selection.field() {
+                    Some(field) if INTROSPECTION_FIELDS.contains(&field.name) => {
+                        has_root_field = true;
+                    }
+                    _ => return false,
+                }
+            }
+        }
+    }
+
+    has_root_field
+}
+
 /// Parse a GraphQL query string and extract necessary information
 ///
 /// This function parses a GraphQL query string and extracts operation information
 /// such as the operation kind (query, mutation, subscription) and name.
 /// It also extracts field paths for tables and relationships needed for schema resolution.
 ///
-/// Note: This parser does not support GraphQL fragments or directives.
+/// Note: This parser does not support GraphQL fragments or directives, except in
+/// introspection queries, which are reported as `GraphQLOperationKind::Introspection`
+/// without any field paths. Field directives listed in `Config.field_directives`
+/// are accepted as annotations and reported in the resolution request.
 #[inline(always)]
-pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest), String> {
-    // Create a new AST context
-    let ctx = ASTContext::new();
-
-    // Generate query ID for caching
-    let query_id = generate_query_id(query);
+pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo<'_>, ResolutionRequest), String> {
+    parse_graphql_with_prefixes(query, None)
+}
 
-    // Parse the query using the ParseNode trait
-    let document = match Document::parse(&ctx, query) {
-        Ok(doc) => doc,
-        Err(e) => return Err(format!("Failed to parse GraphQL query: {}", e)),
-    };
+/// Parse a GraphQL query string with an optional mutation prefix override
+///
+/// Behaves like `parse_graphql`, but classifies mutations using `prefixes`
+/// where given instead of the global configuration, for this parse only.
+/// The override is reflected in the query ID, so results parsed under
+/// different prefixes never share a cache entry.
+pub fn parse_graphql_with_prefixes<'a>(
+    query: &'a str,
+    prefixes: Option<&MutationPrefixes>,
+) -> Result<(ParsedQueryInfo<'a>, ResolutionRequest), String> {
+    parse_graphql_with_overrides(query, prefixes, None)
+}
 
+/// Parse a GraphQL query string with per-call prefixes and variable values
+///
+/// Combines `parse_graphql_with_prefixes` and `parse_graphql_with_variables`
+/// on top of the global configuration. `variables` are only used, and only
+/// reflected in the query ID, when `Config.expand_variable_filters` is set.
+pub fn parse_graphql_with_overrides<'a>(
+    query: &'a str,
+    prefixes: Option<&MutationPrefixes>,
+    variables: Option<&JsonMap<String, JsonValue>>,
+) -> Result<(ParsedQueryInfo<'a>, ResolutionRequest), String> {
     // Get the config once before processing the document to avoid repeated lock acquisitions
     let config = crate::config::CONFIG
         .lock()
@@ -106,109 +237,214 @@ pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest)
         .as_ref()
         .ok_or("GraSQL not initialized".to_string())?
         .clone();
+    let config = match prefixes {
+        Some(prefixes) => prefixes.apply(&config),
+        None => config,
+    };
 
-    // Check for unsupported features: fragments and directives
-    for definition in document.definitions.iter() {
-        // Check for fragment definitions
-        if let Definition::Fragment(_) = definition {
-            return Err(String::from("GraphQL fragments are not supported"));
-        }
+    let variables = variables.filter(|_| config.expand_variable_filters);
+    let query_id = generate_query_id_with_overrides(query, prefixes, variables);
+    let (mut parsed_query_info, resolution_request) =
+        parse_with_query_id(query, &config, query_id, variables)?;
 
-        // Check for directive usage in operations
-        if let Definition::Operation(op) = definition {
-            if !op.directives.is_empty() {
-                return Err(String::from("GraphQL directives are not supported"));
-            }
+    // SQL generation has to classify mutations the same way
+    parsed_query_info.mutation_prefixes = prefixes
+        .filter(|prefixes| **prefixes != MutationPrefixes::default())
+        .cloned();
+    Ok((parsed_query_info, resolution_request))
+}
 
-            // Check for directives and fragments in the selection set
-            for selection in op.selection_set.selections.iter() {
-                match selection {
-                    // FragmentSpread is not supported
-                    Selection::FragmentSpread(_) => {
-                        return Err(String::from("GraphQL fragment spreads are not supported"));
-                    }
-                    // InlineFragment is not supported
-                    Selection::InlineFragment(_) => {
-                        return Err(String::from("GraphQL inline fragments are not supported"));
-                    }
-                    // Check if fields have directives
-                    Selection::Field(field) => {
-                        if !field.directives.is_empty() {
-                            return Err(String::from("GraphQL directives are not supported"));
-                        }
+/// Parse a GraphQL query string using an explicit configuration
+///
+/// Behaves like `parse_graphql`, but applies the limits and naming conventions
+/// from `config` instead of the global configuration.
+pub fn parse_graphql_with_config<'a>(
+    query: &'a str,
+    config: &Config,
+) -> Result<(ParsedQueryInfo<'a>, ResolutionRequest), String> {
+    parse_with_query_id(query, config, generate_query_id(query), None)
+}
 
-                        // Recursively check for directives and fragments in nested fields
-                        if let Err(e) = check_field_for_unsupported_features(field) {
-                            return Err(e);
-                        }
-                    }
-                }
-            }
-        }
+/// Parse a GraphQL query string, expanding filters passed as variables
+///
+/// With `Config.expand_variable_filters` set, a `where` given as a variable
+/// is walked using its value from `variables`, so the relationship paths
+/// inside it are extracted like those of an inline filter. A variable missing
+/// from `variables` uses the default its operation declares, if any. The
+/// variable values are then part of the query ID. Without the flag this is the same
+/// as `parse_graphql_with_config`.
+pub fn parse_graphql_with_variables<'a>(
+    query: &'a str,
+    config: &Config,
+    variables: &JsonMap<String, JsonValue>,
+) -> Result<(ParsedQueryInfo<'a>, ResolutionRequest), String> {
+    if !config.expand_variable_filters {
+        return parse_graphql_with_config(query, config);
     }
 
-    // Determine operation kind (now with specific mutation types)
-    let operation_kind = determine_operation_kind(&document, &config)?;
+    let query_id = generate_query_id_with_variables(query, variables);
+    parse_with_query_id(query, config, query_id, Some(variables))
+}
 
-    // Extract operation name
-    let mut operation_name = None;
+/// Number of parse errors kept for `recent_errors`
+#[cfg(feature = "recent-errors")]
+pub const RECENT_ERRORS_CAPACITY: usize = 16;
 
-    // Find the first operation definition
-    for definition in document.definitions.iter() {
-        if let Definition::Operation(op) = definition {
-            if let Some(name) = &op.name {
-                operation_name = Some(name.name.to_string());
-            }
-            break;
+/// Number of query characters kept with each recorded error
+#[cfg(feature = "recent-errors")]
+const RECENT_ERROR_QUERY_PREFIX: usize = 100;
+
+/// Most recent parse errors as `(query prefix, error)`, oldest first
+#[cfg(feature = "recent-errors")]
+static RECENT_ERRORS: Lazy<Mutex<VecDeque<(String, String)>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(RECENT_ERRORS_CAPACITY)));
+
+/// Return the most recent parse errors as `(query prefix, error)`, oldest first
+///
+/// Only the last `RECENT_ERRORS_CAPACITY` errors are kept. Always empty when
+/// the `recent-errors` feature is disabled.
+pub fn recent_errors() -> Vec<(String, String)> {
+    #[cfg(feature = "recent-errors")]
+    {
+        match RECENT_ERRORS.lock() {
+            Ok(errors) => errors.iter().cloned().collect(),
+            Err(poisoned) => poisoned.into_inner().iter().cloned().collect(),
         }
     }
+    #[cfg(not(feature = "recent-errors"))]
+    {
+        Vec::new()
+    }
+}
 
-    // Extract field paths and column usage
-    let mut extractor = FieldPathExtractor::new();
-    let (field_paths, column_usage) = match extractor.extract(&document) {
-        Ok(result) => result,
-        Err(e) => return Err(e),
+/// Record a parse error, evicting the oldest once the buffer is full
+#[cfg(feature = "recent-errors")]
+fn record_error(query: &str, error: &str) {
+    let prefix: String = query
+        .trim()
+        .chars()
+        .take(RECENT_ERROR_QUERY_PREFIX)
+        .collect();
+    let mut errors = match RECENT_ERRORS.lock() {
+        Ok(errors) => errors,
+        Err(poisoned) => poisoned.into_inner(),
     };
+    if errors.len() == RECENT_ERRORS_CAPACITY {
+        errors.pop_front();
+    }
+    errors.push_back((prefix, error.to_string()));
+}
 
-    // Get all interned strings and create a mapping from SymbolId to index
-    let strings = get_all_strings();
-    let mut symbol_to_index = HashMap::with_capacity(strings.len());
+/// Parse a GraphQL query string, recording `query_id` in the resolution request
+fn parse_with_query_id<'a>(
+    query: &'a str,
+    config: &Config,
+    query_id: String,
+    variables: Option<&JsonMap<String, JsonValue>>,
+) -> Result<(ParsedQueryInfo<'a>, ResolutionRequest), String> {
+    let _symbols = hold_symbols();
+    let result = parse_document(query, config, query_id, variables);
 
-    for (i, name) in strings.iter().enumerate() {
-        let symbol_id = intern_str(name);
-        symbol_to_index.insert(symbol_id, i as u32);
+    #[cfg(feature = "recent-errors")]
+    if let Err(error) = &result {
+        record_error(query, error);
     }
 
-    // Create the encoded paths, path directory, and path types arrays
-    let mut paths = Vec::new();
-    let mut path_dir = Vec::new();
-    let mut path_types = Vec::new();
+    result
+}
 
-    // Encode each field path
-    for (_path_id, path) in field_paths.iter().enumerate() {
-        // Record the current offset in the paths array
-        path_dir.push(paths.len() as u32);
+/// Parse and analyze a GraphQL document; see `parse_with_query_id`
+fn parse_document<'a>(
+    query: &'a str,
+    config: &Config,
+    query_id: String,
+    variables: Option<&JsonMap<String, JsonValue>>,
+) -> Result<(ParsedQueryInfo<'a>, ResolutionRequest), String> {
+    let _parse_span = ParseSpan::parse(&query_id);
 
-        // Add the path length
-        paths.push(path.len() as u32);
+    // Reject blank input uniformly instead of relying on the parser's message
+    if is_blank_query(query) {
+        return Err(EMPTY_QUERY_ERROR.to_string());
+    }
+    let query = strip_bom(query);
 
-        // Add each path segment as an index into the strings array
-        for &symbol_id in path.iter() {
-            let idx = symbol_to_index
-                .get(&symbol_id)
-                .copied()
-                .ok_or_else(|| format!("symbol {:?} missing from mapping", symbol_id))?;
-            paths.push(idx);
-        }
+    // Create a new AST context
+    let ctx = ASTContext::new();
 
-        // Determine if this is a table (0) or relationship (1)
-        // Heuristic: paths of length 1 are tables, longer paths are relationships
-        let path_type: u8 = if path.len() == 1 { 0 } else { 1 };
-        path_types.push(path_type);
+    // Parse the query using the ParseNode trait
+    let document_span = ParseSpan::phase("document");
+    let document = match Document::parse(&ctx, query) {
+        Ok(doc) => doc,
+        Err(e) => return Err(describe_parse_error(query, e)),
+    };
+    drop(document_span);
+
+    let extraction_span = ParseSpan::phase("extraction");
+    let DocumentAnalysis {
+        introspection,
+        operation_kind,
+        operation_name,
+        field_paths,
+        column_usage,
+        column_arguments,
+        function_args,
+        column_aliases,
+        json_paths,
+        args,
+        argument_names,
+        set_columns,
+        filter_columns,
+        directives,
+        insert_tree,
+        warnings,
+    } = analyze_document(document, config, variables).map_err(|error| error.reason)?;
+    drop(extraction_span);
+
+    let _encoding_span = ParseSpan::phase("encoding");
+
+    // Intern base entity names so they are part of the string table
+    let entity_symbols: HashMap<&FieldPath, SymbolId> = field_paths
+        .iter()
+        .filter_map(|path| {
+            let name = resolve_str(*path.last()?)?;
+            Some((path, intern_str(base_entity_name(&name, config))))
+        })
+        .collect();
+
+    // Every symbol used below is interned by now, so the snapshot holds all of
+    // them and each symbol's index into it is its key
+    let strings = get_all_strings();
+
+    // Encode each field path as string table indices
+    // Heuristic: paths of length 1 are tables, longer paths are relationships
+    let EncodedPaths {
+        paths,
+        path_dir,
+        path_types,
+    } = encode_paths(field_paths.iter().map(|path| {
+        let segments: Vec<u32> = path
+            .iter()
+            .map(|&symbol_id| symbol_index(symbol_id))
+            .collect();
+        let path_type = if path.len() == 1 {
+            PATH_TYPE_TABLE
+        } else {
+            PATH_TYPE_RELATIONSHIP
+        };
+        (segments, path_type)
+    }));
+
+    // Base entity of each path, in the same path_id order
+    let mut entities = Vec::with_capacity(field_paths.len());
+    for path in field_paths.iter() {
+        let symbol_id = entity_symbols
+            .get(path)
+            .ok_or_else(|| format!("entity for path {:?} missing from mapping", path))?;
+        entities.push(symbol_index(*symbol_id));
     }
 
     // Convert column_usage to the new cols format
-    let mut cols = Vec::new();
+    let mut cols = Vec::with_capacity(column_usage.len());
     for path in field_paths.iter() {
         // Skip paths that aren't tables (no columns)
         if path.len() != 1 {
@@ -216,23 +452,15 @@ pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest)
         }
 
         // Get the table index (first element of path)
-        let table_idx = symbol_to_index
-            .get(&path[0])
-            .copied()
-            .ok_or_else(|| format!("symbol {:?} missing from mapping", path[0]))?;
+        let table_idx = symbol_index(path[0]);
 
         // Check if there are columns for this table
         if let Some(columns) = column_usage.get(path) {
             // Convert column SymbolIds to indices
             let column_indices: Vec<u32> = columns
                 .iter()
-                .map(|&symbol_id| {
-                    symbol_to_index
-                        .get(&symbol_id)
-                        .copied()
-                        .ok_or_else(|| format!("symbol {:?} missing from mapping", symbol_id))
-                })
-                .collect::<Result<Vec<u32>, String>>()?;
+                .map(|&symbol_id| symbol_index(symbol_id))
+                .collect();
 
             // Only add if there are columns to resolve
             if !column_indices.is_empty() {
@@ -241,8 +469,18 @@ pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest)
         }
     }
 
-    // Extract operations
-    let mut ops = Vec::new();
+    let directives = encode_directives(&field_paths, &directives);
+
+    // Extract operations, one per root field
+    let root_fields = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(op) => Some(op.selection_set.selections.len()),
+            _ => None,
+        })
+        .sum();
+    let mut ops = Vec::with_capacity(root_fields);
 
     // Reuse the config we already acquired instead of locking again
     for definition in document.definitions.iter() {
@@ -250,13 +488,14 @@ pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest)
             // For each operation, add the root fields
             for selection in op.selection_set.selections.iter() {
                 if let Selection::Field(field) = selection {
-                    let field_idx = symbol_to_index
-                        .get(&intern_str(field.name))
-                        .copied()
-                        .ok_or_else(|| format!("field '{}' missing from mapping", field.name))?;
+                    let field_idx = symbol_index(intern_str(field.name));
+                    if field_idx as usize >= strings.len() {
+                        return Err(format!("field '{}' missing from mapping", field.name));
+                    }
 
                     // Determine operation type based on operation kind and field name
                     let op_type = match op.operation {
+                        _ if introspection => 5,
                         graphql_query::ast::OperationKind::Query => 0,
                         graphql_query::ast::OperationKind::Mutation => {
                             // Check field name against configured prefixes to determine specific mutation type
@@ -319,7 +558,9 @@ pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest)
         mem::transmute::<*const Document, *const Document<'static>>(ptr)
     };
 
-    // Create AST context with Arc for thread-safety
+    // Create AST context with Arc for shared ownership. ASTContext is not Sync on
+    // its own; cross-thread access is governed by CachedQueryInfo's Send/Sync impls.
+    #[allow(clippy::arc_with_non_send_sync)]
     let ctx_arc = Arc::new(ctx);
 
     // Create parsed query info with extracted data
@@ -332,25 +573,548 @@ pub fn parse_graphql(query: &str) -> Result<(ParsedQueryInfo, ResolutionRequest)
         original_query: Some(query.to_string()),
         document_ptr: Some(document_ptr),
         column_usage: Some(column_usage),
+        column_arguments: Some(column_arguments),
+        function_args: Some(function_args),
+        column_aliases: Some(column_aliases),
+        json_paths: Some(json_paths),
+        args: Some(args),
+        argument_names: config
+            .collect_argument_names
+            .then(|| argument_names.clone()),
+        set_columns: Some(set_columns),
+        filter_columns: Some(filter_columns),
+        insert_tree: Some(insert_tree),
+        mutation_prefixes: None,
+        warnings: warnings.clone(),
         _phantom: std::marker::PhantomData,
     };
 
     // Create resolution request
     let resolution_request = ResolutionRequest {
         query_id: query_id.clone(),
+        version: PATH_FORMAT_VERSION,
         strings,
         paths,
         path_dir,
         path_types,
+        entities,
         cols,
         ops,
+        directives,
+        warnings,
+        argument_names: argument_names.into_iter().collect(),
     };
+    debug_assert_eq!(resolution_request.validate(), Ok(()));
 
     Ok((parsed_query_info, resolution_request))
 }
 
+/// Encode annotation directives for the resolution request, in path_id order
+///
+/// Path ids follow the iteration order of `field_paths`, as in `encode_paths`.
+fn encode_directives(
+    field_paths: &HashSet<FieldPath>,
+    directives: &FieldDirectives,
+) -> Vec<DirectiveEntry> {
+    let mut entries = Vec::with_capacity(directives.values().map(Vec::len).sum());
+    for (path_id, path) in field_paths.iter().enumerate() {
+        for directive in directives.get(path).into_iter().flatten() {
+            let column_idx = directive
+                .column
+                .map_or(-1, |column| symbol_index(column) as i32);
+            let arguments = directive
+                .arguments
+                .iter()
+                .map(|(name, value)| (symbol_index(*name), value.clone()))
+                .collect();
+            entries.push((
+                path_id as u32,
+                column_idx,
+                symbol_index(directive.name),
+                arguments,
+            ));
+        }
+    }
+    entries
+}
+
+/// What the guards and extraction learn about a document
+struct DocumentAnalysis {
+    introspection: bool,
+    operation_kind: GraphQLOperationKind,
+    operation_name: Option<String>,
+    field_paths: HashSet<FieldPath>,
+    column_usage: ColumnUsage,
+    column_arguments: ColumnArguments,
+    function_args: FunctionArguments,
+    column_aliases: ColumnAliases,
+    json_paths: ColumnJsonPaths,
+    args: FieldArguments,
+    argument_names: BTreeSet<String>,
+    set_columns: ColumnUsage,
+    filter_columns: ColumnUsage,
+    directives: FieldDirectives,
+    insert_tree: Vec<InsertNode>,
+    warnings: Vec<String>,
+}
+
+/// Run every configured guard over a parsed document and extract its paths
+///
+/// Shared by `parse_document` and `validate_query`; everything after this
+/// point only builds the resolution request and cached info.
+fn analyze_document(
+    document: &Document,
+    config: &Config,
+    variables: Option<&JsonMap<String, JsonValue>>,
+) -> Result<DocumentAnalysis, AdmissionError> {
+    // Introspection queries are answered from schema metadata, so they bypass
+    // the SQL-oriented feature checks and field path extraction
+    let introspection = is_introspection_query(document);
+
+    if !introspection {
+        check_unsupported_features(document, config)
+            .map_err(AdmissionError::of(Policy::Features))?;
+    }
+
+    // Reject documents packed with operations before walking any of them
+    if let Some(limit) = config.max_operations {
+        let operations = document
+            .definitions
+            .iter()
+            .filter(|definition| matches!(definition, Definition::Operation(_)))
+            .count();
+        if operations > limit {
+            return Err(AdmissionError::new(
+                Policy::Operations,
+                format!(
+                    "Document defines {} operations, exceeding the limit of {}",
+                    operations, limit
+                ),
+            ));
+        }
+    }
+
+    // Reject runaway nesting before doing any extraction work
+    if !introspection {
+        let depth = query_depth(document);
+        if depth > config.max_query_depth {
+            return Err(AdmissionError::new(
+                Policy::Depth,
+                format!(
+                    "Query depth {} exceeds the maximum of {}",
+                    depth, config.max_query_depth
+                ),
+            ));
+        }
+    }
+
+    // Determine operation kind (now with specific mutation types)
+    let operation_kind = if introspection {
+        GraphQLOperationKind::Introspection
+    } else {
+        determine_operation_kind(document, config)
+            .map_err(AdmissionError::of(Policy::OperationKind))?
+    };
+
+    if config.validate_mutation_shape {
+        check_mutation_shape(document, config)
+            .map_err(AdmissionError::of(Policy::MutationShape))?;
+    }
+
+    if !introspection {
+        check_pagination_variables(document, variables)
+            .map_err(AdmissionError::of(Policy::PaginationVariables))?;
+    }
+
+    // Extract operation name
+    let mut operation_name = None;
+
+    // Find the first operation definition
+    for definition in document.definitions.iter() {
+        if let Definition::Operation(op) = definition {
+            if let Some(name) = &op.name {
+                operation_name = Some(name.name.to_string());
+            }
+            break;
+        }
+    }
+
+    // Extract field paths and column usage
+    let interned_before = interned_count();
+    let mut extractor = FieldPathExtractor::new();
+    if let Some(variables) = variables {
+        extractor.set_variables(variables.clone());
+    }
+    let (field_paths, column_usage) = if introspection {
+        // Root fields are still reported in `ops`, so their names need interning
+        for name in INTROSPECTION_FIELDS {
+            intern_str(name);
+        }
+        (HashSet::new(), HashMap::new())
+    } else if let Some(paths) = extract_simple_selection(document, config) {
+        // Plain single-table queries skip the full walk; every other
+        // extractor output is empty for them
+        paths
+    } else {
+        extractor
+            .extract_with_config(document, config)
+            .map_err(AdmissionError::of(Policy::Extraction))?
+    };
+    let column_arguments = extractor.take_column_arguments();
+    let function_args = extractor.take_function_args();
+    let column_aliases = extractor.take_column_aliases();
+    let json_paths = extractor.take_json_paths();
+    let args = extractor.take_args();
+    let argument_names = extractor.take_argument_names();
+    let (set_columns, filter_columns) = extractor.take_update_columns();
+    let directives = extractor.take_directives();
+    let insert_tree = extractor.take_insert_tree();
+    let warnings = extractor.take_warnings();
+
+    // A flood of never-seen identifiers signals a runaway or adversarial query.
+    // The count is approximate when other parses intern concurrently.
+    if let Some(limit) = config.max_new_symbols_per_query {
+        let new_symbols = interned_count().saturating_sub(interned_before);
+        if new_symbols > limit {
+            return Err(AdmissionError::new(
+                Policy::NewSymbols,
+                format!(
+                    "Query interned {} new identifiers, exceeding the limit of {}",
+                    new_symbols, limit
+                ),
+            ));
+        }
+    }
+
+    // Reject abusively wide selections before building the resolution request
+    if let Some(limit) = config.max_columns_per_table {
+        check_column_limit(&column_usage, limit)
+            .map_err(AdmissionError::of(Policy::ColumnsPerTable))?;
+    }
+
+    Ok(DocumentAnalysis {
+        introspection,
+        operation_kind,
+        operation_name,
+        field_paths,
+        column_usage,
+        column_arguments,
+        function_args,
+        column_aliases,
+        json_paths,
+        args,
+        argument_names,
+        set_columns,
+        filter_columns,
+        directives,
+        insert_tree,
+        warnings,
+    })
+}
+
+/// Check that every mutation root field has the arguments its kind requires
+///
+/// Inserts need `objects` or `object`, updates need `_set`, and deletes need
+/// `where` or the primary key argument (for `delete_*_by_pk`).
+fn check_mutation_shape(document: &Document, config: &Config) -> Result<(), String> {
+    let mutation_fields = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(op)
+                if op.operation == graphql_query::ast::OperationKind::Mutation =>
+            {
+                Some(op)
+            }
+            _ => None,
+        })
+        .flat_map(|op| op.selection_set.selections.iter())
+        .filter_map(Selection::field);
+
+    for field in mutation_fields {
+        let has = |name: &str| field.arguments.children.iter().any(|arg| arg.name == name);
+
+        let (kind, required): (&str, &[&str]) = if field.name.starts_with(&config.insert_prefix) {
+            ("Insert", &["objects", "object"])
+        } else if field.name.starts_with(&config.update_prefix) {
+            ("Update", &["_set"])
+        } else if field.name.starts_with(&config.delete_prefix) {
+            (
+                "Delete",
+                &["where", config.primary_key_argument_name.as_str()],
+            )
+        } else {
+            continue;
+        };
+
+        if !required.iter().any(|name| has(name)) {
+            let names = required
+                .iter()
+                .map(|name| format!("'{}'", name))
+                .collect::<Vec<_>>()
+                .join(" or ");
+            return Err(format!(
+                "{} mutation '{}' requires a {} argument",
+                kind, field.name, names
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Arguments that take a row count or position and must be integers
+const INTEGER_PAGINATION_ARGS: [&str; 4] = ["limit", "offset", "first", "last"];
+
+/// Check that variables used as `limit`/`offset`/`first`/`last` hold integers
+///
+/// Each such variable must be declared by its operation with type `Int` or
+/// `Int!`, and a supplied value, if any, must be a non-negative integer or null.
+/// Anything else would end up as a malformed `LIMIT`/`OFFSET`.
+fn check_pagination_variables(
+    document: &Document,
+    variables: Option<&JsonMap<String, JsonValue>>,
+) -> Result<(), String> {
+    fn check_selection(
+        selection_set: &SelectionSet,
+        op: &OperationDefinition,
+        variables: Option<&JsonMap<String, JsonValue>>,
+    ) -> Result<(), String> {
+        for field in selection_set.selections.iter().filter_map(Selection::field) {
+            for arg in field.arguments.children.iter() {
+                if let (true, Value::Variable(variable)) =
+                    (INTEGER_PAGINATION_ARGS.contains(&arg.name), &arg.value)
+                {
+                    check_pagination_variable(arg.name, variable.name, op, variables)?;
+                }
+            }
+            check_selection(&field.selection_set, op, variables)?;
+        }
+        Ok(())
+    }
+
+    for definition in document.definitions.iter() {
+        if let Definition::Operation(op) = definition {
+            check_selection(&op.selection_set, op, variables)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check one variable used as the pagination argument `arg`
+fn check_pagination_variable(
+    arg: &str,
+    name: &str,
+    op: &OperationDefinition,
+    variables: Option<&JsonMap<String, JsonValue>>,
+) -> Result<(), String> {
+    let definition = op
+        .variable_definitions
+        .children
+        .iter()
+        .find(|definition| definition.variable.name == name)
+        .ok_or_else(|| format!("Variable '${}' used in '{}' is not declared", name, arg))?;
+
+    let named = match &definition.of_type {
+        Type::NonNullType(inner) => inner,
+        of_type => of_type,
+    };
+    if !matches!(named, Type::NamedType(named) if named.name == "Int") {
+        return Err(format!(
+            "Variable '${}' used in '{}' must be an Int, not {}",
+            name,
+            arg,
+            definition.of_type.print()
+        ));
+    }
+
+    match variables.and_then(|variables| variables.get(name)) {
+        Some(value) if !value.is_null() && !value.is_i64() && !value.is_u64() => Err(format!(
+            "Variable '${}' used in '{}' must be an integer, got {}",
+            name, arg, value
+        )),
+        Some(value) if value.as_i64().is_some_and(|value| value < 0) => Err(format!(
+            "Variable '${}' used in '{}' must not be negative, got {}",
+            name, arg, value
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Longest chain of nested fields in any operation, counting root fields as 1
+fn query_depth(document: &Document) -> usize {
+    fn selection_depth(selection_set: &SelectionSet) -> usize {
+        selection_set
+            .selections
+            .iter()
+            .filter_map(Selection::field)
+            .map(|field| 1 + selection_depth(&field.selection_set))
+            .max()
+            .unwrap_or(0)
+    }
+
+    document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(op) => Some(selection_depth(&op.selection_set)),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Check a query against every configured guard without keeping the result
+///
+/// Runs the same parsing, feature checks and limits as `parse_graphql_with_config`
+/// (operation, depth, column and new-symbol limits) but builds no resolution request and
+/// never touches the cache. Meant for cheap admission control; see
+/// `admission::check` for the report behind the result.
+pub fn validate_query(query: &str, config: &Config) -> Result<(), String> {
+    admission::check(query, config)
+        .map(|_| ())
+        .map_err(|error| error.reason)
+}
+
+/// Look up a persisted query by its query ID
+///
+/// Clients using persisted queries send only the ID of a query parsed
+/// earlier. Returns None when the query is no longer cached, in which case
+/// the client has to send the full text again. The resolution request is
+/// taken from the cache, or rebuilt from the cached query text when the entry
+/// was cached without one.
+pub fn parse_persisted(
+    query_id: &str,
+) -> Option<Result<(CachedQueryInfo, ResolutionRequest), String>> {
+    let cached_query_info = get_from_cache(query_id)?;
+
+    let request = match (
+        &cached_query_info.resolution_request,
+        &cached_query_info.original_query,
+    ) {
+        (Some(request), _) => Ok(request.clone()),
+        (None, Some(query)) => {
+            let config = crate::config::CONFIG
+                .lock()
+                .map_err(|_| "Failed to acquire config lock".to_string())
+                .and_then(|cfg| cfg.clone().ok_or("GraSQL not initialized".to_string()));
+            config.and_then(|config| {
+                parse_with_query_id(query, &config, query_id.to_string(), None)
+                    .map(|(_, request)| request)
+            })
+        }
+        (None, None) => {
+            Err("Persisted query has neither a resolution request nor its text".to_string())
+        }
+    };
+
+    Some(request.map(|request| (cached_query_info, request)))
+}
+
+/// Parse a batch of GraphQL queries through the query cache
+///
+/// Each query is served from the cache when possible and parsed otherwise,
+/// looked up and parsed exactly as `parse_graphql_with_overrides` would with
+/// `prefixes` and no variables, so a batch shares cache entries with single
+/// parses. Batches take no variable values: filters passed as variables are
+/// not expanded even with `Config.expand_variable_filters` set. Each result
+/// says whether it came from the cache. The cache is only mutated for
+/// queries that parse successfully.
+///
+/// # Modes
+///
+/// - `BatchMode::PerItem` returns one result per query, in order. A failing
+///   query yields an `Err` item and does not affect the others.
+/// - `BatchMode::FailFast` stops at the first failing query and returns an
+///   error naming its index. Nothing is added to the cache in that case, so an
+///   aborted batch leaves the cache untouched.
+pub fn parse_batch(
+    queries: &[&str],
+    mode: BatchMode,
+    prefixes: Option<&MutationPrefixes>,
+) -> Result<Vec<BatchItem>, String> {
+    // Parsed queries are cached after the whole batch, with the same symbols
+    let _symbols = hold_symbols();
+    let mut results = Vec::with_capacity(queries.len());
+    let mut to_cache = Vec::new();
+
+    for (index, query) in queries.iter().enumerate() {
+        let query_id = generate_query_id_with_overrides(query, prefixes, None);
+
+        if let Some(cached_query_info) = get_matching_from_cache(&query_id, query) {
+            results.push(Ok((cached_query_info, true)));
+            continue;
+        }
+
+        match parse_graphql_with_overrides(query, prefixes, None) {
+            Ok((parsed_query_info, resolution_request)) => {
+                let mut cached_query_info: CachedQueryInfo = parsed_query_info.clone().into();
+                cached_query_info.resolution_request = Some(resolution_request.clone());
+
+                to_cache.push((query_id, parsed_query_info, resolution_request));
+                results.push(Ok((cached_query_info, false)));
+            }
+            Err(e) if mode == BatchMode::FailFast => {
+                return Err(format!("Query at index {} failed: {}", index, e));
+            }
+            Err(e) => results.push(Err(e)),
+        }
+    }
+
+    // Only successfully parsed queries reach the cache
+    for (query_id, parsed_query_info, resolution_request) in to_cache {
+        add_to_cache_with_request(&query_id, parsed_query_info, resolution_request);
+    }
+
+    Ok(results)
+}
+
+/// Reject fragments and directives, which SQL generation does not support
+///
+/// Field directives named in `Config.field_directives` are annotations and
+/// allowed; any other directive is rejected.
+fn check_unsupported_features(document: &Document, config: &Config) -> Result<(), String> {
+    for definition in document.definitions.iter() {
+        // Check for fragment definitions
+        if let Definition::Fragment(_) = definition {
+            return Err(String::from("GraphQL fragments are not supported"));
+        }
+
+        // Check for directive usage in operations
+        if let Definition::Operation(op) = definition {
+            if !op.directives.is_empty() {
+                return Err(String::from("GraphQL directives are not supported"));
+            }
+
+            // Check for directives and fragments in the selection set
+            for selection in op.selection_set.selections.iter() {
+                match selection {
+                    // FragmentSpread is not supported
+                    Selection::FragmentSpread(_) => {
+                        return Err(String::from("GraphQL fragment spreads are not supported"));
+                    }
+                    // InlineFragment is not supported
+                    Selection::InlineFragment(_) => {
+                        return Err(String::from("GraphQL inline fragments are not supported"));
+                    }
+                    // Check if fields have directives
+                    Selection::Field(field) => {
+                        check_field_directives(field, config)?;
+
+                        // Recursively check for directives and fragments in nested fields
+                        check_field_for_unsupported_features(field, config)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Recursively check fields for unsupported features like directives and fragments
-fn check_field_for_unsupported_features(field: &Field) -> Result<(), String> {
+fn check_field_for_unsupported_features(field: &Field, config: &Config) -> Result<(), String> {
     // Check for nested selections
     for selection in field.selection_set.selections.iter() {
         match selection {
@@ -364,15 +1128,27 @@ fn check_field_for_unsupported_features(field: &Field) -> Result<(), String> {
             }
             // Check if nested fields have directives
             Selection::Field(nested_field) => {
-                if !nested_field.directives.is_empty() {
-                    return Err(String::from("GraphQL directives are not supported"));
-                }
+                check_field_directives(nested_field, config)?;
 
                 // Recursively check deeper nested fields
-                check_field_for_unsupported_features(nested_field)?;
+                check_field_for_unsupported_features(nested_field, config)?;
             }
         }
     }
 
     Ok(())
 }
+
+/// Check that a field only uses the annotation directives of `Config.field_directives`
+fn check_field_directives(field: &Field, config: &Config) -> Result<(), String> {
+    for directive in field.directives.children.iter() {
+        if !config
+            .field_directives
+            .iter()
+            .any(|name| name == directive.name)
+        {
+            return Err(String::from("GraphQL directives are not supported"));
+        }
+    }
+    Ok(())
+}
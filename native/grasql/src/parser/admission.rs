@@ -0,0 +1,156 @@
+/// Query admission module
+///
+/// Runs every configured guard over a query in one call, for admission
+/// control in front of the parser. A query that passes gets a report of its
+/// shape; one that fails gets the first policy it violated along with the
+/// same reason a parse would give.
+use super::{
+    analyze_document, describe_parse_error, is_blank_query, query_depth, strip_bom,
+    EMPTY_QUERY_ERROR,
+};
+use crate::config::Config;
+use crate::types::GraphQLOperationKind;
+use graphql_query::ast::{ASTContext, Definition, Document, ParseNode, SelectionSet, Value};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A guard a query can be rejected by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// The query is blank or not valid GraphQL
+    Syntax,
+    /// Fragments and directives are not supported
+    Features,
+    /// `Config.max_operations`
+    Operations,
+    /// `Config.max_query_depth`
+    Depth,
+    /// The operation kind can't be determined, e.g. mixed mutation kinds
+    OperationKind,
+    /// `Config.validate_mutation_shape`
+    MutationShape,
+    /// Pagination arguments passed as variables must be integers
+    PaginationVariables,
+    /// Field, argument and filter checks made while extracting paths
+    Extraction,
+    /// `Config.max_new_symbols_per_query`
+    NewSymbols,
+    /// `Config.max_columns_per_table`
+    ColumnsPerTable,
+}
+
+/// The first policy a query violated, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdmissionError {
+    pub policy: Policy,
+    pub reason: String,
+}
+
+impl AdmissionError {
+    /// Create an error for a violation of `policy`
+    pub fn new(policy: Policy, reason: String) -> Self {
+        AdmissionError { policy, reason }
+    }
+
+    /// Attribute the errors of a guard to `policy`, for use with `map_err`
+    pub(crate) fn of(policy: Policy) -> impl Fn(String) -> Self {
+        move |reason| AdmissionError::new(policy, reason)
+    }
+}
+
+impl fmt::Display for AdmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+/// What the guards measured about an admitted query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdmissionReport {
+    /// Kind of the operation
+    pub operation_kind: GraphQLOperationKind,
+
+    /// Number of operations the document defines
+    pub operations: usize,
+
+    /// Deepest field nesting across operations, as limited by `max_query_depth`
+    pub depth: usize,
+
+    /// Complexity score: the number of fields selected across operations
+    pub complexity: usize,
+
+    /// Filter operators used in `where` arguments, by their GraphQL name
+    pub operators: BTreeSet<String>,
+}
+
+/// Check a query against every configured guard
+///
+/// Parses the query and runs the same checks as `parse_graphql_with_config`,
+/// without building a resolution request or touching the cache.
+pub fn check(query: &str, config: &Config) -> Result<AdmissionReport, AdmissionError> {
+    if is_blank_query(query) {
+        return Err(AdmissionError::new(
+            Policy::Syntax,
+            EMPTY_QUERY_ERROR.to_string(),
+        ));
+    }
+
+    let ctx = ASTContext::new();
+    let document = Document::parse(&ctx, strip_bom(query))
+        .map_err(|e| AdmissionError::new(Policy::Syntax, describe_parse_error(query, e)))?;
+
+    let analysis = analyze_document(document, config, None)?;
+
+    let mut report = AdmissionReport {
+        operation_kind: analysis.operation_kind,
+        operations: 0,
+        depth: query_depth(document),
+        complexity: 0,
+        operators: BTreeSet::new(),
+    };
+    for definition in document.definitions.iter() {
+        if let Definition::Operation(op) = definition {
+            report.operations += 1;
+            measure_selection_set(&op.selection_set, config, &mut report);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Count the fields of a selection set and collect the operators its filters use
+fn measure_selection_set(
+    selection_set: &SelectionSet,
+    config: &Config,
+    report: &mut AdmissionReport,
+) {
+    for field in selection_set.selections.iter().filter_map(|s| s.field()) {
+        report.complexity += 1;
+        for arg in field.arguments.children.iter() {
+            if arg.name == "where" {
+                collect_operators(&arg.value, config, &mut report.operators);
+            }
+        }
+        measure_selection_set(&field.selection_set, config, report);
+    }
+}
+
+/// Collect the configured operators used anywhere in a filter value
+fn collect_operators(value: &Value, config: &Config, operators: &mut BTreeSet<String>) {
+    match value {
+        Value::Object(obj) => {
+            for field in obj.children.iter() {
+                if config.translate_operator(field.name).is_some() {
+                    operators.insert(field.name.to_string());
+                }
+                collect_operators(&field.value, config, operators);
+            }
+        }
+        Value::List(list) => {
+            for item in list.children.iter() {
+                collect_operators(item, config, operators);
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,174 +1,2156 @@
 /// SQL generation module
 ///
-/// This module provides functionality for generating SQL from parsed GraphQL queries.
-/// It converts GraphQL operations, filters, and relationships into equivalent SQL.
-use crate::types::CachedQueryInfo;
+/// This module generates parameterized SQL from a cached parsed query and the
+/// schema information resolved for it by the Elixir side.
+///
+/// Each root field of the operation becomes its own `SELECT` statement.
+/// Relationships in the selection are emitted as correlated subqueries that
+/// build their JSON with the dialect's JSON functions, and relationship
+/// filters become semijoins: `EXISTS` for to-many relationships and an `IN`
+/// over the key columns for to-one relationships.
+///
+/// Every value in the query, literal or variable, is passed as a bind
+/// parameter, so no user input is ever interpolated into the SQL text.
+///
+/// The syntax that differs between databases goes through a `SqlDialect`,
+/// chosen with `Config.sql_dialect`.
+mod dialect;
+
+pub use self::dialect::{MySqlDialect, ParamType, PostgresDialect, SqlDialect};
+use crate::config::{Config, Dialect, NullOrdering, SqlKeywordCase};
+use crate::extraction::{is_aggregate_field, is_shorthand_operand, nested_insert_data};
+use crate::interning::{hold_symbols, intern_str};
+use crate::types::{
+    CachedQueryInfo, FieldPath, GraphQLOperationKind, RelationshipEntry, ResolutionResponse,
+};
+use graphql_query::ast::{
+    Definition, Field, ObjectValue, OperationKind, Selection, SelectionSet, Type, Value,
+};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use xxhash_rust::xxh3::xxh3_64;
 
-// For test-only function
-#[cfg(test)]
-use crate::types::ParsedQueryInfo;
+/// Relationship type codes used in `ResolutionResponse::rels`
+const BELONGS_TO: u8 = 0;
+const HAS_ONE: u8 = 1;
+const HAS_MANY: u8 = 2;
+const MANY_TO_MANY: u8 = 3;
 
-/// Generate SQL from a parsed query info
+/// A value bound to a SQL parameter
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    /// SQL NULL
+    Null,
+    /// Boolean value
+    Boolean(bool),
+    /// Integer value
+    Int(i64),
+    /// Floating point value
+    Float(f64),
+    /// String value, also used for enum values
+    String(String),
+    /// List value, bound as an array
+    List(Vec<ParamValue>),
+    /// Object value, bound as a JSON document
+    Object(Vec<(String, ParamValue)>),
+}
+
+/// A bind parameter of a generated statement
 ///
-/// This is a placeholder implementation that will be expanded with full SQL generation
-/// logic in the future. Currently, it just generates a basic SELECT statement.
-#[inline(always)]
-pub fn generate_sql(cached_query_info: &CachedQueryInfo) -> String {
-    // Placeholder SQL generation - in a real implementation this would use
-    // the parsed query structure to generate SQL based on its operations,
-    // fields, filters, etc.
-
-    // Example operator translation to demonstrate function usage
-    let example_op = "_eq";
-    let sql_op = crate::config::translate_operator(example_op);
-
-    format!(
-        "SELECT * FROM table WHERE col {} value -- Operation: {:?}",
-        sql_op, cached_query_info.operation_kind
+/// Parameters are numbered by their position: the first entry is `$1`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlParam {
+    /// A literal taken from the query text
+    Literal(ParamValue),
+    /// A GraphQL variable, looked up by name when the statement is executed
+    Variable(String),
+}
+
+/// What a generated statement does, for routing it
+///
+/// Lets the execution layer send writes to the primary and reads to
+/// replicas, and pick transaction semantics. Encoded as the atoms `:query`,
+/// `:insert`, `:update` and `:delete`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, rustler::NifUnitEnum)]
+pub enum StatementKind {
+    Query,
+    Insert,
+    Update,
+    Delete,
+}
+
+impl StatementKind {
+    /// Kind of the statement for a root field of an operation
+    ///
+    /// Mutation fields are told apart by the configured prefixes; other
+    /// mutation fields, and every field of queries and subscriptions, read.
+    pub fn of(operation: OperationKind, field_name: &str, config: &Config) -> Self {
+        match operation {
+            OperationKind::Mutation if field_name.starts_with(&config.insert_prefix) => {
+                StatementKind::Insert
+            }
+            OperationKind::Mutation if field_name.starts_with(&config.update_prefix) => {
+                StatementKind::Update
+            }
+            OperationKind::Mutation if field_name.starts_with(&config.delete_prefix) => {
+                StatementKind::Delete
+            }
+            _ => StatementKind::Query,
+        }
+    }
+
+    /// Whether the statement modifies data
+    #[inline(always)]
+    pub fn is_write(self) -> bool {
+        self != StatementKind::Query
+    }
+}
+
+/// A generated SQL statement for one root field
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlStatement {
+    /// Response key of the root field (its alias, or its name)
+    pub name: String,
+    /// What the statement does
+    pub kind: StatementKind,
+    /// The SQL text with `$n` placeholders
+    pub sql: String,
+    /// Bind parameters in placeholder order
+    pub params: Vec<SqlParam>,
+}
+
+/// A generated statement to prepare once and execute many times
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedStatement {
+    /// Statement name, identical for structurally identical queries
+    pub name: String,
+    /// The statement to prepare
+    pub statement: SqlStatement,
+    /// SQL type of each parameter in placeholder order, or None where the
+    /// database has to infer it
+    pub param_types: Vec<Option<String>>,
+}
+
+/// How the rows of a plan node are joined to the rows of its parent
+///
+/// Encoded as the atoms `:belongs_to`, `:has_one`, `:has_many` and
+/// `:many_to_many`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, rustler::NifUnitEnum)]
+pub enum PlanJoin {
+    BelongsTo,
+    HasOne,
+    HasMany,
+    ManyToMany,
+}
+
+/// A table read by a generated statement, described for plan inspection
+///
+/// Conditions and pagination are kept as they appear in the SQL, with
+/// placeholders in place of their values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanNode {
+    /// Response key of the field reading the table
+    pub name: String,
+    /// Qualified table reference, as written in the SQL
+    pub table: String,
+    /// Relationship the rows are read through, or None for the root field
+    pub join: Option<PlanJoin>,
+    /// Condition of the field's `where` argument
+    pub filter: Option<String>,
+    /// ORDER BY items, in order
+    pub order_by: Vec<String>,
+    /// LIMIT placeholder
+    pub limit: Option<String>,
+    /// OFFSET placeholder
+    pub offset: Option<String>,
+    /// Nodes of the relationships selected below this one
+    pub children: Vec<PlanNode>,
+}
+
+/// A generated statement together with a description of its plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainedStatement {
+    /// The statement, as `generate_sql` returns it
+    pub statement: SqlStatement,
+    /// The tables it reads, starting from its root field
+    pub plan: PlanNode,
+}
+
+/// Generate SQL for a cached query using the global configuration
+pub fn generate_sql(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+) -> Result<Vec<SqlStatement>, String> {
+    let config = crate::config::CONFIG
+        .lock()
+        .map_err(|_| "Failed to acquire config lock".to_string())?
+        .as_ref()
+        .ok_or("GraSQL not initialized".to_string())?
+        .clone();
+
+    generate_sql_with_config(cached_query_info, response, &config)
+}
+
+/// Generate SQL for a cached query using an explicit configuration
+///
+/// Returns one statement per root field, in document order. Introspection
+/// queries produce no statements since they are answered from the schema.
+pub fn generate_sql_with_config(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+    config: &Config,
+) -> Result<Vec<SqlStatement>, String> {
+    Ok(
+        generate_statements(cached_query_info, response, config, false)?
+            .into_iter()
+            .map(|(statement, _)| statement)
+            .collect(),
+    )
+}
+
+/// Generate SQL for a cached query with a description of each statement's plan
+///
+/// The statements are the ones `generate_sql` returns. Each plan lists the
+/// tables the statement reads, how relationships join them, and their
+/// filters, ordering and pagination, for display or cost estimation without
+/// running the query.
+pub fn explain_sql(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+) -> Result<Vec<ExplainedStatement>, String> {
+    let config = crate::config::CONFIG
+        .lock()
+        .map_err(|_| "Failed to acquire config lock".to_string())?
+        .as_ref()
+        .ok_or("GraSQL not initialized".to_string())?
+        .clone();
+
+    explain_sql_with_config(cached_query_info, response, &config)
+}
+
+/// Generate SQL with plan descriptions using an explicit configuration
+pub fn explain_sql_with_config(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+    config: &Config,
+) -> Result<Vec<ExplainedStatement>, String> {
+    Ok(
+        generate_statements(cached_query_info, response, config, true)?
+            .into_iter()
+            .map(|(statement, plan)| ExplainedStatement {
+                statement,
+                plan: plan.unwrap_or_default(),
+            })
+            .collect(),
     )
 }
 
-/// Generate SQL from a full parsed query info
-/// This version is used when we have the full ParsedQueryInfo with AST context and document
-#[cfg(test)] // Only compile this function in test mode
-pub fn generate_sql_from_full(parsed_query_info: &ParsedQueryInfo) -> String {
-    // This implementation can use the AST context and document for more advanced SQL generation
-    // For now, we delegate to the simpler implementation
-    let cached_info = CachedQueryInfo {
-        operation_kind: parsed_query_info.operation_kind,
-        operation_name: parsed_query_info.operation_name.clone(),
-        field_paths: parsed_query_info.field_paths.clone(),
-        path_index: parsed_query_info.path_index.clone(),
-        column_usage: parsed_query_info.column_usage.clone(),
-        ast_context: parsed_query_info.ast_context.clone(),
-        original_query: parsed_query_info.original_query.clone(),
-        document_ptr: parsed_query_info.document_ptr,
-        resolution_request: None,
+/// Generate the statements of a cached query, with their plans when `explain` is set
+fn generate_statements(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+    config: &Config,
+    explain: bool,
+) -> Result<Vec<(SqlStatement, Option<PlanNode>)>, String> {
+    if cached_query_info.operation_kind == GraphQLOperationKind::Introspection {
+        return Ok(Vec::new());
+    }
+
+    // Classify mutations with the prefixes the query was parsed with
+    let prefixed_config;
+    let config = match &cached_query_info.mutation_prefixes {
+        Some(prefixes) => {
+            prefixed_config = prefixes.apply(config);
+            &prefixed_config
+        }
+        None => config,
+    };
+
+    let _symbols = hold_symbols();
+    let document = cached_query_info
+        .document()
+        .ok_or("Parsed document is not available")?;
+    let path_index = cached_query_info
+        .path_index
+        .as_ref()
+        .ok_or("Path index is not available")?;
+
+    let mut statements = Vec::new();
+
+    for definition in document.definitions.iter() {
+        if let Definition::Operation(op) = definition {
+            for selection in op.selection_set.selections.iter() {
+                let field = match selection.field() {
+                    Some(field) => field,
+                    None => return Err("Root selections must be fields".to_string()),
+                };
+
+                let mut generator = SqlGenerator::new(response, path_index, config);
+                if explain {
+                    generator.plan = Some(Vec::new());
+                }
+                let sql = match op.operation {
+                    OperationKind::Mutation if field.name.starts_with(&config.insert_prefix) => {
+                        generator.insert_statement(field)?
+                    }
+                    OperationKind::Mutation
+                        if field.name.starts_with(&config.delete_prefix)
+                            && field.name.ends_with(&config.by_pk_suffix) =>
+                    {
+                        generator.delete_by_pk_statement(field)?
+                    }
+                    OperationKind::Mutation => {
+                        return Err(format!(
+                            "SQL generation for mutation '{}' is not supported",
+                            field.name
+                        ));
+                    }
+                    _ => generator.root_select(field)?,
+                };
+
+                if let Some(name) = generator.overlong_identifier.get() {
+                    return Err(format!(
+                        "Identifier '{}' in the statement for '{}' is {} bytes long, exceeding the limit of {}",
+                        name,
+                        response_key(field),
+                        name.len(),
+                        config.max_identifier_length.unwrap_or_default()
+                    ));
+                }
+
+                // Databases cap the parameters of one statement (65535 for PostgreSQL)
+                if let Some(limit) = config.max_bind_params {
+                    if generator.params.len() > limit {
+                        return Err(format!(
+                            "Statement for '{}' binds {} parameters, exceeding the limit of {}",
+                            response_key(field),
+                            generator.params.len(),
+                            limit
+                        ));
+                    }
+                }
+
+                let plan = generator
+                    .plan
+                    .and_then(|mut nodes| nodes.pop())
+                    .map(|plan| apply_plan_keyword_case(plan, config.sql_keyword_case));
+                statements.push((
+                    SqlStatement {
+                        name: response_key(field).to_string(),
+                        kind: StatementKind::of(op.operation, field.name, config),
+                        sql: apply_keyword_case(sql, config.sql_keyword_case),
+                        params: generator.params,
+                    },
+                    plan,
+                ));
+            }
+        }
+    }
+
+    Ok(statements)
+}
+
+/// Generate named statement templates for a cached query using the global configuration
+pub fn generate_prepared_sql(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+) -> Result<Vec<PreparedStatement>, String> {
+    let config = crate::config::CONFIG
+        .lock()
+        .map_err(|_| "Failed to acquire config lock".to_string())?
+        .as_ref()
+        .ok_or("GraSQL not initialized".to_string())?
+        .clone();
+
+    generate_prepared_sql_with_config(cached_query_info, response, &config)
+}
+
+/// Generate named statement templates for a cached query using an explicit configuration
+///
+/// Each statement is named after the query's structural signature together
+/// with its SQL text and parameter types, so queries differing only in their
+/// literal values share statement names, while a name never stands for two
+/// different statements. Literal parameters are typed by their value and
+/// variables by their declared GraphQL type; custom scalars and input
+/// objects are left for the database to infer.
+pub fn generate_prepared_sql_with_config(
+    cached_query_info: &CachedQueryInfo,
+    response: &ResolutionResponse,
+    config: &Config,
+) -> Result<Vec<PreparedStatement>, String> {
+    let statements = generate_sql_with_config(cached_query_info, response, config)?;
+    let dialect = sql_dialect(config.sql_dialect);
+
+    let mut variable_types = HashMap::new();
+    if let Some(document) = cached_query_info.document() {
+        for definition in document.definitions.iter() {
+            if let Definition::Operation(op) = definition {
+                for variable in op.variable_definitions.children.iter() {
+                    variable_types.insert(
+                        variable.variable.name,
+                        variable_sql_type(&variable.of_type, dialect),
+                    );
+                }
+            }
+        }
+    }
+
+    let signature = cached_query_info.structural_signature();
+    Ok(statements
+        .into_iter()
+        .map(|statement| {
+            let param_types: Vec<Option<String>> = statement
+                .params
+                .iter()
+                .map(|param| match param {
+                    SqlParam::Literal(value) => literal_sql_type(value, dialect),
+                    SqlParam::Variable(name) => {
+                        variable_types.get(name.as_str()).cloned().flatten()
+                    }
+                })
+                .collect();
+
+            let mut key = format!("{}\0{}", signature, statement.sql);
+            for param_type in &param_types {
+                key.push('\0');
+                key.push_str(param_type.as_deref().unwrap_or("?"));
+            }
+
+            PreparedStatement {
+                name: format!("grasql_{:x}", xxh3_64(key.as_bytes())),
+                statement,
+                param_types,
+            }
+        })
+        .collect())
+}
+
+/// SQL type of a parameter bound to a literal
+fn literal_sql_type(value: &ParamValue, dialect: &dyn SqlDialect) -> Option<String> {
+    let param = match value {
+        ParamValue::Null => return None,
+        ParamValue::Boolean(_) => ParamType::Boolean,
+        ParamValue::Int(_) => ParamType::BigInt,
+        ParamValue::Float(_) => ParamType::Float,
+        ParamValue::String(_) => ParamType::String,
+        // Lists need one element type; mixed or empty lists are inferred
+        ParamValue::List(items) => {
+            let mut types = items.iter().map(|item| literal_sql_type(item, dialect));
+            let first = types.next()??;
+            return types
+                .all(|item| item.as_ref() == Some(&first))
+                .then(|| dialect.list_param_type(&first));
+        }
+        ParamValue::Object(_) => ParamType::Json,
     };
+    Some(dialect.param_type(param).to_string())
+}
+
+/// SQL type of a parameter bound to a variable of the given GraphQL type
+fn variable_sql_type(of_type: &Type, dialect: &dyn SqlDialect) -> Option<String> {
+    match of_type {
+        Type::NonNullType(inner) => variable_sql_type(inner, dialect),
+        Type::ListType(inner) => {
+            variable_sql_type(inner, dialect).map(|item| dialect.list_param_type(&item))
+        }
+        Type::NamedType(named) => {
+            let param = match named.name {
+                "String" | "ID" => ParamType::String,
+                "Int" => ParamType::Integer,
+                "Float" => ParamType::Float,
+                "Boolean" => ParamType::Boolean,
+                _ => return None,
+            };
+            Some(dialect.param_type(param).to_string())
+        }
+    }
+}
+
+/// The name a field has in the GraphQL response
+#[inline(always)]
+fn response_key<'a>(field: &Field<'a>) -> &'a str {
+    field.alias.unwrap_or(field.name)
+}
+
+/// Rewrite the keywords of a generated statement in the configured case
+///
+/// The generator writes keywords in upper case and quotes every identifier,
+/// so outside quoted identifiers (in double quotes or MySQL's backticks) and
+/// string literals, letters only occur in keywords and in names that are
+/// already lower case.
+fn apply_keyword_case(sql: String, case: SqlKeywordCase) -> String {
+    match case {
+        SqlKeywordCase::Upper => sql,
+        SqlKeywordCase::Lower => {
+            let mut quote = None;
+            sql.chars()
+                .map(|c| match quote {
+                    Some(open) => {
+                        // A doubled quote closes and reopens, which leaves it quoted
+                        if c == open {
+                            quote = None;
+                        }
+                        c
+                    }
+                    None => {
+                        if matches!(c, '"' | '\'' | '`') {
+                            quote = Some(c);
+                        }
+                        c.to_ascii_lowercase()
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+/// Apply `Config.sql_keyword_case` to the SQL fragments of a plan
+fn apply_plan_keyword_case(mut plan: PlanNode, case: SqlKeywordCase) -> PlanNode {
+    plan.filter = plan.filter.map(|filter| apply_keyword_case(filter, case));
+    plan.order_by = plan
+        .order_by
+        .into_iter()
+        .map(|item| apply_keyword_case(item, case))
+        .collect();
+    plan.children = plan
+        .children
+        .into_iter()
+        .map(|child| apply_plan_keyword_case(child, case))
+        .collect();
+    plan
+}
+
+/// Aggregate functions an `order_by` may sort a relationship's column by
+const AGGREGATE_ORDER_FUNCTIONS: [&str; 10] = [
+    "avg",
+    "max",
+    "min",
+    "stddev",
+    "stddev_pop",
+    "stddev_samp",
+    "sum",
+    "var_pop",
+    "var_samp",
+    "variance",
+];
+
+/// Arguments that sort or page the rows of a relationship
+const ROW_SHAPING_ARGUMENTS: [&str; 4] = ["order_by", "limit", "offset", "distinct_on"];
+
+/// Whether an `order_by` direction is descending, and where it places NULLs
+///
+/// `null_ordering` places NULLs for `asc` and `desc`; directions naming a
+/// placement keep it.
+#[inline(always)]
+fn order_direction(direction: &str, null_ordering: NullOrdering) -> Option<(bool, NullOrdering)> {
+    Some(match direction {
+        "asc" => (false, null_ordering),
+        "desc" => (true, null_ordering),
+        "asc_nulls_first" => (false, NullOrdering::NullsFirst),
+        "asc_nulls_last" => (false, NullOrdering::NullsLast),
+        "desc_nulls_first" => (true, NullOrdering::NullsFirst),
+        "desc_nulls_last" => (true, NullOrdering::NullsLast),
+        _ => return None,
+    })
+}
+
+/// Build a JSON object projecting the selected keys of a JSON column
+///
+/// `metadata { address { city } }` becomes
+/// `json_build_object('address', json_build_object('city', t0."metadata" -> 'address' -> 'city'))`
+/// in PostgreSQL.
+fn json_projection(
+    dialect: &dyn SqlDialect,
+    expr: &str,
+    selection_set: &SelectionSet,
+) -> Result<String, String> {
+    let mut entries = Vec::new();
+
+    for selection in selection_set.selections.iter() {
+        let field = match selection {
+            Selection::Field(field) => field,
+            _ => return Err("Fragments are not supported in SQL generation".to_string()),
+        };
+
+        let value = dialect.json_field(expr, field.name);
+        let value = if field.selection_set.is_empty() {
+            value
+        } else {
+            json_projection(dialect, &value, &field.selection_set)?
+        };
+        entries.push((response_key(field).to_string(), value));
+    }
+
+    Ok(dialect.json_object(&entries))
+}
+
+/// Resolved entity a field path refers to
+enum PathEntity<'r> {
+    /// A root table, by index into `ResolutionResponse::tables`
+    Table(u32),
+    /// A relationship entry
+    Relationship(&'r RelationshipEntry),
+}
+
+/// The `SqlDialect` implementing a configured dialect
+fn sql_dialect(dialect: Dialect) -> &'static dyn SqlDialect {
+    match dialect {
+        Dialect::Postgres => &PostgresDialect,
+        Dialect::Mysql => &MySqlDialect,
+    }
+}
+
+/// State for generating a single statement
+struct SqlGenerator<'r> {
+    dialect: &'r dyn SqlDialect,
+    response: &'r ResolutionResponse,
+    path_index: &'r HashMap<FieldPath, usize>,
+    config: &'r Config,
+    params: Vec<SqlParam>,
+    next_alias: usize,
+    /// Tables read by the fields being generated, innermost last
+    tables: Vec<u32>,
+    /// First identifier longer than `Config.max_identifier_length`
+    overlong_identifier: OnceCell<String>,
+    /// Plan nodes of the fields being generated, innermost last, when explaining
+    plan: Option<Vec<PlanNode>>,
+}
+
+impl<'r> SqlGenerator<'r> {
+    fn new(
+        response: &'r ResolutionResponse,
+        path_index: &'r HashMap<FieldPath, usize>,
+        config: &'r Config,
+    ) -> Self {
+        SqlGenerator {
+            dialect: sql_dialect(config.sql_dialect),
+            response,
+            path_index,
+            config,
+            params: Vec::new(),
+            next_alias: 0,
+            tables: Vec::new(),
+            overlong_identifier: OnceCell::new(),
+            plan: None,
+        }
+    }
+
+    /// Build the statement for a root field
+    fn root_select(&mut self, field: &Field) -> Result<String, String> {
+        let mut path = FieldPath::new();
+        path.push(intern_str(field.name));
+
+        let table_idx = match self.entity(&path) {
+            Some(PathEntity::Table(table_idx)) => table_idx,
+            _ => {
+                return Err(format!(
+                    "Root field '{}' is not resolved to a table",
+                    field.name
+                ))
+            }
+        };
+
+        self.enter_table(field, table_idx, None)?;
+        let alias = self.alias();
+        let columns = self.select_list(&field.selection_set, &path, &alias)?;
+        let (distinct_on, distinct) = self.distinct_on(field, &alias)?;
+
+        // Function fields select from a call of the function they resolve to
+        let mut source = self.table_ref(table_idx)?;
+        if self
+            .config
+            .function_fields
+            .iter()
+            .any(|name| name == field.name)
+        {
+            source.push_str(&self.function_call_arguments(field)?);
+        }
+
+        let mut sql = format!(
+            "SELECT {}{} FROM {} AS {}",
+            distinct,
+            columns
+                .iter()
+                .map(|(key, expr)| format!("{} AS {}", expr, self.ident(key)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            source,
+            alias
+        );
+
+        // Joins for sorting by related columns go before the WHERE clause
+        let (joins, order) = self.ordering(field, &path, &alias, &distinct_on)?;
+        for join in &joins {
+            sql.push(' ');
+            sql.push_str(join);
+        }
+
+        // A `_by_pk` field reads the one row its key arguments name
+        let by_pk = field.name.ends_with(&self.config.by_pk_suffix);
+        let mut conditions = if by_pk {
+            self.pk_conditions(field, &alias)?
+        } else {
+            Vec::new()
+        };
+        if let Some(condition) = self.where_clause(field, &path, &alias)? {
+            conditions.push(condition);
+        }
+        if !conditions.is_empty() {
+            let condition = conditions.join(" AND ");
+            sql.push_str(" WHERE ");
+            sql.push_str(&condition);
+            self.update_plan(|node| node.filter = Some(condition));
+        }
+
+        if by_pk {
+            sql.push_str(&self.dialect.limit_offset_clause(Some("1"), None));
+            self.update_plan(|node| node.limit = Some("1".to_string()));
+        } else {
+            sql.push_str(&self.order_and_pagination(field, order)?);
+        }
+
+        self.leave_table();
+        Ok(sql)
+    }
+
+    /// Build one equality per primary key argument of a `_by_pk` field
+    ///
+    /// Every scalar argument is a key column, so a composite key conjoins
+    /// one comparison per column.
+    fn pk_conditions(&mut self, field: &Field, alias: &str) -> Result<Vec<String>, String> {
+        let mut conditions = Vec::with_capacity(field.arguments.children.len());
+        for arg in field.arguments.children.iter() {
+            if matches!(arg.value, Value::Object(_) | Value::List(_)) {
+                continue;
+            }
+            let value = self.bind_value(&arg.value)?;
+            conditions.push(format!("{}.{} = {}", alias, self.ident(arg.name), value));
+        }
+        if conditions.is_empty() {
+            return Err(format!("'{}' requires its primary key columns", field.name));
+        }
+        Ok(conditions)
+    }
+
+    /// Build the `distinct_on` expressions of a field and the clause they
+    /// form after `SELECT`, with a trailing space, or an empty string
+    fn distinct_on(&self, field: &Field, alias: &str) -> Result<(Vec<String>, String), String> {
+        let distinct_on: Vec<String> = match argument(field, "distinct_on") {
+            Some(value) => distinct_on_columns(value)?
+                .into_iter()
+                .map(|column| format!("{}.{}", alias, self.ident(column)))
+                .collect(),
+            None => Vec::new(),
+        };
+        if distinct_on.is_empty() {
+            return Ok((distinct_on, String::new()));
+        }
+        let clause = self
+            .dialect
+            .distinct_on_clause(&distinct_on)
+            .ok_or("'distinct_on' is not supported by the SQL dialect")?;
+        Ok((distinct_on, format!("{} ", clause)))
+    }
+
+    /// Collect the joins and ORDER BY items of a field's `order_by` argument
+    ///
+    /// Rows are picked per distinct combination by the leading ORDER BY
+    /// keys, so PostgreSQL requires those to be the `distinct_on` columns.
+    fn ordering(
+        &mut self,
+        field: &Field,
+        path: &FieldPath,
+        alias: &str,
+        distinct_on: &[String],
+    ) -> Result<(Vec<String>, Vec<String>), String> {
+        let mut joins = Vec::new();
+        let mut order = Vec::new();
+        if let Some(value) = argument(field, "order_by") {
+            self.order_by(value, path, alias, &mut joins, &mut order)?;
+        }
+
+        if !order.is_empty() && !distinct_leads_order(distinct_on, &order) {
+            return Err(format!(
+                "'distinct_on' columns of '{}' must be the leading 'order_by' columns",
+                field.name
+            ));
+        }
+
+        Ok((joins.into_iter().map(|(_, _, join)| join).collect(), order))
+    }
+
+    /// Build the ORDER BY and pagination clauses of a field, with a leading
+    /// space, and record them in its plan node
+    fn order_and_pagination(
+        &mut self,
+        field: &Field,
+        order: Vec<String>,
+    ) -> Result<String, String> {
+        let mut sql = String::new();
+        if !order.is_empty() {
+            sql.push_str(" ORDER BY ");
+            sql.push_str(&order.join(", "));
+        }
+
+        let mut pagination = [None, None];
+        for (slot, name) in pagination.iter_mut().zip(["limit", "offset"]) {
+            if let Some(value) = argument(field, name) {
+                *slot = Some(self.pagination_value(name, value)?);
+            }
+        }
+        let [limit, offset] = pagination;
+        sql.push_str(
+            &self
+                .dialect
+                .limit_offset_clause(limit.as_deref(), offset.as_deref()),
+        );
+        self.update_plan(|node| {
+            node.order_by = order;
+            node.limit = limit;
+            node.offset = offset;
+        });
+
+        Ok(sql)
+    }
+
+    /// Build the argument list of a function call from a function field's `args`
+    ///
+    /// Arguments are passed in named notation (`"search" => $1`), so their
+    /// order in the query doesn't matter.
+    fn function_call_arguments(&mut self, field: &Field) -> Result<String, String> {
+        let mut arguments = Vec::new();
+        match argument(field, &self.config.function_args_argument) {
+            Some(Value::Object(obj)) => {
+                for arg in &obj.children {
+                    let placeholder = self.bind_value(&arg.value)?;
+                    arguments.push(format!("{} => {}", self.ident(arg.name), placeholder));
+                }
+            }
+            Some(_) => return Err("Function arguments must be an object".to_string()),
+            None => {}
+        }
+        Ok(format!("({})", arguments.join(", ")))
+    }
+
+    /// Collect the ORDER BY items of an `order_by` argument
+    ///
+    /// A column maps to a direction (`name: asc`). A to-one relationship maps
+    /// to a nested `order_by` (`profile: { name: asc }`) and is LEFT JOINed
+    /// once per path, so rows without a related row are kept. Sorting through
+    /// a to-many relationship would multiply rows and is rejected; sorting by
+    /// one of its aggregates (`posts_agg: { count: desc }`) is allowed.
+    fn order_by(
+        &mut self,
+        value: &Value,
+        path: &FieldPath,
+        alias: &str,
+        joins: &mut Vec<(FieldPath, String, String)>,
+        order: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let object = match value {
+            Value::Object(object) => object,
+            Value::List(list) => {
+                for item in list.children.iter() {
+                    self.order_by(item, path, alias, joins, order)?;
+                }
+                return Ok(());
+            }
+            Value::Null => return Ok(()),
+            _ => return Err("'order_by' must be an object or a list of objects".to_string()),
+        };
+
+        for entry in object.children.iter() {
+            let mut child_path = path.clone();
+            child_path.push(intern_str(entry.name));
+
+            match (self.entity(&child_path), &entry.value) {
+                (_, Value::Object(functions)) if is_aggregate_field(entry.name, self.config) => {
+                    let relationship =
+                        &entry.name[..entry.name.len() - self.config.aggregate_field_suffix.len()];
+                    let mut rel_path = path.clone();
+                    rel_path.push(intern_str(relationship));
+                    let rel = match self.entity(&rel_path) {
+                        Some(PathEntity::Relationship(rel)) => rel,
+                        _ => {
+                            return Err(format!(
+                                "'order_by' on '{}' needs relationship '{}' to be resolved",
+                                entry.name, relationship
+                            ))
+                        }
+                    };
+                    self.order_by_aggregate(entry.name, functions, rel, alias, order)?;
+                }
+                (Some(PathEntity::Relationship(rel)), Value::Object(_)) => {
+                    if !matches!(rel.2, BELONGS_TO | HAS_ONE) {
+                        return Err(format!(
+                            "Cannot order by to-many relationship '{}'",
+                            entry.name
+                        ));
+                    }
+                    let join_alias = self.order_join(rel, &child_path, alias, joins)?;
+                    self.order_by(&entry.value, &child_path, &join_alias, joins, order)?;
+                }
+                (_, Value::Enum(direction)) => {
+                    let (descending, nulls) =
+                        order_direction(direction.value, self.config.null_ordering).ok_or_else(
+                            || {
+                                format!(
+                                    "Unknown order direction '{}' for '{}'",
+                                    direction.value, entry.name
+                                )
+                            },
+                        )?;
+                    let expr = format!("{}.{}", alias, self.ident(entry.name));
+                    order.push(self.dialect.order_item(&expr, descending, nulls));
+                }
+                _ => {
+                    return Err(format!(
+                        "'order_by' on '{}' must be a direction or a relationship",
+                        entry.name
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collect the ORDER BY items sorting by aggregates of a relationship
+    ///
+    /// `count: desc` sorts by the number of related rows, and
+    /// `max: { views: desc }` by an aggregate of a related column. Each item
+    /// is a correlated subquery over the relationship, so no rows are
+    /// multiplied.
+    fn order_by_aggregate(
+        &mut self,
+        name: &str,
+        functions: &ObjectValue,
+        rel: &RelationshipEntry,
+        parent_alias: &str,
+        order: &mut Vec<String>,
+    ) -> Result<(), String> {
+        for function in functions.children.iter() {
+            let mut items = Vec::new();
+            match (function.name, &function.value) {
+                ("count", Value::Enum(direction)) => items.push((None, direction)),
+                (function_name, Value::Object(columns))
+                    if AGGREGATE_ORDER_FUNCTIONS.contains(&function_name) =>
+                {
+                    for column in columns.children.iter() {
+                        let Value::Enum(direction) = &column.value else {
+                            return Err(format!(
+                                "'order_by' on '{}.{}.{}' must be a direction",
+                                name, function_name, column.name
+                            ));
+                        };
+                        items.push((Some(column.name), direction));
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown aggregate '{}' in 'order_by' on '{}'",
+                        function.name, name
+                    ))
+                }
+            }
+
+            for (column, direction) in items {
+                let (descending, nulls) =
+                    order_direction(direction.value, self.config.null_ordering).ok_or_else(
+                        || {
+                            format!(
+                                "Unknown order direction '{}' for '{}'",
+                                direction.value, name
+                            )
+                        },
+                    )?;
 
-    generate_sql(&cached_info)
-}
-
-// In test module, use the function to ensure it's not considered dead code
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//     use crate::parser::parse_graphql;
-//     use crate::types::GraphQLOperationKind;
-
-//     use std::collections::{HashMap, HashSet};
-
-//     /// Test SQL generation with a dummy query info without document
-//     #[test]
-//     fn test_basic_sql_generation() {
-//         let dummy_query_info = ParsedQueryInfo {
-//             operation_kind: GraphQLOperationKind::Query,
-//             operation_name: Some("test".to_string()),
-//             field_paths: Some(HashSet::new()),
-//             path_index: Some(HashMap::new()),
-//             ast_context: None,
-//             column_usage: None,
-//             original_query: None,
-//             document_ptr: None,
-//             _phantom: std::marker::PhantomData,
-//         };
-
-//         let sql = generate_sql_from_full(&dummy_query_info);
-//         assert!(sql.contains("SELECT"));
-//     }
-
-//     /// Test SQL generation with a real query and document access
-//     #[test]
-//     fn test_sql_generation_with_document() {
-//         // Parse a real GraphQL query
-//         let query = r#"
-//         {
-//             users {
-//                 id
-//                 name
-//                 posts {
-//                     title
-//                 }
-//             }
-//         }
-//         "#;
-
-//         let result = parse_graphql(query);
-//         assert!(result.is_ok(), "Failed to parse valid GraphQL query");
-
-//         let (parsed_query_info, _) = result.unwrap();
-
-//         // Verify document access
-//         let document = parsed_query_info.document();
-//         assert!(document.is_some(), "Document should be accessible");
-
-//         // Generate SQL using the query info with document
-//         let sql = generate_sql_from_full(&parsed_query_info);
-//         assert!(sql.contains("SELECT"));
-//     }
-
-//     /// Test SQL generation using document from cached query info
-//     #[test]
-//     fn test_sql_generation_with_cached_document() {
-//         // Parse a query and convert to cached version
-//         let query = "{ users { id name } }";
-//         let result = parse_graphql(query);
-//         assert!(result.is_ok(), "Failed to parse valid GraphQL query");
-
-//         let (parsed_query_info, _) = result.unwrap();
-//         let cached_query_info = CachedQueryInfo::from(parsed_query_info);
-
-//         // Verify document access from cached info
-//         let document = cached_query_info.document();
-//         assert!(
-//             document.is_some(),
-//             "Document should be accessible from cache"
-//         );
-
-//         // Generate SQL using cached info that contains document access
-//         let sql = generate_sql(&cached_query_info);
-//         assert!(sql.contains("SELECT"));
-//     }
-
-//     /// Test SQL generation that explicitly uses document information
-//     /// This test simulates what would happen in a real SQL generator that
-//     /// needs to access the document structure
-//     #[test]
-//     fn test_sql_generation_using_document_data() {
-//         // Parse a query with specific content
-//         let query = r#"
-//         {
-//             users(where: { active: true }) {
-//                 id
-//                 name
-//             }
-//         }
-//         "#;
-
-//         let result = parse_graphql(query);
-//         assert!(result.is_ok(), "Failed to parse valid GraphQL query");
-
-//         let (parsed_query_info, _) = result.unwrap();
-
-//         // Access the document and extract some information
-//         let document = parsed_query_info.document();
-//         assert!(document.is_some(), "Document should be accessible");
-
-//         let doc = document.unwrap();
-//         let operation = doc.operation(None).expect("Failed to get operation");
-
-//         // Verify operation has selections (fields)
-//         assert!(
-//             !operation.selection_set.is_empty(),
-//             "Selection set should have fields"
-//         );
-
-//         // In a real implementation, we would traverse the document structure
-//         // and use that to generate SQL. For this test, we just verify that
-//         // document access works properly.
-
-//         // Generate SQL with parsed query info that has document access
-//         let sql = generate_sql_from_full(&parsed_query_info);
-//         assert!(sql.contains("SELECT"));
-//     }
-// }
+                let alias = self.alias();
+                let aggregate = match column {
+                    Some(column) => {
+                        format!("{}({}.{})", function.name, alias, self.ident(column))
+                    }
+                    None => "count(*)".to_string(),
+                };
+                let mut conditions = Vec::new();
+                let from = self.relationship_source(rel, parent_alias, &alias, &mut conditions)?;
+                let expr = format!(
+                    "(SELECT {} FROM {} WHERE {})",
+                    aggregate,
+                    from,
+                    conditions.join(" AND ")
+                );
+                order.push(self.dialect.order_item(&expr, descending, nulls));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the alias a relationship is joined under for sorting, joining it if needed
+    fn order_join(
+        &mut self,
+        rel: &RelationshipEntry,
+        path: &FieldPath,
+        parent_alias: &str,
+        joins: &mut Vec<(FieldPath, String, String)>,
+    ) -> Result<String, String> {
+        if let Some((_, alias, _)) = joins.iter().find(|(joined, _, _)| joined == path) {
+            return Ok(alias.clone());
+        }
+
+        let alias = self.alias();
+        let on = self
+            .column_pairs(&rel.4, &rel.5)?
+            .iter()
+            .map(|(src, tgt)| {
+                format!(
+                    "{}.{} = {}.{}",
+                    alias,
+                    self.ident(tgt),
+                    parent_alias,
+                    self.ident(src)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let join = format!(
+            "LEFT JOIN {} AS {} ON {}",
+            self.table_ref(rel.1)?,
+            alias,
+            on
+        );
+        joins.push((path.clone(), alias.clone(), join));
+        Ok(alias)
+    }
+
+    /// Bind the value of a `limit` or `offset` argument
+    ///
+    /// Variables are bound by name and filled from the variable map.
+    fn pagination_value(&mut self, name: &str, value: &Value) -> Result<String, String> {
+        match value {
+            Value::Int(int) if int.value.starts_with('-') => Err(format!(
+                "'{}' must not be negative, got {}",
+                name, int.value
+            )),
+            Value::Int(_) | Value::Variable(_) => self.bind_value(value),
+            _ => Err(format!("'{}' expects an integer or a variable", name)),
+        }
+    }
+
+    /// Build the INSERT statement for an insert mutation root field
+    ///
+    /// Rows come from the `objects` (or `object`) argument. Columns missing
+    /// from some rows are filled with `DEFAULT`. An `on_conflict` argument
+    /// turns the statement into an upsert on the named constraint.
+    fn insert_statement(&mut self, field: &Field) -> Result<String, String> {
+        let mut path = FieldPath::new();
+        path.push(intern_str(field.name));
+
+        let table_idx = match self.entity(&path) {
+            Some(PathEntity::Table(table_idx)) => table_idx,
+            _ => {
+                return Err(format!(
+                    "Root field '{}' is not resolved to a table",
+                    field.name
+                ))
+            }
+        };
+        self.enter_table(field, table_idx, None)?;
+        let alias = self.alias();
+
+        let rows: Vec<&ObjectValue> = match argument(field, "objects").or(argument(field, "object"))
+        {
+            Some(Value::Object(object)) => vec![object],
+            Some(Value::List(list)) => list
+                .children
+                .iter()
+                .map(|item| match item {
+                    Value::Object(object) => Ok(object),
+                    _ => Err(format!("'{}' expects a list of objects", field.name)),
+                })
+                .collect::<Result<_, _>>()?,
+            Some(Value::Variable(variable)) => {
+                return Err(format!(
+                    "Insert objects passed as variable '${}' are not supported",
+                    variable.name
+                ));
+            }
+            _ => return Err(format!("'{}' requires objects to insert", field.name)),
+        };
+        if rows.is_empty() {
+            if !self.config.allow_empty_insert {
+                return Err(format!(
+                    "'{}' has an empty objects list; set allow_empty_insert to insert zero rows",
+                    field.name
+                ));
+            }
+            return self.empty_insert_statement(field, &path, table_idx, &alias);
+        }
+
+        // Columns in order of first appearance across all rows
+        let mut columns: Vec<&str> = Vec::new();
+        for row in &rows {
+            for entry in row.children.iter() {
+                if nested_insert_data(&entry.value).is_some() {
+                    return Err(format!(
+                        "Nested insert into '{}' is not supported; plan it from the insert tree",
+                        entry.name
+                    ));
+                }
+                if !columns.contains(&entry.name) {
+                    columns.push(entry.name);
+                }
+            }
+        }
+
+        let mut values = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let mut row_values = Vec::with_capacity(columns.len());
+            for column in &columns {
+                match row.children.iter().find(|entry| entry.name == *column) {
+                    Some(entry) => row_values.push(self.bind_value(&entry.value)?),
+                    None => row_values.push("DEFAULT".to_string()),
+                }
+            }
+            values.push(format!("({})", row_values.join(", ")));
+        }
+
+        let mut sql = format!(
+            "INSERT INTO {} AS {} ({}) VALUES {}",
+            self.table_ref(table_idx)?,
+            alias,
+            columns
+                .iter()
+                .map(|column| self.ident(column))
+                .collect::<Vec<_>>()
+                .join(", "),
+            values.join(", ")
+        );
+
+        if let Some(on_conflict) = argument(field, "on_conflict") {
+            sql.push(' ');
+            sql.push_str(&self.on_conflict_clause(on_conflict, &path, &alias)?);
+        }
+
+        self.push_returning(&mut sql, field, &path, &alias)?;
+        self.leave_table();
+        Ok(sql)
+    }
+
+    /// Build the DELETE statement for a `delete_*_by_pk` mutation root field
+    ///
+    /// The row is matched on its key arguments (see `pk_conditions`). The
+    /// selection is the deleted row, returned through `RETURNING`.
+    fn delete_by_pk_statement(&mut self, field: &Field) -> Result<String, String> {
+        let mut path = FieldPath::new();
+        path.push(intern_str(field.name));
+
+        let table_idx = match self.entity(&path) {
+            Some(PathEntity::Table(table_idx)) => table_idx,
+            _ => {
+                return Err(format!(
+                    "Root field '{}' is not resolved to a table",
+                    field.name
+                ))
+            }
+        };
+        self.enter_table(field, table_idx, None)?;
+        let alias = self.alias();
+
+        let conditions = self.pk_conditions(field, &alias)?;
+        let mut sql = format!(
+            "DELETE FROM {} AS {} WHERE {}",
+            self.table_ref(table_idx)?,
+            alias,
+            conditions.join(" AND ")
+        );
+
+        self.push_returning_selection(&mut sql, &field.selection_set, &path, &alias)?;
+        self.leave_table();
+        Ok(sql)
+    }
+
+    /// Build an INSERT of zero rows, for `objects: []` with `Config.allow_empty_insert`
+    ///
+    /// An empty column list is not valid SQL, so the rows come from a query
+    /// that selects none. `returning` still yields an empty list.
+    fn empty_insert_statement(
+        &mut self,
+        field: &Field,
+        path: &FieldPath,
+        table_idx: u32,
+        alias: &str,
+    ) -> Result<String, String> {
+        let table = self.table_ref(table_idx)?;
+        let mut sql = format!(
+            "INSERT INTO {} AS {} SELECT * FROM {} WHERE {}",
+            table,
+            alias,
+            table,
+            self.dialect.bool_literal(false)
+        );
+        self.push_returning(&mut sql, field, path, alias)?;
+        Ok(sql)
+    }
+
+    /// Build the `ON CONFLICT` clause of an upsert
+    ///
+    /// The constraint is emitted by name; it is not a column and is never
+    /// resolved. Listed `update_columns` are overwritten from the proposed
+    /// row, and an empty list means the conflicting row is left unchanged.
+    fn on_conflict_clause(
+        &mut self,
+        value: &Value,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<String, String> {
+        let on_conflict = match value {
+            Value::Object(object) => object,
+            _ => return Err("'on_conflict' must be an object".to_string()),
+        };
+        let entry = |name: &str| {
+            on_conflict
+                .children
+                .iter()
+                .find(|entry| entry.name == name)
+                .map(|entry| &entry.value)
+        };
+
+        let constraint = match entry("constraint") {
+            Some(Value::Enum(constraint)) => constraint.value,
+            Some(Value::String(constraint)) => constraint.value,
+            _ => return Err("'on_conflict' requires a constraint name".to_string()),
+        };
+
+        let update_columns: Vec<&str> = match entry("update_columns") {
+            Some(Value::List(list)) => list
+                .children
+                .iter()
+                .map(name_value)
+                .collect::<Result<_, _>>()?,
+            Some(Value::Null) | None => Vec::new(),
+            Some(value) => vec![name_value(value)?],
+        };
+
+        let update_columns: Vec<String> = update_columns
+            .iter()
+            .map(|column| self.ident(column))
+            .collect();
+        let filter = match entry("where") {
+            Some(Value::Object(filter)) if !update_columns.is_empty() => {
+                self.filter_object(filter, path, alias)?
+            }
+            _ => None,
+        };
+
+        self.dialect
+            .on_conflict_clause(&self.ident(constraint), &update_columns, filter.as_deref())
+    }
+
+    /// Append a `RETURNING` clause for the mutation's `returning` selection
+    fn push_returning(
+        &mut self,
+        sql: &mut String,
+        field: &Field,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<(), String> {
+        let returning = field
+            .selection_set
+            .selections
+            .iter()
+            .filter_map(Selection::field)
+            .find(|child| child.name == "returning");
+
+        if let Some(returning) = returning {
+            let mut returning_path = path.clone();
+            returning_path.push(intern_str(returning.name));
+
+            self.push_returning_selection(sql, &returning.selection_set, &returning_path, alias)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return the rows a mutation writes with the columns of `selection_set`
+    ///
+    /// Appends a `RETURNING` clause, or with `Config.mutation_returning_cte`
+    /// wraps the mutation in a `mutation` CTE returning whole rows and
+    /// selects from that instead, so relationship subqueries join off the
+    /// written rows. Nothing is returned for an empty selection.
+    fn push_returning_selection(
+        &mut self,
+        sql: &mut String,
+        selection_set: &SelectionSet,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<(), String> {
+        if !selection_set.is_empty() && !self.dialect.supports_returning() {
+            return Err("'returning' is not supported by the SQL dialect".to_string());
+        }
+
+        if !self.config.mutation_returning_cte {
+            let columns = self.select_list(selection_set, path, alias)?;
+            if !columns.is_empty() {
+                sql.push_str(" RETURNING ");
+                sql.push_str(&self.select_items(&columns));
+            }
+            return Ok(());
+        }
+
+        let cte_alias = self.alias();
+        let columns = self.select_list(selection_set, path, &cte_alias)?;
+        if !columns.is_empty() {
+            *sql = format!(
+                "WITH mutation AS ({} RETURNING {}.*) SELECT {} FROM mutation AS {}",
+                sql,
+                alias,
+                self.select_items(&columns),
+                cte_alias
+            );
+        }
+        Ok(())
+    }
+
+    /// Join `(response key, SQL expression)` pairs into `expr AS key` items
+    fn select_items(&self, columns: &[(String, String)]) -> String {
+        columns
+            .iter()
+            .map(|(key, expr)| format!("{} AS {}", expr, self.ident(key)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Build `(response key, SQL expression)` pairs for a selection set
+    fn select_list(
+        &mut self,
+        selection_set: &SelectionSet,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<Vec<(String, String)>, String> {
+        let mut columns = Vec::new();
+
+        for selection in selection_set.selections.iter() {
+            let field = match selection {
+                Selection::Field(field) => field,
+                _ => return Err("Fragments are not supported in SQL generation".to_string()),
+            };
+
+            let key = response_key(field).to_string();
+
+            if field.name == "__typename" {
+                let typename = self.typename(path)?;
+                let placeholder = self.bind(SqlParam::Literal(ParamValue::String(typename)));
+                columns.push((key, placeholder));
+                continue;
+            }
+
+            let mut child_path = path.clone();
+            child_path.push(intern_str(field.name));
+
+            match self.entity(&child_path) {
+                Some(PathEntity::Relationship(rel)) => {
+                    let subquery = self.relationship_select(field, &child_path, rel, alias)?;
+                    columns.push((key, subquery));
+                }
+                _ if !field.arguments.is_empty() => {
+                    return Err(format!(
+                        "Arguments on column '{}' are not supported in SQL generation",
+                        key
+                    ));
+                }
+                _ if field.selection_set.is_empty() => {
+                    columns.push((key, format!("{}.{}", alias, self.ident(field.name))));
+                }
+                _ if self
+                    .config
+                    .scalar_fields
+                    .iter()
+                    .any(|name| name == field.name) =>
+                {
+                    let column = format!("{}.{}", alias, self.ident(field.name));
+                    let projection = json_projection(self.dialect, &column, &field.selection_set)?;
+                    columns.push((key, projection));
+                }
+                _ => {
+                    return Err(format!(
+                        "Field '{}' is not resolved to a relationship",
+                        field.name
+                    ));
+                }
+            }
+        }
+
+        if self.config.always_include_pk {
+            self.push_primary_key(&mut columns, path, alias)?;
+        }
+
+        Ok(columns)
+    }
+
+    /// Append the table's primary key columns the selection left out
+    ///
+    /// Added columns use their own name as response key; a key already taken
+    /// by another selection is left alone.
+    fn push_primary_key(
+        &self,
+        columns: &mut Vec<(String, String)>,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<(), String> {
+        let table_idx = self.table_for_path(path)?;
+        let Some(primary_key) = self.response.primary_key(table_idx) else {
+            return Ok(());
+        };
+
+        for &column_idx in primary_key {
+            let column = self.string(column_idx)?;
+            let expr = format!("{}.{}", alias, self.ident(column));
+            if columns.iter().any(|(key, e)| *e == expr || key == column) {
+                continue;
+            }
+            columns.push((column.to_string(), expr));
+        }
+
+        Ok(())
+    }
+
+    /// Build the correlated JSON subquery for a selected relationship
+    fn relationship_select(
+        &mut self,
+        field: &Field,
+        path: &FieldPath,
+        rel: &RelationshipEntry,
+        parent_alias: &str,
+    ) -> Result<String, String> {
+        let join = match rel.2 {
+            BELONGS_TO => PlanJoin::BelongsTo,
+            HAS_ONE => PlanJoin::HasOne,
+            HAS_MANY => PlanJoin::HasMany,
+            _ => PlanJoin::ManyToMany,
+        };
+        self.enter_table(field, rel.1, Some(join))?;
+
+        // Sorting and paging pick rows of a to-many relationship; a to-one
+        // relationship has a single row to pick
+        let to_many = matches!(rel.2, HAS_MANY | MANY_TO_MANY);
+        let shaping = ROW_SHAPING_ARGUMENTS
+            .into_iter()
+            .find(|name| argument(field, name).is_some());
+        if let (false, Some(name)) = (to_many, shaping) {
+            return Err(format!(
+                "'{}' is not supported on to-one relationship '{}'",
+                name, field.name
+            ));
+        }
+
+        let alias = self.alias();
+        let columns = self.select_list(&field.selection_set, path, &alias)?;
+
+        let object = self.dialect.json_object(&columns);
+
+        let mut conditions = vec![];
+        let from = self.relationship_source(rel, parent_alias, &alias, &mut conditions)?;
+
+        if shaping.is_none() {
+            if let Some(condition) = self.where_clause(field, path, &alias)? {
+                self.update_plan(|node| node.filter = Some(condition.clone()));
+                conditions.push(condition);
+            }
+
+            self.leave_table();
+            let projection = if to_many {
+                self.dialect.json_agg(&object)
+            } else {
+                object
+            };
+            return Ok(format!(
+                "(SELECT {} FROM {} WHERE {})",
+                projection,
+                from,
+                conditions.join(" AND ")
+            ));
+        }
+
+        // Rows are sorted and paged in an inner SELECT before they are
+        // aggregated, which keeps their order
+        let (distinct_on, distinct) = self.distinct_on(field, &alias)?;
+        let (joins, order) = self.ordering(field, path, &alias, &distinct_on)?;
+        if let Some(condition) = self.where_clause(field, path, &alias)? {
+            self.update_plan(|node| node.filter = Some(condition.clone()));
+            conditions.push(condition);
+        }
+        let mut rows = format!("SELECT {}{} AS item FROM {}", distinct, object, from);
+        for join in &joins {
+            rows.push(' ');
+            rows.push_str(join);
+        }
+        rows.push_str(" WHERE ");
+        rows.push_str(&conditions.join(" AND "));
+        rows.push_str(&self.order_and_pagination(field, order)?);
+
+        self.leave_table();
+        let rows_alias = self.alias();
+        Ok(format!(
+            "(SELECT {} FROM ({}) AS {})",
+            self.dialect.json_agg(&format!("{}.item", rows_alias)),
+            rows,
+            rows_alias
+        ))
+    }
+
+    /// Build the FROM clause joining a relationship's target to its parent
+    ///
+    /// Pushes the correlation conditions onto `conditions` and returns the
+    /// FROM clause, which includes the join table for many-to-many
+    /// relationships.
+    fn relationship_source(
+        &mut self,
+        rel: &RelationshipEntry,
+        parent_alias: &str,
+        alias: &str,
+        conditions: &mut Vec<String>,
+    ) -> Result<String, String> {
+        let (_, target_idx, type_code, join_idx, src_cols, tgt_cols) = rel;
+        let target = format!("{} AS {}", self.table_ref(*target_idx)?, alias);
+
+        if *type_code != MANY_TO_MANY {
+            for (src, tgt) in self.column_pairs(src_cols, tgt_cols)? {
+                conditions.push(format!(
+                    "{}.{} = {}.{}",
+                    alias,
+                    self.ident(&tgt),
+                    parent_alias,
+                    self.ident(&src)
+                ));
+            }
+            return Ok(target);
+        }
+
+        let join = usize::try_from(*join_idx)
+            .ok()
+            .and_then(|idx| self.response.joins.get(idx))
+            .ok_or("Many-to-many relationship is missing its join table")?;
+        let join_alias = self.alias();
+
+        let mut on = Vec::new();
+        for (join_col, tgt) in self.column_pairs(&join.3, tgt_cols)? {
+            on.push(format!(
+                "{}.{} = {}.{}",
+                alias,
+                self.ident(&tgt),
+                join_alias,
+                self.ident(&join_col)
+            ));
+        }
+        for (src, join_col) in self.column_pairs(src_cols, &join.2)? {
+            conditions.push(format!(
+                "{}.{} = {}.{}",
+                join_alias,
+                self.ident(&join_col),
+                parent_alias,
+                self.ident(&src)
+            ));
+        }
+
+        Ok(format!(
+            "{}.{} AS {} JOIN {} ON {}",
+            self.ident(self.string(join.0)?),
+            self.ident(self.string(join.1)?),
+            join_alias,
+            target,
+            on.join(" AND ")
+        ))
+    }
+
+    /// Build the condition for a field's `where` argument, if it has one
+    fn where_clause(
+        &mut self,
+        field: &Field,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<Option<String>, String> {
+        match argument(field, "where") {
+            Some(Value::Object(object)) => self.filter_object(object, path, alias),
+            Some(Value::Null) | None => Ok(None),
+            Some(_) => Err(format!("'where' on '{}' must be an object", field.name)),
+        }
+    }
+
+    /// Build the conjunction of every entry in a filter object
+    fn filter_object(
+        &mut self,
+        object: &ObjectValue,
+        path: &FieldPath,
+        alias: &str,
+    ) -> Result<Option<String>, String> {
+        let mut conditions = Vec::new();
+
+        for entry in object.children.iter() {
+            let condition = match self.config.translate_operator(entry.name) {
+                Some("AND") => self.filter_list(&entry.value, path, alias, " AND ")?,
+                Some("OR") => self.filter_list(&entry.value, path, alias, " OR ")?,
+                Some("NOT") => match &entry.value {
+                    Value::Object(inner) => self
+                        .filter_object(inner, path, alias)?
+                        .map(|condition| format!("NOT ({})", condition)),
+                    _ => return Err(format!("'{}' expects an object", entry.name)),
+                },
+                Some(_) => {
+                    return Err(format!(
+                        "Operator '{}' must be applied to a column",
+                        entry.name
+                    ));
+                }
+                // Extraction ignores unknown operators with a warning rather
+                // than reading them as columns, and so does generation
+                None if entry.name.starts_with('_') => None,
+                None => {
+                    let mut child_path = path.clone();
+                    child_path.push(intern_str(entry.name));
+
+                    match (self.entity(&child_path), &entry.value) {
+                        (Some(PathEntity::Relationship(rel)), Value::Object(inner)) => {
+                            Some(self.relationship_filter(inner, &child_path, rel, alias)?)
+                        }
+                        (_, Value::Object(operators)) => {
+                            self.column_filter(entry.name, operators, alias)?
+                        }
+                        // `active: true` is shorthand for `active: { _eq: true }`
+                        (_, value) if is_shorthand_operand(value) => {
+                            let target = format!("{}.{}", alias, self.ident(entry.name));
+                            Some(match value {
+                                Value::Null => format!("{} IS NULL", target),
+                                _ => format!("{} = {}", target, self.bind_value(value)?),
+                            })
+                        }
+                        _ => {
+                            return Err(format!(
+                                "Filter on '{}' must be an object of operators",
+                                entry.name
+                            ));
+                        }
+                    }
+                }
+            };
+
+            conditions.extend(condition);
+        }
+
+        Ok(match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(conditions.join(" AND ")),
+        })
+    }
+
+    /// Combine the filters of an `_and`/`_or` list with `separator`
+    fn filter_list(
+        &mut self,
+        value: &Value,
+        path: &FieldPath,
+        alias: &str,
+        separator: &str,
+    ) -> Result<Option<String>, String> {
+        let objects: Vec<&ObjectValue> = match value {
+            Value::List(list) => list
+                .children
+                .iter()
+                .map(|item| match item {
+                    Value::Object(object) => Ok(object),
+                    _ => Err("Logical operators expect a list of objects".to_string()),
+                })
+                .collect::<Result<_, _>>()?,
+            Value::Object(object) => vec![object],
+            _ => return Err("Logical operators expect a list of objects".to_string()),
+        };
+
+        let mut conditions = Vec::new();
+        for object in objects {
+            if let Some(condition) = self.filter_object(object, path, alias)? {
+                conditions.push(format!("({})", condition));
+            }
+        }
+
+        Ok(match conditions.len() {
+            0 => None,
+            _ => Some(format!("({})", conditions.join(separator))),
+        })
+    }
+
+    /// Build the semijoin for a filter on a relationship
+    ///
+    /// To-many relationships use a correlated `EXISTS`. To-one relationships
+    /// compare the parent's key columns with the matching target keys.
+    fn relationship_filter(
+        &mut self,
+        filter: &ObjectValue,
+        path: &FieldPath,
+        rel: &RelationshipEntry,
+        parent_alias: &str,
+    ) -> Result<String, String> {
+        let alias = self.alias();
+        let inner = self.filter_object(filter, path, &alias)?;
+
+        if matches!(rel.2, BELONGS_TO | HAS_ONE) {
+            let pairs = self.column_pairs(&rel.4, &rel.5)?;
+            let parent_keys: Vec<String> = pairs
+                .iter()
+                .map(|(src, _)| format!("{}.{}", parent_alias, self.ident(src)))
+                .collect();
+            let target_keys: Vec<String> = pairs
+                .iter()
+                .map(|(_, tgt)| format!("{}.{}", alias, self.ident(tgt)))
+                .collect();
+
+            let mut sql = format!(
+                "({}) IN (SELECT {} FROM {} AS {}",
+                parent_keys.join(", "),
+                target_keys.join(", "),
+                self.table_ref(rel.1)?,
+                alias
+            );
+            if let Some(inner) = inner {
+                sql.push_str(" WHERE ");
+                sql.push_str(&inner);
+            }
+            sql.push(')');
+            return Ok(sql);
+        }
+
+        let mut conditions = Vec::new();
+        let from = self.relationship_source(rel, parent_alias, &alias, &mut conditions)?;
+        conditions.extend(inner);
+
+        Ok(format!(
+            "EXISTS (SELECT 1 FROM {} WHERE {})",
+            from,
+            conditions.join(" AND ")
+        ))
+    }
+
+    /// Build the comparisons applied to a single column
+    fn column_filter(
+        &mut self,
+        column: &str,
+        operators: &ObjectValue,
+        alias: &str,
+    ) -> Result<Option<String>, String> {
+        let target = format!("{}.{}", alias, self.ident(column));
+        self.comparisons(column, &target, operators)
+    }
+
+    /// Build the comparisons applied to `target`, an expression over `column`
+    fn comparisons(
+        &mut self,
+        column: &str,
+        target: &str,
+        operators: &ObjectValue,
+    ) -> Result<Option<String>, String> {
+        let mut conditions = Vec::new();
+
+        for entry in operators.children.iter() {
+            let sql_op = self
+                .config
+                .translate_operator(entry.name)
+                .ok_or_else(|| format!("Unknown operator '{}' on '{}'", entry.name, column))?;
+
+            let condition = match sql_op {
+                "IS NULL" | "IS JSON" => {
+                    let negate = match &entry.value {
+                        Value::Boolean(value) => !value.value,
+                        _ => return Err(format!("'{}' expects a boolean", entry.name)),
+                    };
+                    match (sql_op, negate) {
+                        ("IS NULL", false) => format!("{} IS NULL", target),
+                        ("IS NULL", true) => format!("{} IS NOT NULL", target),
+                        (_, false) => format!("{} IS JSON", target),
+                        (_, true) => format!("{} IS NOT JSON", target),
+                    }
+                }
+                "IN" | "NOT IN" => self.membership(target, sql_op, &entry.value)?,
+                "CAST" => match self.cast(column, target, &entry.value)? {
+                    Some(condition) => condition,
+                    None => continue,
+                },
+                "AND" | "OR" | "NOT" => {
+                    return Err(format!(
+                        "Logical operator '{}' cannot be applied to column '{}'",
+                        entry.name, column
+                    ));
+                }
+                // A literal null is a real comparison, not an absent filter;
+                // `= NULL` never matches in SQL, so equality becomes `IS NULL`
+                "=" | "<>" if matches!(entry.value, Value::Null) => {
+                    if sql_op == "=" {
+                        format!("{} IS NULL", target)
+                    } else {
+                        format!("{} IS NOT NULL", target)
+                    }
+                }
+                _ if matches!(entry.value, Value::Null) => {
+                    return Err(format!(
+                        "'{}' on '{}' cannot compare against null",
+                        entry.name, column
+                    ));
+                }
+                _ => {
+                    let placeholder = self.bind_value(&entry.value)?;
+                    format!("{} {} {}", target, sql_op, placeholder)
+                }
+            };
+
+            conditions.push(condition);
+        }
+
+        Ok(match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(conditions.join(" AND ")),
+        })
+    }
+
+    /// Build the comparisons of a `_cast`, keyed by the type to cast to
+    ///
+    /// `{ _cast: { String: { _eq: "1" } } }` compares `CAST(column AS text)`.
+    fn cast(
+        &mut self,
+        column: &str,
+        target: &str,
+        value: &Value,
+    ) -> Result<Option<String>, String> {
+        let types = match value {
+            Value::Object(types) => types,
+            _ => {
+                return Err(format!(
+                    "'_cast' on '{}' expects an object of types",
+                    column
+                ))
+            }
+        };
+
+        let mut conditions = Vec::new();
+        for entry in types.children.iter() {
+            let operators = match &entry.value {
+                Value::Object(operators) => operators,
+                _ => {
+                    return Err(format!(
+                        "Cast of '{}' to '{}' expects an object of operators",
+                        column, entry.name
+                    ))
+                }
+            };
+            let cast_target = format!("CAST({} AS {})", target, self.dialect.cast_type(entry.name));
+            conditions.extend(self.comparisons(column, &cast_target, operators)?);
+        }
+
+        Ok(match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(conditions.join(" AND ")),
+        })
+    }
+
+    /// Build an `IN`/`NOT IN` comparison
+    ///
+    /// Literal lists bind one parameter per element. A variable is bound as
+    /// a single array parameter and compared through the dialect.
+    fn membership(&mut self, target: &str, sql_op: &str, value: &Value) -> Result<String, String> {
+        match value {
+            Value::List(list) if list.children.is_empty() => {
+                Ok(self.dialect.bool_literal(sql_op != "IN").to_string())
+            }
+            Value::List(list) => {
+                let placeholders = list
+                    .children
+                    .iter()
+                    .map(|item| self.bind_value(item))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!(
+                    "{} {} ({})",
+                    target,
+                    sql_op,
+                    placeholders.join(", ")
+                ))
+            }
+            Value::Variable(_) => {
+                let placeholder = self.bind_value(value)?;
+                Ok(self
+                    .dialect
+                    .array_membership(target, &placeholder, sql_op != "IN"))
+            }
+            _ => Err(format!("'{}' expects a list", sql_op)),
+        }
+    }
+
+    /// Bind a GraphQL value and return its placeholder
+    fn bind_value(&mut self, value: &Value) -> Result<String, String> {
+        let param = match value {
+            Value::Variable(variable) => SqlParam::Variable(variable.name.to_string()),
+            _ => SqlParam::Literal(literal(value)?),
+        };
+        Ok(self.bind(param))
+    }
+
+    /// Start generating a field reading `table_idx`
+    ///
+    /// Enforces `Config.max_relationship_chain` on the tables read along the
+    /// path to the field, and starts the field's plan node when explaining.
+    fn enter_table(
+        &mut self,
+        field: &Field,
+        table_idx: u32,
+        join: Option<PlanJoin>,
+    ) -> Result<(), String> {
+        self.tables.push(table_idx);
+        if let Some(limit) = self.config.max_relationship_chain {
+            let count = self.tables.iter().filter(|&&idx| idx == table_idx).count();
+            if count > limit {
+                return Err(format!(
+                    "Field '{}' reads table '{}' {} times along one path, exceeding the limit of {}",
+                    response_key(field),
+                    self.string(self.table(table_idx)?.1)?,
+                    count,
+                    limit
+                ));
+            }
+        }
+
+        if self.plan.is_some() {
+            let node = PlanNode {
+                name: response_key(field).to_string(),
+                table: self.table_ref(table_idx)?,
+                join,
+                ..PlanNode::default()
+            };
+            if let Some(nodes) = self.plan.as_mut() {
+                nodes.push(node);
+            }
+        }
+        Ok(())
+    }
+
+    /// Update the plan node of the field being generated, when explaining
+    #[inline(always)]
+    fn update_plan(&mut self, update: impl FnOnce(&mut PlanNode)) {
+        if let Some(node) = self.plan.as_mut().and_then(|nodes| nodes.last_mut()) {
+            update(node);
+        }
+    }
+
+    /// Finish generating the current field, attaching its plan node to its parent
+    ///
+    /// The root field's plan node stays on the stack for the caller to take.
+    fn leave_table(&mut self) {
+        self.tables.pop();
+        if let Some(nodes) = self.plan.as_mut().filter(|nodes| nodes.len() > 1) {
+            if let Some(node) = nodes.pop() {
+                if let Some(parent) = nodes.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+        }
+    }
+
+    /// Add a parameter and return its placeholder
+    #[inline(always)]
+    fn bind(&mut self, param: SqlParam) -> String {
+        self.params.push(param);
+        self.dialect.placeholder(self.params.len())
+    }
+
+    /// Write an identifier, quoted as `Config.quote_all_identifiers` and the dialect require
+    ///
+    /// Names over `Config.max_identifier_length` are remembered, and fail
+    /// the statement once it is generated.
+    #[inline(always)]
+    fn ident(&self, name: &str) -> String {
+        if self
+            .config
+            .max_identifier_length
+            .is_some_and(|limit| name.len() > limit)
+        {
+            let _ = self.overlong_identifier.set(name.to_string());
+        }
+        self.dialect.ident(name, self.config.quote_all_identifiers)
+    }
+
+    /// Allocate a fresh table alias
+    #[inline(always)]
+    fn alias(&mut self) -> String {
+        let alias = format!("t{}", self.next_alias);
+        self.next_alias += 1;
+        alias
+    }
+
+    /// Look up what a field path was resolved to
+    fn entity(&self, path: &FieldPath) -> Option<PathEntity<'r>> {
+        let path_id = *self.path_index.get(path)?;
+        match self.response.path_map.get(path_id)? {
+            (0, table_idx) => Some(PathEntity::Table(*table_idx)),
+            (1, rel_idx) => self
+                .response
+                .rels
+                .get(*rel_idx as usize)
+                .map(PathEntity::Relationship),
+            _ => None,
+        }
+    }
+
+    /// Table index of the rows at a field path
+    fn table_for_path(&self, path: &FieldPath) -> Result<u32, String> {
+        match self.entity(path) {
+            Some(PathEntity::Table(table_idx)) => Ok(table_idx),
+            Some(PathEntity::Relationship(rel)) => Ok(rel.1),
+            None => Err("Field path is not resolved".to_string()),
+        }
+    }
+
+    /// GraphQL type name of the rows at a field path
+    fn typename(&self, path: &FieldPath) -> Result<String, String> {
+        let table_idx = self.table_for_path(path)?;
+        let table = self.table(table_idx)?;
+        Ok(self.string(table.2)?.to_string())
+    }
+
+    /// Qualified, quoted reference to a table
+    fn table_ref(&self, table_idx: u32) -> Result<String, String> {
+        let table = self.table(table_idx)?;
+        Ok(format!(
+            "{}.{}",
+            self.ident(self.string(table.0)?),
+            self.ident(self.string(table.1)?)
+        ))
+    }
+
+    #[inline(always)]
+    fn table(&self, table_idx: u32) -> Result<&'r (u32, u32, u32), String> {
+        self.response
+            .tables
+            .get(table_idx as usize)
+            .ok_or_else(|| format!("Table index {} out of range", table_idx))
+    }
+
+    #[inline(always)]
+    fn string(&self, idx: u32) -> Result<&'r str, String> {
+        self.response
+            .strings
+            .get(idx as usize)
+            .map(String::as_str)
+            .ok_or_else(|| format!("String index {} out of range", idx))
+    }
+
+    /// Resolve matching source and target key column names
+    fn column_pairs(&self, left: &[u32], right: &[u32]) -> Result<Vec<(String, String)>, String> {
+        if left.len() != right.len() || left.is_empty() {
+            return Err("Relationship key columns do not match".to_string());
+        }
+
+        left.iter()
+            .zip(right.iter())
+            .map(|(&l, &r)| Ok((self.string(l)?.to_string(), self.string(r)?.to_string())))
+            .collect()
+    }
+}
+
+/// Columns of a `distinct_on` argument: one column, or a list of them
+///
+/// Columns are enum values, as in `distinct_on: [city, name]`; repeated
+/// columns are kept once.
+fn distinct_on_columns<'v>(value: &Value<'v>) -> Result<Vec<&'v str>, String> {
+    let items = match value {
+        Value::List(list) => list.children.iter().collect(),
+        Value::Null => Vec::new(),
+        value => vec![value],
+    };
+
+    let mut columns = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Value::Enum(column) if !columns.contains(&column.value) => columns.push(column.value),
+            Value::Enum(_) => {}
+            _ => return Err("'distinct_on' expects a column or a list of columns".to_string()),
+        }
+    }
+    Ok(columns)
+}
+
+/// Whether the leading ORDER BY items sort by exactly the distinct_on columns
+///
+/// The distinct_on columns may lead in any order; items sorting by other
+/// columns may follow them.
+fn distinct_leads_order(distinct_on: &[String], order: &[String]) -> bool {
+    let sorts_by = |item: &String, expr: &String| {
+        item.strip_prefix(expr.as_str())
+            .is_some_and(|direction| direction.starts_with(' '))
+    };
+
+    order.len() >= distinct_on.len()
+        && distinct_on.iter().all(|expr| {
+            order[..distinct_on.len()]
+                .iter()
+                .any(|item| sorts_by(item, expr))
+        })
+}
+
+/// Look up a field argument by name
+#[inline(always)]
+fn argument<'v>(field: &'v Field, name: &str) -> Option<&'v Value<'v>> {
+    field
+        .arguments
+        .children
+        .iter()
+        .find(|argument| argument.name == name)
+        .map(|argument| &argument.value)
+}
+
+/// Read a column or constraint name given as an enum value or string
+fn name_value<'v>(value: &Value<'v>) -> Result<&'v str, String> {
+    match value {
+        Value::Enum(name) => Ok(name.value),
+        Value::String(name) => Ok(name.value),
+        _ => Err("Expected a column name".to_string()),
+    }
+}
+
+/// Convert a literal GraphQL value to a parameter value
+fn literal(value: &Value) -> Result<ParamValue, String> {
+    Ok(match value {
+        Value::Null => ParamValue::Null,
+        Value::Boolean(value) => ParamValue::Boolean(value.value),
+        Value::Int(value) => ParamValue::Int(
+            value
+                .value
+                .parse()
+                .map_err(|_| format!("Integer literal '{}' is out of range", value.value))?,
+        ),
+        Value::Float(value) => ParamValue::Float(
+            value
+                .value
+                .parse()
+                .map_err(|_| format!("Invalid float literal '{}'", value.value))?,
+        ),
+        Value::String(value) => ParamValue::String(value.value.to_string()),
+        Value::Enum(value) => ParamValue::String(value.value.to_string()),
+        Value::List(list) => ParamValue::List(
+            list.children
+                .iter()
+                .map(literal)
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(object) => ParamValue::Object(
+            object
+                .children
+                .iter()
+                .map(|field| Ok((field.name.to_string(), literal(&field.value)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        Value::Variable(variable) => {
+            return Err(format!(
+                "Variable '${}' cannot be nested inside a literal",
+                variable.name
+            ));
+        }
+    })
+}
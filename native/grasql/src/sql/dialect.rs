@@ -0,0 +1,765 @@
+//! SQL dialect module
+//!
+//! The generator writes the SQL shared by the databases it targets itself
+//! and asks a dialect for the pieces that differ between them: identifier
+//! quoting, placeholders, JSON objects and aggregation, pagination,
+//! `distinct_on`, NULL placement in sorts, casts, array membership, upserts,
+//! `RETURNING`, boolean literals and the types of prepared parameters.
+//! Supporting another database means implementing `SqlDialect` for it and
+//! adding it to `Config.sql_dialect`.
+//!
+//! Each dialect lists its reserved words. An identifier colliding with one,
+//! such as a column named `order`, is quoted even when
+//! `Config.quote_all_identifiers` is off.
+
+use crate::config::NullOrdering;
+
+/// Kind of value bound to a parameter, for declaring its type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamType {
+    Boolean,
+    /// A GraphQL `Int`
+    Integer,
+    /// An integer literal, which may exceed the range of `Int`
+    BigInt,
+    Float,
+    String,
+    Json,
+}
+
+/// Database-specific parts of generated SQL
+pub trait SqlDialect: Sync {
+    /// Quote an identifier, escaping embedded quote characters
+    fn quote_ident(&self, name: &str) -> String;
+
+    /// Reserved words that can't be used as bare identifiers, in lowercase
+    fn reserved_words(&self) -> &'static [&'static str];
+
+    /// Whether `name` is a reserved word, in any letter case
+    fn is_reserved(&self, name: &str) -> bool {
+        self.reserved_words()
+            .iter()
+            .any(|word| word.eq_ignore_ascii_case(name))
+    }
+
+    /// Write an identifier, quoted when `quote_all` is set or when it must be
+    ///
+    /// Reserved words must be quoted, as must names other than plain
+    /// lowercase identifiers, which the database would fold or reject.
+    fn ident(&self, name: &str, quote_all: bool) -> String {
+        if quote_all || !is_plain_identifier(name) || self.is_reserved(name) {
+            self.quote_ident(name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Placeholder for the bind parameter at 1-based position `n`
+    fn placeholder(&self, n: usize) -> String;
+
+    /// Aggregate the values of `expr` into a JSON array, empty when there are no rows
+    fn json_agg(&self, expr: &str) -> String;
+
+    /// Build a JSON object from `(key, expression)` pairs
+    fn json_object(&self, entries: &[(String, String)]) -> String;
+
+    /// Read the value under `key` of the JSON expression `expr`
+    fn json_field(&self, expr: &str, key: &str) -> String;
+
+    /// Type a `_cast` type key converts to; GraphQL scalar names map to the
+    /// database's types and any other key already names a SQL type
+    fn cast_type<'a>(&self, graphql_type: &'a str) -> &'a str;
+
+    /// Compare `target` with every element of the array bound at `array`,
+    /// matching when it equals one, or when it equals none if `negated`
+    fn array_membership(&self, target: &str, array: &str, negated: bool) -> String;
+
+    /// Clause resolving an insert conflict on `constraint` by updating the
+    /// `update_columns` of the existing row, or skipping the row when there
+    /// are none, optionally only for existing rows matching `filter`
+    ///
+    /// Identifiers are already quoted. Errors when the database can't express
+    /// the requested resolution.
+    fn on_conflict_clause(
+        &self,
+        constraint: &str,
+        update_columns: &[String],
+        filter: Option<&str>,
+    ) -> Result<String, String>;
+
+    /// Whether mutations can return the rows they wrote with `RETURNING`
+    fn supports_returning(&self) -> bool;
+
+    /// Type a parameter holding a `param` value is declared with
+    fn param_type(&self, param: ParamType) -> &'static str;
+
+    /// Type a parameter holding a list of `element` values is declared with
+    fn list_param_type(&self, element: &str) -> String;
+
+    /// Pagination clause, with a leading space, or an empty string for neither
+    fn limit_offset_clause(&self, limit: Option<&str>, offset: Option<&str>) -> String;
+
+    /// Literal for a constant boolean condition
+    fn bool_literal(&self, value: bool) -> &'static str;
+
+    /// Clause keeping the first row of each distinct `exprs` combination,
+    /// placed after `SELECT`, or None if the database has no such clause
+    fn distinct_on_clause(&self, exprs: &[String]) -> Option<String>;
+
+    /// ORDER BY item sorting by `expr`, with NULLs placed as `nulls` asks
+    fn order_item(&self, expr: &str, descending: bool, nulls: NullOrdering) -> String;
+}
+
+/// Quote `value` as a SQL string literal
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Join `(key, expression)` pairs into the arguments of a JSON object function
+fn json_object_arguments(entries: &[(String, String)]) -> String {
+    entries
+        .iter()
+        .map(|(key, expr)| format!("{}, {}", string_literal(key), expr))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Sort keyword for a direction
+fn direction_keyword(descending: bool) -> &'static str {
+    if descending {
+        "DESC"
+    } else {
+        "ASC"
+    }
+}
+
+/// Whether `name` is a lowercase letter or underscore followed by lowercase
+/// letters, digits and underscores
+fn is_plain_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some('a'..='z' | '_'))
+        && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+}
+
+/// PostgreSQL, the dialect SQL is generated in
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostgresDialect;
+
+impl SqlDialect for PostgresDialect {
+    fn quote_ident(&self, name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    fn reserved_words(&self) -> &'static [&'static str] {
+        POSTGRES_RESERVED_WORDS
+    }
+
+    fn placeholder(&self, n: usize) -> String {
+        format!("${}", n)
+    }
+
+    fn json_agg(&self, expr: &str) -> String {
+        format!("coalesce(json_agg({}), '[]')", expr)
+    }
+
+    fn json_object(&self, entries: &[(String, String)]) -> String {
+        format!("json_build_object({})", json_object_arguments(entries))
+    }
+
+    fn json_field(&self, expr: &str, key: &str) -> String {
+        format!("{} -> {}", expr, string_literal(key))
+    }
+
+    fn cast_type<'a>(&self, graphql_type: &'a str) -> &'a str {
+        match graphql_type {
+            "String" | "ID" => "text",
+            "Int" => "integer",
+            "Float" => "double precision",
+            "Boolean" => "boolean",
+            other => other,
+        }
+    }
+
+    fn array_membership(&self, target: &str, array: &str, negated: bool) -> String {
+        if negated {
+            format!("{} <> ALL({})", target, array)
+        } else {
+            format!("{} = ANY({})", target, array)
+        }
+    }
+
+    fn on_conflict_clause(
+        &self,
+        constraint: &str,
+        update_columns: &[String],
+        filter: Option<&str>,
+    ) -> Result<String, String> {
+        let mut sql = format!("ON CONFLICT ON CONSTRAINT {}", constraint);
+        if update_columns.is_empty() {
+            sql.push_str(" DO NOTHING");
+            return Ok(sql);
+        }
+
+        sql.push_str(" DO UPDATE SET ");
+        sql.push_str(
+            &update_columns
+                .iter()
+                .map(|column| format!("{} = EXCLUDED.{}", column, column))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        if let Some(filter) = filter {
+            sql.push_str(" WHERE ");
+            sql.push_str(filter);
+        }
+        Ok(sql)
+    }
+
+    fn supports_returning(&self) -> bool {
+        true
+    }
+
+    fn param_type(&self, param: ParamType) -> &'static str {
+        match param {
+            ParamType::Boolean => "boolean",
+            ParamType::Integer => "integer",
+            ParamType::BigInt => "bigint",
+            ParamType::Float => "double precision",
+            ParamType::String => "text",
+            ParamType::Json => "jsonb",
+        }
+    }
+
+    fn list_param_type(&self, element: &str) -> String {
+        format!("{}[]", element)
+    }
+
+    fn limit_offset_clause(&self, limit: Option<&str>, offset: Option<&str>) -> String {
+        let mut clause = String::new();
+        if let Some(limit) = limit {
+            clause.push_str(&format!(" LIMIT {}", limit));
+        }
+        if let Some(offset) = offset {
+            clause.push_str(&format!(" OFFSET {}", offset));
+        }
+        clause
+    }
+
+    fn bool_literal(&self, value: bool) -> &'static str {
+        if value {
+            "TRUE"
+        } else {
+            "FALSE"
+        }
+    }
+
+    fn distinct_on_clause(&self, exprs: &[String]) -> Option<String> {
+        Some(format!("DISTINCT ON ({})", exprs.join(", ")))
+    }
+
+    fn order_item(&self, expr: &str, descending: bool, nulls: NullOrdering) -> String {
+        let placement = match nulls {
+            NullOrdering::DbDefault => "",
+            NullOrdering::NullsFirst => " NULLS FIRST",
+            NullOrdering::NullsLast => " NULLS LAST",
+        };
+        format!("{} {}{}", expr, direction_keyword(descending), placement)
+    }
+}
+
+/// MySQL
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MySqlDialect;
+
+impl SqlDialect for MySqlDialect {
+    fn quote_ident(&self, name: &str) -> String {
+        format!("`{}`", name.replace('`', "``"))
+    }
+
+    fn reserved_words(&self) -> &'static [&'static str] {
+        MYSQL_RESERVED_WORDS
+    }
+
+    fn placeholder(&self, _n: usize) -> String {
+        "?".to_string()
+    }
+
+    fn json_agg(&self, expr: &str) -> String {
+        format!("COALESCE(JSON_ARRAYAGG({}), JSON_ARRAY())", expr)
+    }
+
+    fn json_object(&self, entries: &[(String, String)]) -> String {
+        format!("JSON_OBJECT({})", json_object_arguments(entries))
+    }
+
+    fn json_field(&self, expr: &str, key: &str) -> String {
+        let path = format!("$.\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""));
+        format!("JSON_EXTRACT({}, {})", expr, string_literal(&path))
+    }
+
+    fn cast_type<'a>(&self, graphql_type: &'a str) -> &'a str {
+        match graphql_type {
+            "String" | "ID" => "CHAR",
+            "Int" | "Boolean" => "SIGNED",
+            "Float" => "DOUBLE",
+            other => other,
+        }
+    }
+
+    fn array_membership(&self, target: &str, array: &str, negated: bool) -> String {
+        // MySQL has no array parameters, so the list is bound as a JSON array
+        let condition = format!("{} MEMBER OF (CAST({} AS JSON))", target, array);
+        if negated {
+            format!("NOT ({})", condition)
+        } else {
+            condition
+        }
+    }
+
+    fn on_conflict_clause(
+        &self,
+        _constraint: &str,
+        update_columns: &[String],
+        filter: Option<&str>,
+    ) -> Result<String, String> {
+        // MySQL resolves conflicts on any unique key and can't skip or filter rows
+        if update_columns.is_empty() {
+            return Err("'on_conflict' needs 'update_columns' in the SQL dialect".to_string());
+        }
+        if filter.is_some() {
+            return Err("'on_conflict' 'where' is not supported by the SQL dialect".to_string());
+        }
+
+        Ok(format!(
+            "ON DUPLICATE KEY UPDATE {}",
+            update_columns
+                .iter()
+                .map(|column| format!("{} = VALUES({})", column, column))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+
+    fn supports_returning(&self) -> bool {
+        false
+    }
+
+    fn param_type(&self, param: ParamType) -> &'static str {
+        match param {
+            ParamType::Boolean => "BOOLEAN",
+            ParamType::Integer => "INT",
+            ParamType::BigInt => "BIGINT",
+            ParamType::Float => "DOUBLE",
+            ParamType::String => "TEXT",
+            ParamType::Json => "JSON",
+        }
+    }
+
+    fn list_param_type(&self, _element: &str) -> String {
+        // Lists are bound as JSON arrays, see `array_membership`
+        "JSON".to_string()
+    }
+
+    fn limit_offset_clause(&self, limit: Option<&str>, offset: Option<&str>) -> String {
+        match (limit, offset) {
+            (Some(limit), Some(offset)) => format!(" LIMIT {} OFFSET {}", limit, offset),
+            (Some(limit), None) => format!(" LIMIT {}", limit),
+            // MySQL has no OFFSET without LIMIT; use the largest row count instead
+            (None, Some(offset)) => format!(" LIMIT 18446744073709551615 OFFSET {}", offset),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn bool_literal(&self, value: bool) -> &'static str {
+        if value {
+            "1"
+        } else {
+            "0"
+        }
+    }
+
+    fn distinct_on_clause(&self, _exprs: &[String]) -> Option<String> {
+        None
+    }
+
+    fn order_item(&self, expr: &str, descending: bool, nulls: NullOrdering) -> String {
+        let item = format!("{} {}", expr, direction_keyword(descending));
+        // MySQL has no NULLS FIRST/LAST; sort on whether the value is NULL first
+        match nulls {
+            NullOrdering::DbDefault => item,
+            NullOrdering::NullsFirst => format!("{} IS NULL DESC, {}", expr, item),
+            NullOrdering::NullsLast => format!("{} IS NULL ASC, {}", expr, item),
+        }
+    }
+}
+
+/// Words PostgreSQL reserves, which can't name a column or table unquoted
+const POSTGRES_RESERVED_WORDS: &[&str] = &[
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "asymmetric",
+    "authorization",
+    "binary",
+    "both",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "collation",
+    "column",
+    "concurrently",
+    "constraint",
+    "create",
+    "cross",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_schema",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "default",
+    "deferrable",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "freeze",
+    "from",
+    "full",
+    "grant",
+    "group",
+    "having",
+    "ilike",
+    "in",
+    "initially",
+    "inner",
+    "intersect",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "lateral",
+    "leading",
+    "left",
+    "like",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "natural",
+    "not",
+    "notnull",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "outer",
+    "overlaps",
+    "placing",
+    "primary",
+    "references",
+    "returning",
+    "right",
+    "select",
+    "session_user",
+    "similar",
+    "some",
+    "symmetric",
+    "system_user",
+    "table",
+    "tablesample",
+    "then",
+    "to",
+    "trailing",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "variadic",
+    "verbose",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+/// Words MySQL 8 reserves, which can't name a column or table unquoted
+const MYSQL_RESERVED_WORDS: &[&str] = &[
+    "accessible",
+    "add",
+    "all",
+    "alter",
+    "analyze",
+    "and",
+    "as",
+    "asc",
+    "asensitive",
+    "before",
+    "between",
+    "bigint",
+    "binary",
+    "blob",
+    "both",
+    "by",
+    "call",
+    "cascade",
+    "case",
+    "change",
+    "char",
+    "character",
+    "check",
+    "collate",
+    "column",
+    "condition",
+    "constraint",
+    "continue",
+    "convert",
+    "create",
+    "cross",
+    "cube",
+    "cume_dist",
+    "current_date",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "cursor",
+    "database",
+    "databases",
+    "day_hour",
+    "day_microsecond",
+    "day_minute",
+    "day_second",
+    "dec",
+    "decimal",
+    "declare",
+    "default",
+    "delayed",
+    "delete",
+    "dense_rank",
+    "desc",
+    "describe",
+    "deterministic",
+    "distinct",
+    "distinctrow",
+    "div",
+    "double",
+    "drop",
+    "dual",
+    "each",
+    "else",
+    "elseif",
+    "empty",
+    "enclosed",
+    "escaped",
+    "except",
+    "exists",
+    "exit",
+    "explain",
+    "false",
+    "fetch",
+    "first_value",
+    "float",
+    "float4",
+    "float8",
+    "for",
+    "force",
+    "foreign",
+    "from",
+    "fulltext",
+    "function",
+    "generated",
+    "get",
+    "grant",
+    "group",
+    "grouping",
+    "groups",
+    "having",
+    "high_priority",
+    "hour_microsecond",
+    "hour_minute",
+    "hour_second",
+    "if",
+    "ignore",
+    "in",
+    "index",
+    "infile",
+    "inner",
+    "inout",
+    "insensitive",
+    "insert",
+    "int",
+    "int1",
+    "int2",
+    "int3",
+    "int4",
+    "int8",
+    "integer",
+    "intersect",
+    "interval",
+    "into",
+    "io_after_gtids",
+    "io_before_gtids",
+    "is",
+    "iterate",
+    "join",
+    "json_table",
+    "key",
+    "keys",
+    "kill",
+    "lag",
+    "last_value",
+    "lateral",
+    "lead",
+    "leading",
+    "leave",
+    "left",
+    "like",
+    "limit",
+    "linear",
+    "lines",
+    "load",
+    "localtime",
+    "localtimestamp",
+    "lock",
+    "long",
+    "longblob",
+    "longtext",
+    "loop",
+    "low_priority",
+    "master_bind",
+    "master_ssl_verify_server_cert",
+    "match",
+    "maxvalue",
+    "mediumblob",
+    "mediumint",
+    "mediumtext",
+    "middleint",
+    "minute_microsecond",
+    "minute_second",
+    "mod",
+    "modifies",
+    "natural",
+    "no_write_to_binlog",
+    "not",
+    "nth_value",
+    "ntile",
+    "null",
+    "numeric",
+    "of",
+    "on",
+    "optimize",
+    "optimizer_costs",
+    "option",
+    "optionally",
+    "or",
+    "order",
+    "out",
+    "outer",
+    "outfile",
+    "over",
+    "partition",
+    "percent_rank",
+    "precision",
+    "primary",
+    "procedure",
+    "purge",
+    "range",
+    "rank",
+    "read",
+    "read_write",
+    "reads",
+    "real",
+    "recursive",
+    "references",
+    "regexp",
+    "release",
+    "rename",
+    "repeat",
+    "replace",
+    "require",
+    "resignal",
+    "restrict",
+    "return",
+    "revoke",
+    "right",
+    "rlike",
+    "row",
+    "row_number",
+    "rows",
+    "schema",
+    "schemas",
+    "second_microsecond",
+    "select",
+    "sensitive",
+    "separator",
+    "set",
+    "show",
+    "signal",
+    "smallint",
+    "spatial",
+    "specific",
+    "sql",
+    "sql_big_result",
+    "sql_calc_found_rows",
+    "sql_small_result",
+    "sqlexception",
+    "sqlstate",
+    "sqlwarning",
+    "ssl",
+    "starting",
+    "stored",
+    "straight_join",
+    "system",
+    "table",
+    "terminated",
+    "then",
+    "tinyblob",
+    "tinyint",
+    "tinytext",
+    "to",
+    "trailing",
+    "trigger",
+    "true",
+    "undo",
+    "union",
+    "unique",
+    "unlock",
+    "unsigned",
+    "update",
+    "usage",
+    "use",
+    "using",
+    "utc_date",
+    "utc_time",
+    "utc_timestamp",
+    "values",
+    "varbinary",
+    "varchar",
+    "varcharacter",
+    "varying",
+    "virtual",
+    "when",
+    "where",
+    "while",
+    "window",
+    "with",
+    "write",
+    "xor",
+    "year_month",
+    "zerofill",
+];
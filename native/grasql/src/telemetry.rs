@@ -0,0 +1,75 @@
+//! Tracing instrumentation module
+//!
+//! With the `tracing` feature, each parse runs inside a `grasql.parse` span
+//! carrying the query ID, with a `grasql.phase` child span for each phase:
+//! `document` (parsing the GraphQL text), `extraction` (guards and path
+//! extraction) and `encoding` (building the resolution request). Every span
+//! records how long it was open as `duration_us` when it closes, so slow
+//! queries show where their time went. Without the feature the guards are
+//! empty and compile away.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// Entered span timing a parse or one of its phases, closed when dropped
+pub(crate) struct ParseSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    start: Instant,
+}
+
+impl ParseSpan {
+    /// Enter the span covering the whole parse of the query with `query_id`
+    #[inline(always)]
+    pub(crate) fn parse(query_id: &str) -> Self {
+        #[cfg(feature = "tracing")]
+        {
+            Self::enter(tracing::info_span!(
+                "grasql.parse",
+                query_id,
+                duration_us = tracing::field::Empty
+            ))
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = query_id;
+            ParseSpan {}
+        }
+    }
+
+    /// Enter the span covering one phase of the current parse
+    #[inline(always)]
+    pub(crate) fn phase(phase: &'static str) -> Self {
+        #[cfg(feature = "tracing")]
+        {
+            Self::enter(tracing::info_span!(
+                "grasql.phase",
+                phase,
+                duration_us = tracing::field::Empty
+            ))
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = phase;
+            ParseSpan {}
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    fn enter(span: tracing::Span) -> Self {
+        ParseSpan {
+            span: span.entered(),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ParseSpan {
+    #[inline(always)]
+    fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        self.span
+            .record("duration_us", self.start.elapsed().as_micros() as u64);
+    }
+}
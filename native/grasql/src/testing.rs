@@ -0,0 +1,107 @@
+/// Query replay module
+///
+/// Reproduces field-reported issues by parsing a corpus of real queries,
+/// such as ones dumped from production logs, and reporting how each one
+/// fared. Unlike fuzzing, the shapes replayed are the ones clients actually
+/// send. Parsing uses the global configuration, so GraSQL must be
+/// initialized first.
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::parser::parse_graphql;
+use crate::types::ResolutionRequest;
+
+/// What happened when one query was replayed
+#[derive(Debug, Clone)]
+pub enum ReplayResult {
+    /// The query parsed and produced this resolution request, boxed as it
+    /// dwarfs the error variants
+    Parsed(Box<ResolutionRequest>),
+
+    /// The parser rejected the query with this error
+    Failed(String),
+
+    /// The parser panicked, with this panic message
+    Panicked(String),
+}
+
+/// Outcome of replaying one query
+#[derive(Debug, Clone)]
+pub struct ReplayOutcome {
+    /// The query as replayed
+    pub query: String,
+
+    /// The request or error it produced
+    pub result: ReplayResult,
+}
+
+/// Outcomes of a replay, in corpus order
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub outcomes: Vec<ReplayOutcome>,
+}
+
+impl ReplayReport {
+    /// Number of queries that parsed
+    pub fn parsed(&self) -> usize {
+        self.outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.result, ReplayResult::Parsed(_)))
+            .count()
+    }
+
+    /// Queries the parser rejected, with their errors
+    pub fn failures(&self) -> Vec<(&str, &str)> {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| match &outcome.result {
+                ReplayResult::Failed(error) => Some((outcome.query.as_str(), error.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Queries the parser panicked on, with their panic messages
+    pub fn panics(&self) -> Vec<(&str, &str)> {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| match &outcome.result {
+                ReplayResult::Panicked(message) => Some((outcome.query.as_str(), message.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Parse every query in `queries` and report the outcome of each
+///
+/// A panic while parsing one query is caught and recorded, so the rest of
+/// the corpus is still replayed.
+pub fn replay(queries: &[&str]) -> ReplayReport {
+    let outcomes = queries
+        .iter()
+        .map(|&query| {
+            let result = match panic::catch_unwind(AssertUnwindSafe(|| parse_graphql(query))) {
+                Ok(Ok((_, request))) => ReplayResult::Parsed(Box::new(request)),
+                Ok(Err(error)) => ReplayResult::Failed(error),
+                Err(payload) => ReplayResult::Panicked(panic_message(payload.as_ref())),
+            };
+            ReplayOutcome {
+                query: query.to_string(),
+                result,
+            }
+        })
+        .collect();
+
+    ReplayReport { outcomes }
+}
+
+/// Text of a panic payload, for the common `&str` and `String` payloads
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
@@ -1,16 +1,136 @@
-use graphql_query::ast::{ASTContext, Document, ParseNode};
+use crate::config::MutationPrefixes;
+use crate::encoding::{
+    decode_path, validate_path_dir, PATH_FORMAT_VERSION, PATH_TYPE_RELATIONSHIP,
+};
+use crate::extraction::{build_path_index, insert_path};
+use crate::interning::resolve_str;
+use graphql_query::ast::{
+    ASTContext, Definition, Document, ParseNode, PrintNode, Selection, SelectionSet, Value,
+};
 /// GraSQL type definitions
 ///
 /// This module contains type definitions used throughout the GraSQL library.
 use lasso::Spur;
-use std::collections::{HashMap, HashSet};
+use once_cell::sync::OnceCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::ops::Deref;
 use std::sync::Arc;
+use xxhash_rust::xxh3::xxh3_64;
 
 /// Type alias for interned string ID
 pub type SymbolId = Spur;
 
+/// Column usage information keyed by table path
+pub type ColumnUsage = HashMap<FieldPath, HashSet<SymbolId>>;
+
+/// Arguments passed to a single column, as (argument name, printed GraphQL value) pairs
+pub type ColumnArgumentList = Vec<(SymbolId, String)>;
+
+/// Column-level arguments (e.g. `avatar(size: 100)`) keyed by table path, then
+/// by response key, with the column they are passed to
+///
+/// Aliases of one column can pass different arguments, so each response key
+/// keeps its own. SQL generation can't honour them and rejects such columns.
+pub type ColumnArguments = HashMap<FieldPath, HashMap<String, (SymbolId, ColumnArgumentList)>>;
+
+/// Arguments of function root fields (`Config.function_fields`) keyed by field path
+///
+/// Lists the keys of the field's `args` object with their printed GraphQL
+/// values, in source order. These name function parameters, not columns.
+pub type FunctionArguments = HashMap<FieldPath, ColumnArgumentList>;
+
+/// A custom directive on a field, kept as an annotation (`Config.field_directives`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDirective {
+    /// Column the directive is on, or None when it is on the table or relationship itself
+    pub column: Option<SymbolId>,
+    /// Directive name without the `@`
+    pub name: SymbolId,
+    /// Directive arguments with their printed GraphQL values, in source order
+    pub arguments: ColumnArgumentList,
+}
+
+/// Annotation directives keyed by the path of the table or relationship they appear in
+pub type FieldDirectives = HashMap<FieldPath, Vec<FieldDirective>>;
+
+/// A table an insert mutation writes to, with the inserts nested in its rows
+///
+/// A nested insert is a relationship field of an inserted object whose value
+/// is `{ data: ... }`. The rows of a child reference the rows of its parent,
+/// so all of them go in one transaction; which of the two is inserted first
+/// depends on the direction of the relationship.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertNode {
+    /// Path of the table: the insert root field followed by relationship fields
+    pub path: FieldPath,
+
+    /// Inserts nested in this table's rows, in order of first appearance
+    pub children: Vec<InsertNode>,
+}
+
+impl InsertNode {
+    /// Create a node without nested inserts
+    pub fn new(path: FieldPath) -> Self {
+        InsertNode {
+            path,
+            children: Vec::new(),
+        }
+    }
+
+    /// Get the child for a relationship path, adding it if absent
+    pub fn child_mut(&mut self, path: FieldPath) -> &mut InsertNode {
+        match self.children.iter().position(|child| child.path == path) {
+            Some(idx) => &mut self.children[idx],
+            None => {
+                self.children.push(InsertNode::new(path));
+                self.children.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// Response keys of aliased columns keyed by table path, then by column
+///
+/// Lists every distinct key the column is selected under, in selection order,
+/// including the column name itself when it is also selected without an alias.
+pub type ColumnAliases = HashMap<FieldPath, HashMap<SymbolId, Vec<String>>>;
+
+/// JSON paths projected from scalar JSON columns keyed by table path, then by column
+///
+/// Each path lists the keys below the column, e.g. `["address", "city"]` for
+/// `metadata { address { city } }`, in selection order.
+pub type ColumnJsonPaths = HashMap<FieldPath, HashMap<SymbolId, Vec<Vec<String>>>>;
+
+/// Value of a pagination argument such as `limit` or `offset`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgValue {
+    /// An integer literal (`limit: 10`)
+    Literal(i64),
+    /// A GraphQL variable, by name without the `$` (`limit: $limit`)
+    Variable(String),
+}
+
+/// Pagination arguments passed to a table or relationship field
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldArgs {
+    /// Maximum number of rows (`limit`)
+    pub limit: Option<ArgValue>,
+    /// Number of rows to skip (`offset`)
+    pub offset: Option<ArgValue>,
+}
+
+/// Pagination arguments keyed by table or relationship path
+pub type FieldArguments = HashMap<FieldPath, FieldArgs>;
+
+/// Relationship entry in a ResolutionResponse:
+/// (src_table_idx, target_table_idx, type_code, join_table_idx, [src_col_idxs], [tgt_col_idxs])
+pub type RelationshipEntry = (u32, u32, u8, i32, Vec<u32>, Vec<u32>);
+
+/// Directive entry in a ResolutionRequest:
+/// (path_id, column_idx or -1 for the field itself, directive_idx, [(arg_idx, printed_value)])
+pub type DirectiveEntry = (u32, i32, u32, Vec<(u32, String)>);
+
 /// GraphQL operation kind
 ///
 /// This enum represents the different kinds of GraphQL operations.
@@ -26,8 +146,35 @@ pub enum GraphQLOperationKind {
     DeleteMutation,
     /// Subscription operation
     Subscription,
+    /// Introspection query answered from schema metadata rather than SQL
+    Introspection,
+}
+
+impl GraphQLOperationKind {
+    /// Whether answering this operation needs the database
+    ///
+    /// Introspection-only documents such as `{ __typename }` parse fine but
+    /// reference no tables, so they can be answered without generating SQL.
+    #[inline(always)]
+    pub fn requires_sql(self) -> bool {
+        !matches!(self, GraphQLOperationKind::Introspection)
+    }
 }
 
+/// How a batch of queries handles an item that fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchMode {
+    /// Return a result for every item; failures do not affect other items
+    #[default]
+    PerItem,
+    /// Abort the whole batch on the first failing item
+    FailFast,
+}
+
+/// Result for one query of a batch: the query and whether it came from the
+/// cache, or the error parsing it
+pub type BatchItem = Result<(CachedQueryInfo, bool), String>;
+
 /// A path to a field in the GraphQL query, represented as a sequence of symbol IDs
 ///
 /// Using SmallVec for optimal performance with small paths (which is the common case)
@@ -87,6 +234,13 @@ impl FieldPath {
     }
 }
 
+impl Default for FieldPath {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Deref for FieldPath {
     type Target = [SymbolId];
 
@@ -96,16 +250,29 @@ impl Deref for FieldPath {
     }
 }
 
+impl FromIterator<SymbolId> for FieldPath {
+    #[inline(always)]
+    fn from_iter<I: IntoIterator<Item = SymbolId>>(iter: I) -> Self {
+        FieldPath(iter.into_iter().collect())
+    }
+}
+
 /// Resolution request to be sent to Elixir
 ///
 /// This type encapsulates the information needed for resolving
 /// field paths to actual database tables and relationships.
-#[derive(Debug, Clone)]
+/// It is the only request shape: the parser builds it and the NIF layer
+/// encodes it as-is, so there is no separate legacy representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResolutionRequest {
     /// Unique query identifier generated by the parser.
     /// This is the only field that doesn't use indices for optimization.
     pub query_id: String,
 
+    /// Version of the `paths`/`path_dir`/`path_types` encoding,
+    /// `encoding::PATH_FORMAT_VERSION` for requests built by this crate.
+    pub version: u8,
+
     /// Shared string table containing all identifiers used in the request.
     /// This includes field names, argument names, etc.
     pub strings: Vec<String>,
@@ -113,7 +280,7 @@ pub struct ResolutionRequest {
     /// Encoded field paths as a flat array with path lengths prefixed.
     /// Format: [path1_len, path1_idx1, path1_idx2, ..., path2_len, path2_idx1, ...]
     /// Each path_idx is an index into the strings array.
-    /// This encoding provides memory efficiency while maintaining O(1) path access.
+    /// See the `encoding` module for the full format.
     pub paths: Vec<u32>,
 
     /// Directory mapping path_id to offset in the paths array.
@@ -126,6 +293,12 @@ pub struct ResolutionRequest {
     /// Indexed by path_id, provides O(1) lookup of path type.
     pub path_types: Vec<u8>,
 
+    /// Base entity name for each path_id.
+    /// Index into the strings array for the path's last segment with the
+    /// configured mutation prefixes and `_by_pk`/aggregate suffixes removed,
+    /// so `users`, `users_by_pk` and `insert_users` all resolve `users`.
+    pub entities: Vec<u32>,
+
     /// Column map containing table indices and their column indices.
     /// Format: [(table_idx, [column_idx1, column_idx2, ...]), ...]
     /// table_idx is an index into strings array for the table name.
@@ -139,6 +312,20 @@ pub struct ResolutionRequest {
     /// operation_type: 0=query, 1=insert, 2=update, 3=delete
     /// Preserves operation order without depending on operation names.
     pub ops: Vec<(u32, u8)>,
+
+    /// Annotation directives from `Config.field_directives`, in path_id order.
+    /// Format: [(path_id, column_idx, directive_idx, [(arg_idx, value), ...]), ...]
+    /// column_idx is -1 for a directive on the table or relationship itself.
+    /// Argument values are printed GraphQL, e.g. `"uuid"` or `$id`.
+    pub directives: Vec<DirectiveEntry>,
+
+    /// Non-fatal issues met during extraction, as readable messages.
+    /// The query still parses; these only surface what was skipped.
+    pub warnings: Vec<String>,
+
+    /// Every argument name used on any field, sorted, when
+    /// `Config.collect_argument_names` is set; empty otherwise.
+    pub argument_names: Vec<String>,
 }
 
 impl ResolutionRequest {
@@ -147,14 +334,157 @@ impl ResolutionRequest {
     pub fn new() -> Self {
         ResolutionRequest {
             query_id: String::new(),
+            version: PATH_FORMAT_VERSION,
             strings: Vec::new(),
             paths: Vec::new(),
             path_dir: Vec::new(),
             path_types: Vec::new(),
+            entities: Vec::new(),
             cols: Vec::new(),
             ops: Vec::new(),
+            directives: Vec::new(),
+            warnings: Vec::new(),
+            argument_names: Vec::new(),
         }
     }
+
+    /// Decode a path back into its field names
+    ///
+    /// Returns None if the path_id is unknown or the path references
+    /// indices outside the string table.
+    pub fn decode_path(&self, path_id: usize) -> Option<Vec<&str>> {
+        decode_path(&self.paths, &self.path_dir, path_id)?
+            .iter()
+            .map(|&idx| self.strings.get(idx as usize).map(String::as_str))
+            .collect()
+    }
+
+    /// Decode every path, indexed by path_id
+    ///
+    /// Returns None if any path fails to decode (see `decode_path`).
+    pub fn decode_paths(&self) -> Option<Vec<Vec<&str>>> {
+        (0..self.path_dir.len())
+            .map(|path_id| self.decode_path(path_id))
+            .collect()
+    }
+
+    /// Check that the request is internally consistent
+    ///
+    /// Verifies the path format version, that every per-path array has one
+    /// entry per path, that `path_dir` is valid for `paths` (see
+    /// `encoding::validate_path_dir`) and that path segments, entities,
+    /// column map, operation field and directive indices point into the
+    /// string table, with directives attached to existing paths.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != PATH_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported path format version {} (expected {})",
+                self.version, PATH_FORMAT_VERSION
+            ));
+        }
+
+        let path_count = self.path_dir.len();
+        if self.path_types.len() != path_count || self.entities.len() != path_count {
+            return Err(format!(
+                "Path directory has {} entries but {} path types and {} entities",
+                path_count,
+                self.path_types.len(),
+                self.entities.len()
+            ));
+        }
+
+        validate_path_dir(&self.paths, &self.path_dir)?;
+
+        let string_count = self.strings.len() as u32;
+        let check_index = |idx: u32, what: &str| {
+            if idx < string_count {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{} index {} is outside the string table of {} entries",
+                    what, idx, string_count
+                ))
+            }
+        };
+        for path_id in 0..path_count {
+            for &idx in decode_path(&self.paths, &self.path_dir, path_id).unwrap_or(&[]) {
+                check_index(idx, "Path segment")?;
+            }
+        }
+        for &idx in self.entities.iter() {
+            check_index(idx, "Entity")?;
+        }
+        for (table_idx, columns) in self.cols.iter() {
+            check_index(*table_idx, "Table")?;
+            for &idx in columns.iter() {
+                check_index(idx, "Column")?;
+            }
+        }
+        for (field_idx, _) in self.ops.iter() {
+            check_index(*field_idx, "Operation field")?;
+        }
+        for (path_id, column_idx, directive_idx, args) in self.directives.iter() {
+            if *path_id as usize >= path_count {
+                return Err(format!(
+                    "Directive path {} is outside the path directory of {} entries",
+                    path_id, path_count
+                ));
+            }
+            if *column_idx != -1 {
+                check_index(
+                    u32::try_from(*column_idx).unwrap_or(u32::MAX),
+                    "Directive column",
+                )?;
+            }
+            check_index(*directive_idx, "Directive")?;
+            for (arg_idx, _) in args.iter() {
+                check_index(*arg_idx, "Directive argument")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List the relationships referenced by this request
+    ///
+    /// Each relationship path (`path_types == 1`) is returned as a
+    /// `(parent, child)` pair of field names taken from its last two
+    /// segments, in path_id order.
+    pub fn relationships(&self) -> Vec<(String, String)> {
+        self.path_types
+            .iter()
+            .enumerate()
+            .filter(|(_, &path_type)| path_type == PATH_TYPE_RELATIONSHIP)
+            .filter_map(|(path_id, _)| match self.decode_path(path_id)?.as_slice() {
+                [.., parent, child] => Some((parent.to_string(), child.to_string())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Map each table in `cols` to the names of its columns
+    ///
+    /// Column names are sorted so the result does not depend on extraction
+    /// order. Indices missing from `strings` are skipped.
+    pub fn columns_by_table(&self) -> HashMap<String, Vec<String>> {
+        let name = |idx: &u32| self.strings.get(*idx as usize).cloned();
+
+        self.cols
+            .iter()
+            .filter_map(|(table_idx, column_idxs)| {
+                let mut columns: Vec<String> = column_idxs.iter().filter_map(name).collect();
+                columns.sort_unstable();
+                Some((name(table_idx)?, columns))
+            })
+            .collect()
+    }
+}
+
+impl Default for ResolutionRequest {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// ResolutionResponse represents the resolved schema information.
@@ -173,6 +503,7 @@ pub struct ResolutionResponse {
     /// - schema_idx: Index into strings for schema name (e.g., "public")
     /// - name_idx: Index into strings for table name (e.g., "users")
     /// - typename_idx: Index into strings for GraphQL type name (e.g., "User")
+    ///
     /// Indexed by table_id, which is used in other parts of the response.
     pub tables: Vec<(u32, u32, u32)>,
 
@@ -184,8 +515,9 @@ pub struct ResolutionResponse {
     /// - join_table_idx: Index into joins array, or -1 if no join table
     /// - src_col_idxs: Array of indices into strings array for source column names
     /// - tgt_col_idxs: Array of indices into strings array for target column names
+    ///
     /// Indexed by relationship_id, which is used in path_map.
-    pub rels: Vec<(u32, u32, u8, i32, Vec<u32>, Vec<u32>)>,
+    pub rels: Vec<RelationshipEntry>,
 
     /// Join tables information (for many-to-many relationships), each entry containing:
     /// (schema_idx, name_idx, [src_col_idxs], [tgt_col_idxs])
@@ -193,6 +525,7 @@ pub struct ResolutionResponse {
     /// - name_idx: Index into strings for join table name
     /// - src_col_idxs: Indices into strings for source column names
     /// - tgt_col_idxs: Indices into strings for target column names
+    ///
     /// Indexed by join_table_id, which is referenced in rels.
     pub joins: Vec<(u32, u32, Vec<u32>, Vec<u32>)>,
 
@@ -200,7 +533,10 @@ pub struct ResolutionResponse {
     /// Format: [(entity_type, entity_idx), ...]
     /// - entity_type: 0=table, 1=relationship
     /// - entity_idx: Index into tables or rels array based on entity_type
+    ///
     /// Indexed by path_id from ResolutionRequest, provides O(1) lookup.
+    /// Any other entity_type marks a path that did not resolve, such as a
+    /// column filter picked up as a potential relationship.
     pub path_map: Vec<(u8, u32)>,
 
     /// Columns information, each entry containing:
@@ -209,9 +545,18 @@ pub struct ResolutionResponse {
     /// - name_idx: Index into strings for column name
     /// - type_idx: Index into strings for SQL type
     /// - default_val_idx: Index into strings for default value, or -1 if none
+    ///
     /// Provides O(1) lookup of column information.
     pub cols: Vec<(u32, u32, u32, i32)>,
 
+    /// Primary key columns, each entry containing:
+    /// (table_idx, [column_name_idxs])
+    /// - table_idx: Index into tables array
+    /// - column_name_idxs: Indices into strings for the key's column names
+    ///
+    /// Optional; only tables whose key the resolver knows are listed.
+    pub pks: Vec<(u32, Vec<u32>)>,
+
     /// Operations contained in the GraphQL document.
     /// Format: [(root_field_idx, operation_type), ...]
     /// root_field_idx is an index into strings array for the root field name.
@@ -232,9 +577,38 @@ impl ResolutionResponse {
             joins: Vec::new(),
             path_map: Vec::new(),
             cols: Vec::new(),
+            pks: Vec::new(),
             ops: Vec::new(),
         }
     }
+
+    /// Primary key column name indices of a table, if the resolver supplied them
+    #[inline(always)]
+    pub fn primary_key(&self, table_idx: u32) -> Option<&[u32]> {
+        self.pks
+            .iter()
+            .find(|(idx, _)| *idx == table_idx)
+            .map(|(_, columns)| columns.as_slice())
+    }
+
+    /// Whether a relationship links a table to itself (e.g. `employees.manager`)
+    ///
+    /// The resolver reports both ends of every relationship, so no extra
+    /// marking is needed. SQL generation gives every table reference its own
+    /// alias, which keeps the parent and child of such relationships apart.
+    #[inline(always)]
+    pub fn is_self_referential(&self, rel_idx: usize) -> bool {
+        self.rels
+            .get(rel_idx)
+            .is_some_and(|(src, tgt, ..)| src == tgt)
+    }
+}
+
+impl Default for ResolutionResponse {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Thread-safe version of ParsedQueryInfo for caching
@@ -301,6 +675,38 @@ pub struct CachedQueryInfo {
     /// Column usage information keyed by table path
     pub column_usage: Option<HashMap<FieldPath, HashSet<SymbolId>>>,
 
+    /// Arguments passed to columns, keyed by table path and column
+    pub column_arguments: Option<ColumnArguments>,
+
+    /// Arguments passed to function root fields, keyed by field path
+    pub function_args: Option<FunctionArguments>,
+
+    /// Response keys of aliased columns, keyed by table path and column
+    pub column_aliases: Option<ColumnAliases>,
+
+    /// JSON paths projected from scalar columns, keyed by table path and column
+    pub json_paths: Option<ColumnJsonPaths>,
+
+    /// Pagination arguments, keyed by table or relationship path
+    pub args: Option<FieldArguments>,
+
+    /// Every argument name used on any field, when `Config.collect_argument_names` is set
+    pub argument_names: Option<BTreeSet<String>>,
+
+    /// Columns written by UPDATE mutations (`_set`), keyed by table path
+    pub set_columns: Option<ColumnUsage>,
+
+    /// Columns UPDATE mutations match rows on (`where`, `pk_columns`) and the
+    /// key arguments of `delete_*_by_pk`, keyed by table path
+    pub filter_columns: Option<ColumnUsage>,
+
+    /// Tables written by each insert root field, with their nested inserts
+    pub insert_tree: Option<Vec<InsertNode>>,
+
+    /// Per-call mutation prefixes the query was parsed with, applied again
+    /// when generating its SQL
+    pub mutation_prefixes: Option<MutationPrefixes>,
+
     /// Store the original AST context for future use
     pub ast_context: Option<Arc<ASTContext>>,
 
@@ -310,8 +716,16 @@ pub struct CachedQueryInfo {
     /// Raw pointer to the Document - valid as long as ast_context exists
     pub document_ptr: Option<*const Document<'static>>,
 
+    /// Document rebuilt by `reconstructed_document`, parsed into ast_context
+    /// at most once
+    pub reconstructed_document: OnceCell<Option<*const Document<'static>>>,
+
     /// Cached ResolutionRequest to avoid regeneration when retrieving from cache
     pub resolution_request: Option<ResolutionRequest>,
+
+    /// The resolution request in external term format (see `etf::encode_request`),
+    /// returned to Elixir on cache hits instead of rebuilding the request term
+    pub encoded_request: Option<Arc<Vec<u8>>>,
 }
 
 // Implementation of Send for CachedQueryInfo
@@ -351,6 +765,16 @@ impl fmt::Debug for CachedQueryInfo {
             .field("field_paths", &self.field_paths)
             .field("path_index", &self.path_index)
             .field("column_usage", &self.column_usage)
+            .field("column_arguments", &self.column_arguments)
+            .field("function_args", &self.function_args)
+            .field("column_aliases", &self.column_aliases)
+            .field("json_paths", &self.json_paths)
+            .field("args", &self.args)
+            .field("argument_names", &self.argument_names)
+            .field("set_columns", &self.set_columns)
+            .field("filter_columns", &self.filter_columns)
+            .field("insert_tree", &self.insert_tree)
+            .field("mutation_prefixes", &self.mutation_prefixes)
             .field("ast_context", &"<ASTContext>")
             .field(
                 "original_query",
@@ -360,12 +784,152 @@ impl fmt::Debug for CachedQueryInfo {
                     .map(|q| format!("{}...", &q[..20.min(q.len())])),
             )
             .field("document_ptr", &self.document_ptr.map(|_| "<Document>"))
+            .field(
+                "reconstructed_document",
+                &self.reconstructed_document.get().map(|_| "<Document>"),
+            )
             .field("resolution_request", &self.resolution_request)
             .finish()
     }
 }
 
 impl CachedQueryInfo {
+    /// Compute a stable signature of the query's structure
+    ///
+    /// Same as `ParsedQueryInfo::structural_signature` for the query this
+    /// entry was cached from.
+    pub fn structural_signature(&self) -> String {
+        structural_signature(
+            self.operation_kind,
+            self.document(),
+            self.field_paths.as_ref(),
+            self.column_usage.as_ref(),
+        )
+    }
+
+    /// Add a field path to a cached query, returning its path index id
+    ///
+    /// Updates `field_paths` and `path_index` in place through `insert_path`,
+    /// so existing paths keep their ids. The cached resolution request is left
+    /// as is; a new path has to be resolved before SQL can use it.
+    pub fn add_field_path(&mut self, path: FieldPath) -> usize {
+        self.field_paths
+            .get_or_insert_with(HashSet::new)
+            .insert(path.clone());
+
+        let index = match &mut self.path_index {
+            Some(index) => index,
+            None => self
+                .path_index
+                .insert(build_path_index(self.field_paths.as_ref().unwrap())),
+        };
+        insert_path(index, path)
+    }
+
+    /// Rewrite every symbol ID the query holds through `remap`
+    ///
+    /// Used by `interning::compact`, which gives the symbols it keeps new IDs.
+    /// The resolution request carries its own string table and is left as is.
+    pub fn remap_symbols(&mut self, remap: &dyn Fn(SymbolId) -> SymbolId) {
+        let path = |path: &FieldPath| -> FieldPath { path.iter().map(|&id| remap(id)).collect() };
+        let arguments = |arguments: &ColumnArgumentList| -> ColumnArgumentList {
+            arguments
+                .iter()
+                .map(|(name, value)| (remap(*name), value.clone()))
+                .collect()
+        };
+
+        if let Some(paths) = &mut self.field_paths {
+            *paths = paths.iter().map(path).collect();
+        }
+        if let Some(index) = &mut self.path_index {
+            *index = index.iter().map(|(p, &id)| (path(p), id)).collect();
+        }
+        for usage in [
+            &mut self.column_usage,
+            &mut self.set_columns,
+            &mut self.filter_columns,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *usage = usage
+                .iter()
+                .map(|(p, columns)| (path(p), columns.iter().map(|&id| remap(id)).collect()))
+                .collect();
+        }
+        if let Some(column_arguments) = &mut self.column_arguments {
+            *column_arguments = column_arguments
+                .iter()
+                .map(|(p, keys)| {
+                    let keys = keys
+                        .iter()
+                        .map(|(key, (column, args))| {
+                            (key.clone(), (remap(*column), arguments(args)))
+                        })
+                        .collect();
+                    (path(p), keys)
+                })
+                .collect();
+        }
+        if let Some(function_args) = &mut self.function_args {
+            *function_args = function_args
+                .iter()
+                .map(|(p, args)| (path(p), arguments(args)))
+                .collect();
+        }
+        if let Some(column_aliases) = &mut self.column_aliases {
+            *column_aliases = column_aliases
+                .iter()
+                .map(|(p, columns)| {
+                    let columns = columns
+                        .iter()
+                        .map(|(&column, keys)| (remap(column), keys.clone()))
+                        .collect();
+                    (path(p), columns)
+                })
+                .collect();
+        }
+        if let Some(json_paths) = &mut self.json_paths {
+            *json_paths = json_paths
+                .iter()
+                .map(|(p, columns)| {
+                    let columns = columns
+                        .iter()
+                        .map(|(&column, paths)| (remap(column), paths.clone()))
+                        .collect();
+                    (path(p), columns)
+                })
+                .collect();
+        }
+        if let Some(args) = &mut self.args {
+            *args = args
+                .iter()
+                .map(|(p, args)| (path(p), args.clone()))
+                .collect();
+        }
+
+        fn remap_node(node: &mut InsertNode, path: &dyn Fn(&FieldPath) -> FieldPath) {
+            node.path = path(&node.path);
+            for child in &mut node.children {
+                remap_node(child, path);
+            }
+        }
+        for node in self.insert_tree.iter_mut().flatten() {
+            remap_node(node, &path);
+        }
+    }
+
+    /// Every symbol ID the query holds
+    pub fn symbols(&self) -> HashSet<SymbolId> {
+        let symbols = std::cell::RefCell::new(HashSet::new());
+        self.clone().remap_symbols(&|id| {
+            symbols.borrow_mut().insert(id);
+            id
+        });
+        symbols.into_inner()
+    }
+
     /// Safely get a reference to the Document
     ///
     /// This method provides safe access to the Document AST with proper lifetime guarantees.
@@ -384,11 +948,20 @@ impl CachedQueryInfo {
     /// - Re-parse the original query using the stored context
     /// - Return the freshly parsed document (at a small performance cost)
     ///
+    /// If original_query is also missing, there is no document; see
+    /// `reconstructed_document` for a selection-only stand-in.
+    ///
     /// # Returns
     ///
     /// - Some(&Document) if a valid document is available through pointer or re-parsing
     /// - None if no document can be obtained
-    pub fn document(&self) -> Option<&Document> {
+    ///
+    /// # Lifetime
+    ///
+    /// The returned Document borrows from `self`, so it cannot outlive the
+    /// CachedQueryInfo holding its AST context; `tests/ui` checks that code
+    /// keeping it longer does not compile.
+    pub fn document(&self) -> Option<&Document<'_>> {
         match (&self.ast_context, self.document_ptr) {
             (Some(ctx), Some(ptr)) => {
                 // Verify AST context is properly maintained with at least one strong reference
@@ -429,6 +1002,124 @@ impl CachedQueryInfo {
             _ => None,
         }
     }
+
+    /// Get the Document, or one rebuilt from the cached paths when it is gone
+    ///
+    /// Falls back to parsing `reconstruct_query` into the stored context when
+    /// `document` returns None. The rebuilt document is parsed once and kept
+    /// in `reconstructed_document`. Arguments are not part of the cached
+    /// paths, so it carries selections only; it describes the query's shape
+    /// but must never be used to generate SQL.
+    pub fn reconstructed_document(&self) -> Option<&Document<'_>> {
+        if let Some(document) = self.document() {
+            return Some(document);
+        }
+
+        let ctx = self.ast_context.as_ref()?;
+        let ptr = self.reconstructed_document.get_or_init(|| {
+            let query = ctx.alloc_str(&self.reconstruct_query()?);
+            match Document::parse(ctx, query) {
+                Ok(document) => {
+                    eprintln!("Falling back to reconstructed query: performance warning");
+                    Some(document as *const Document<'_> as *const _)
+                }
+                Err(e) => {
+                    eprintln!("Parsing reconstructed query failed: {:?}", e);
+                    None
+                }
+            }
+        });
+
+        // Safety: The rebuilt Document lives in the ast_context arena, which
+        // the Arc we're holding keeps alive, like document_ptr.
+        ptr.map(|ptr| unsafe { &*ptr })
+    }
+
+    /// Rebuild a minimal GraphQL query from the cached field paths and column usage
+    ///
+    /// The result selects the same tables, relationships, and columns as the
+    /// original query, with fields sorted by name. Arguments, aliases, and the
+    /// operation name are not cached and therefore not reproduced.
+    ///
+    /// # Returns
+    ///
+    /// - Some(String) with the reconstructed query
+    /// - None if there are no cached field paths to rebuild from
+    pub fn reconstruct_query(&self) -> Option<String> {
+        let mut root = SelectionNode::default();
+
+        let paths = self.field_paths.iter().flatten();
+        let column_paths = self.column_usage.iter().flat_map(|usage| usage.keys());
+        for path in paths.chain(column_paths) {
+            root.insert_path(path);
+        }
+
+        if let Some(column_usage) = &self.column_usage {
+            for (path, columns) in column_usage {
+                let node = root.insert_path(path);
+                for &column in columns {
+                    node.columns.insert(resolve_str(column)?);
+                }
+            }
+        }
+
+        if root.children.is_empty() {
+            return None;
+        }
+
+        let keyword = match self.operation_kind {
+            GraphQLOperationKind::Query | GraphQLOperationKind::Introspection => "query",
+            GraphQLOperationKind::InsertMutation
+            | GraphQLOperationKind::UpdateMutation
+            | GraphQLOperationKind::DeleteMutation => "mutation",
+            GraphQLOperationKind::Subscription => "subscription",
+        };
+
+        let mut query = String::from(keyword);
+        query.push(' ');
+        root.write_selection_set(&mut query);
+        Some(query)
+    }
+}
+
+/// Node in the selection tree used to reconstruct a query from cached paths
+#[derive(Default)]
+struct SelectionNode {
+    /// Leaf columns selected on this table/relationship
+    columns: BTreeSet<String>,
+
+    /// Nested tables/relationships keyed by field name
+    children: BTreeMap<String, SelectionNode>,
+}
+
+impl SelectionNode {
+    /// Ensure every segment of `path` exists in the tree and return its node
+    fn insert_path(&mut self, path: &FieldPath) -> &mut SelectionNode {
+        path.iter().fold(self, |node, &symbol_id| {
+            let name = resolve_str(symbol_id).unwrap_or_default();
+            node.children.entry(name).or_default()
+        })
+    }
+
+    /// Write this node's selection set, e.g. `{ id name posts { title } }`
+    fn write_selection_set(&self, out: &mut String) {
+        out.push('{');
+        for column in &self.columns {
+            out.push(' ');
+            out.push_str(column);
+        }
+        for (name, child) in &self.children {
+            out.push(' ');
+            out.push_str(name);
+            out.push(' ');
+            child.write_selection_set(out);
+        }
+        // GraphQL forbids empty selection sets
+        if self.columns.is_empty() && self.children.is_empty() {
+            out.push_str(" __typename");
+        }
+        out.push_str(" }");
+    }
 }
 
 /// Convert ParsedQueryInfo to CachedQueryInfo
@@ -454,10 +1145,22 @@ impl<'a> From<ParsedQueryInfo<'a>> for CachedQueryInfo {
             field_paths: info.field_paths,
             path_index: info.path_index,
             column_usage: info.column_usage,
+            column_arguments: info.column_arguments,
+            function_args: info.function_args,
+            column_aliases: info.column_aliases,
+            json_paths: info.json_paths,
+            args: info.args,
+            argument_names: info.argument_names,
+            set_columns: info.set_columns,
+            filter_columns: info.filter_columns,
+            insert_tree: info.insert_tree,
+            mutation_prefixes: info.mutation_prefixes,
             ast_context: info.ast_context,
             original_query: info.original_query,
             document_ptr: info.document_ptr,
+            reconstructed_document: OnceCell::new(),
             resolution_request: None,
+            encoded_request: None,
         }
     }
 }
@@ -489,6 +1192,41 @@ pub struct ParsedQueryInfo<'a> {
     /// Column usage information keyed by table path
     pub column_usage: Option<HashMap<FieldPath, HashSet<SymbolId>>>,
 
+    /// Arguments passed to columns, keyed by table path and column
+    pub column_arguments: Option<ColumnArguments>,
+
+    /// Arguments passed to function root fields, keyed by field path
+    pub function_args: Option<FunctionArguments>,
+
+    /// Response keys of aliased columns, keyed by table path and column
+    pub column_aliases: Option<ColumnAliases>,
+
+    /// JSON paths projected from scalar columns, keyed by table path and column
+    pub json_paths: Option<ColumnJsonPaths>,
+
+    /// Pagination arguments, keyed by table or relationship path
+    pub args: Option<FieldArguments>,
+
+    /// Every argument name used on any field, when `Config.collect_argument_names` is set
+    pub argument_names: Option<BTreeSet<String>>,
+
+    /// Columns written by UPDATE mutations (`_set`), keyed by table path
+    pub set_columns: Option<ColumnUsage>,
+
+    /// Columns UPDATE mutations match rows on (`where`, `pk_columns`) and the
+    /// key arguments of `delete_*_by_pk`, keyed by table path
+    pub filter_columns: Option<ColumnUsage>,
+
+    /// Tables written by each insert root field, with their nested inserts
+    pub insert_tree: Option<Vec<InsertNode>>,
+
+    /// Per-call mutation prefixes the query was parsed with, applied again
+    /// when generating its SQL
+    pub mutation_prefixes: Option<MutationPrefixes>,
+
+    /// Non-fatal issues met during extraction, such as ignored unknown operators
+    pub warnings: Vec<String>,
+
     /// Raw pointer to the Document - valid as long as ast_context exists
     pub document_ptr: Option<*const Document<'static>>,
 
@@ -513,24 +1251,168 @@ impl<'a> fmt::Debug for ParsedQueryInfo<'a> {
                     .map(|q| format!("{}...", &q[..20.min(q.len())])),
             )
             .field("column_usage", &self.column_usage)
+            .field("column_arguments", &self.column_arguments)
+            .field("function_args", &self.function_args)
+            .field("column_aliases", &self.column_aliases)
+            .field("json_paths", &self.json_paths)
+            .field("args", &self.args)
+            .field("argument_names", &self.argument_names)
+            .field("set_columns", &self.set_columns)
+            .field("filter_columns", &self.filter_columns)
+            .field("insert_tree", &self.insert_tree)
+            .field("mutation_prefixes", &self.mutation_prefixes)
+            .field("warnings", &self.warnings)
             .field("document_ptr", &self.document_ptr.map(|_| "<Document>"))
             .finish()
     }
 }
 
+/// Signature of a query's structure, see `ParsedQueryInfo::structural_signature`
+fn structural_signature(
+    operation_kind: GraphQLOperationKind,
+    document: Option<&Document>,
+    field_paths: Option<&HashSet<FieldPath>>,
+    column_usage: Option<&HashMap<FieldPath, HashSet<SymbolId>>>,
+) -> String {
+    let mut shape = operation_kind.to_string();
+
+    match document {
+        Some(document) => {
+            for definition in document.definitions.iter() {
+                if let Definition::Operation(op) = definition {
+                    shape.push(' ');
+                    shape.push_str(&selection_set_shape(&op.selection_set));
+                }
+            }
+        }
+        None => {
+            let mut paths: Vec<String> = Vec::new();
+            for path in field_paths.into_iter().flatten() {
+                let mut columns: Vec<String> = column_usage
+                    .and_then(|usage| usage.get(path))
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|&column| resolve_str(column))
+                    .collect();
+                columns.sort();
+                let names: Vec<String> = path.iter().filter_map(|&s| resolve_str(s)).collect();
+                paths.push(format!("{}{{{}}}", names.join("."), columns.join(",")));
+            }
+            paths.sort();
+            shape.push(' ');
+            shape.push_str(&paths.join(" "));
+        }
+    }
+
+    format!("{:x}", xxh3_64(shape.as_bytes()))
+}
+
+/// Describe a selection set with fields and arguments in sorted order
+fn selection_set_shape(selection_set: &SelectionSet) -> String {
+    let mut selections: Vec<String> = selection_set
+        .selections
+        .iter()
+        .map(|selection| match selection {
+            Selection::Field(field) => {
+                let mut shape = field.name.to_string();
+                if !field.arguments.is_empty() {
+                    let mut arguments: Vec<String> = field
+                        .arguments
+                        .children
+                        .iter()
+                        .map(|argument| {
+                            format!("{}:{}", argument.name, value_shape(&argument.value))
+                        })
+                        .collect();
+                    arguments.sort();
+                    shape.push_str(&format!("({})", arguments.join(",")));
+                }
+                if !field.selection_set.is_empty() {
+                    shape.push_str(&selection_set_shape(&field.selection_set));
+                }
+                shape
+            }
+            Selection::FragmentSpread(spread) => format!("...{}", spread.name.name),
+            Selection::InlineFragment(fragment) => {
+                format!("...{}", selection_set_shape(&fragment.selection_set))
+            }
+        })
+        .collect();
+    selections.sort();
+    format!("{{{}}}", selections.join(" "))
+}
+
+/// Describe an argument value, replacing literals with a placeholder
+fn value_shape(value: &Value) -> String {
+    match value {
+        Value::Variable(variable) => format!("${}", variable.name),
+        Value::List(list) => {
+            // Lists of literals share a shape regardless of their length
+            let children: BTreeSet<String> = list.children.iter().map(value_shape).collect();
+            let children: Vec<String> = children.into_iter().collect();
+            format!("[{}]", children.join(","))
+        }
+        Value::Object(object) => {
+            let mut fields: Vec<String> = object
+                .children
+                .iter()
+                .map(|field| format!("{}:{}", field.name, value_shape(&field.value)))
+                .collect();
+            fields.sort();
+            format!("{{{}}}", fields.join(","))
+        }
+        _ => String::from("?"),
+    }
+}
+
 impl<'a> ParsedQueryInfo<'a> {
+    /// Print the parsed query as canonical GraphQL text
+    ///
+    /// The output is derived from the AST, so it reflects the fields, arguments,
+    /// and selection order of the query independent of its original formatting.
+    /// This makes it suitable for audit logs where equivalent queries should
+    /// produce identical entries.
+    ///
+    /// # Returns
+    ///
+    /// - Some(String) with the printed document, or the original query text if
+    ///   the document is unavailable
+    /// - None if neither the document nor the original query is available
+    pub fn printed(&self) -> Option<String> {
+        match self.document() {
+            Some(document) => Some(document.print()),
+            None => self.original_query.clone(),
+        }
+    }
+
+    /// Compute a stable signature of the query's structure
+    ///
+    /// Unlike the query id, the signature ignores literal argument values and
+    /// the order of fields, so queries that only differ in their inputs or
+    /// formatting share a signature. Variables are kept by name since they are
+    /// part of the query's interface. This makes the signature suitable for
+    /// grouping metrics and caching query plans by shape.
+    ///
+    /// Falls back to the extracted field paths and column usage when the
+    /// document is unavailable.
+    pub fn structural_signature(&self) -> String {
+        structural_signature(
+            self.operation_kind,
+            self.document(),
+            self.field_paths.as_ref(),
+            self.column_usage.as_ref(),
+        )
+    }
+
     /// Safely get a reference to the Document
-    pub fn document(&self) -> Option<&Document> {
+    pub fn document(&self) -> Option<&Document<'_>> {
         if let (Some(_ctx), Some(ptr)) = (&self.ast_context, self.document_ptr) {
             // Safety: The Document pointer is valid as long as ast_context is alive,
             // which is guaranteed by the Arc we're holding.
             unsafe { Some(&*ptr) }
         } else if let (Some(ctx), Some(query)) = (&self.ast_context, &self.original_query) {
             // Re-parse the query using the stored ASTContext if no document_ptr is available
-            match Document::parse(ctx, query) {
-                Ok(doc) => Some(doc),
-                Err(_) => None,
-            }
+            Document::parse(ctx, query).ok()
         } else {
             None
         }
@@ -545,6 +1427,7 @@ impl fmt::Display for GraphQLOperationKind {
             GraphQLOperationKind::UpdateMutation => write!(f, "update_mutation"),
             GraphQLOperationKind::DeleteMutation => write!(f, "delete_mutation"),
             GraphQLOperationKind::Subscription => write!(f, "subscription"),
+            GraphQLOperationKind::Introspection => write!(f, "introspection"),
         }
     }
 }
@@ -569,16 +1452,8 @@ pub fn initialize_for_test() -> Result<(), String> {
     use crate::config::{Config, CONFIG};
     let default_config = Config {
         aggregate_field_suffix: "_aggregate".to_string(),
-        primary_key_argument_name: "id".to_string(),
-        aggregate_nodes_field_name: "nodes".to_string(),
-        insert_prefix: "insert_".to_string(),
-        update_prefix: "update_".to_string(),
-        delete_prefix: "delete_".to_string(),
-        operators: std::collections::HashMap::new(),
-        string_interner_capacity: 10000,
-        query_cache_max_size: 1000,
         query_cache_ttl_seconds: 3600,
-        max_query_depth: 10,
+        ..Config::default()
     };
 
     match CONFIG.lock() {
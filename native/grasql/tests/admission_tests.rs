@@ -0,0 +1,106 @@
+use grasql::parser::admission::{check, Policy};
+use grasql::{Config, GraphQLOperationKind};
+
+/// Helper function to ensure GraSQL is initialized before running tests
+fn initialize_grasql() {
+    let _ = grasql::types::initialize_for_test();
+}
+
+/// Assert that `query` is rejected under `config` by `policy`
+fn assert_rejected(query: &str, config: &Config, policy: Policy) {
+    let err = check(query, config).unwrap_err();
+    assert_eq!(err.policy, policy, "{}", err);
+}
+
+#[test]
+fn test_admitted_query_report() {
+    initialize_grasql();
+
+    let query = r#"
+    query Posts {
+        users(where: { _or: [{ name: { _ilike: "a%" } }, { id: { _in: [1, 2] } }] }) {
+            id
+            posts(where: { published: { _eq: true } }) { title }
+        }
+    }
+    "#;
+    let report = check(query, &Config::default()).unwrap();
+
+    assert_eq!(report.operation_kind, GraphQLOperationKind::Query);
+    assert_eq!(report.operations, 1);
+    assert_eq!(report.depth, 3);
+    assert_eq!(report.complexity, 4);
+    assert_eq!(
+        report.operators.into_iter().collect::<Vec<_>>(),
+        ["_eq", "_ilike", "_in", "_or"]
+    );
+}
+
+#[test]
+fn test_each_policy_violation() {
+    initialize_grasql();
+    let config = Config::default();
+
+    assert_rejected("  ", &config, Policy::Syntax);
+    assert_rejected("{ users { id ", &config, Policy::Syntax);
+    assert_rejected(
+        "{ users { ...F } } fragment F on users { id }",
+        &config,
+        Policy::Features,
+    );
+    assert_rejected(
+        "{ users { id } } query B { posts { id } }",
+        &Config {
+            max_operations: Some(1),
+            ..Config::default()
+        },
+        Policy::Operations,
+    );
+    assert_rejected(
+        "{ users { posts { comments { id } } } }",
+        &Config {
+            max_query_depth: 2,
+            ..Config::default()
+        },
+        Policy::Depth,
+    );
+    assert_rejected(
+        "mutation { archive_users { id } }",
+        &config,
+        Policy::OperationKind,
+    );
+    assert_rejected(
+        "mutation { insert_users { id } }",
+        &Config {
+            validate_mutation_shape: true,
+            ..Config::default()
+        },
+        Policy::MutationShape,
+    );
+    assert_rejected(
+        "query($n: String) { users(limit: $n) { id } }",
+        &config,
+        Policy::PaginationVariables,
+    );
+    assert_rejected(
+        "{ users(limit: 1, after: \"c\") { id } }",
+        &config,
+        Policy::Extraction,
+    );
+    assert_rejected(
+        "{ admission_a { admission_b admission_c admission_d } }",
+        &Config {
+            max_new_symbols_per_query: Some(1),
+            ..Config::default()
+        },
+        Policy::NewSymbols,
+    );
+    assert_rejected(
+        "{ users { id name email } }",
+        &Config {
+            max_columns_per_table: Some(2),
+            ..Config::default()
+        },
+        Policy::ColumnsPerTable,
+    );
+}
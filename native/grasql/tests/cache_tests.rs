@@ -1,19 +1,39 @@
 #![cfg(feature = "test-utils")]
 
-use std::sync::{Arc, Barrier};
+use std::sync::{Arc, Barrier, Mutex, MutexGuard};
 use std::thread;
 
 use graphql_query::ast::OperationKind;
 
+use grasql::parser::{parse_batch, parse_graphql, parse_persisted};
+use grasql::types::{BatchMode, CachedQueryInfo, GraphQLOperationKind};
+use grasql::{
+    add_to_cache, add_to_cache_with_request, generate_query_id, get_from_cache,
+    get_matching_from_cache, get_resolution_request, memory_estimate, original_query,
+};
 #[cfg(test)]
-use grasql::insert_raw_for_test;
-use grasql::parser::parse_graphql;
-use grasql::types::{CachedQueryInfo, GraphQLOperationKind, ResolutionRequest};
-use grasql::{add_to_cache, add_to_cache_with_request, generate_query_id, get_from_cache};
+use grasql::{clear_cache_for_test, insert_raw_for_test};
+
+// All tests in this file share the global query cache, so they run one at a time
+static CACHE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+// Helper function to ensure GraSQL is initialized and hold the cache for the test
+fn initialize_grasql() -> MutexGuard<'static, ()> {
+    let guard = CACHE_TEST_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    // Ignore errors if already initialized
+    let _ = grasql::types::initialize_for_test();
+
+    guard
+}
 
 /// Test basic cache functionality
 #[test]
 fn test_basic_cache_functionality() {
+    let _guard = initialize_grasql();
+
     // Parse a simple query
     let query = "{ users { id name } }";
     let (parsed_info, _) = parse_graphql(query).unwrap();
@@ -45,6 +65,8 @@ fn test_basic_cache_functionality() {
 /// Test concurrent cache access
 #[test]
 fn test_concurrent_cache_access() {
+    let _guard = initialize_grasql();
+
     // Parse a query to cache
     let query = "{ users { id email posts { title } } }";
     let (parsed_info, _) = parse_graphql(query).unwrap();
@@ -96,20 +118,35 @@ fn test_concurrent_cache_access() {
 /// Test fallback reparse behavior when document_ptr is not available
 #[test]
 fn test_fallback_reparse_behavior() {
+    let _guard = initialize_grasql();
+
     // Parse a query but manually create a version without document_ptr
     let query = "{ users { id name } }";
     let (parsed_info, _) = parse_graphql(query).unwrap();
 
     // Create a modified copy with no document_ptr
     let modified_info = CachedQueryInfo {
-        operation_kind: parsed_info.operation_kind.clone(),
+        operation_kind: parsed_info.operation_kind,
         operation_name: parsed_info.operation_name.clone(),
         field_paths: parsed_info.field_paths.clone(),
         path_index: parsed_info.path_index.clone(),
         column_usage: parsed_info.column_usage.clone(),
+        column_arguments: parsed_info.column_arguments.clone(),
+        function_args: parsed_info.function_args.clone(),
+        column_aliases: parsed_info.column_aliases.clone(),
+        json_paths: parsed_info.json_paths.clone(),
+        args: parsed_info.args.clone(),
+        argument_names: parsed_info.argument_names.clone(),
+        set_columns: parsed_info.set_columns.clone(),
+        filter_columns: parsed_info.filter_columns.clone(),
+        insert_tree: parsed_info.insert_tree.clone(),
+        mutation_prefixes: parsed_info.mutation_prefixes.clone(),
         ast_context: parsed_info.ast_context.clone(),
         original_query: parsed_info.original_query.clone(),
         document_ptr: None, // Intentionally set to None to test fallback
+        reconstructed_document: Default::default(),
+        resolution_request: None,
+        encoded_request: None,
     };
 
     // Add to cache using our test helper
@@ -128,6 +165,8 @@ fn test_fallback_reparse_behavior() {
 /// Test cache eviction memory safety by filling cache beyond capacity
 #[test]
 fn test_cache_eviction_memory_safety() {
+    let _guard = initialize_grasql();
+
     // This test verifies that memory is properly managed when cache entries are evicted
 
     // First fill the cache with many queries to trigger eviction
@@ -171,11 +210,16 @@ fn test_cache_eviction_memory_safety() {
             "Document should be accessible for recent cache entry"
         );
     }
+
+    // Leave room in the cache for the tests that follow
+    clear_cache_for_test();
 }
 
 /// Test that CachedQueryInfo properly handles cloning and dropping
 #[test]
 fn test_ast_context_droppability() {
+    let _guard = initialize_grasql();
+
     // This test verifies that CachedQueryInfo properly manages its resources
     // when dropped, even when multiple copies exist
 
@@ -220,12 +264,14 @@ fn test_ast_context_droppability() {
 /// Test cache behavior in a high-concurrency scenario with multiple operations
 #[test]
 fn test_high_concurrency_mixed_operations() {
+    let _guard = initialize_grasql();
+
     // Create different query types
-    let queries = vec![
+    let queries = [
         "{ users { id name } }",
         "{ posts { id title } }",
         "{ comments { id content } }",
-        "mutation { createUser(name: \"test\") { id } }",
+        "mutation { insert_users(object: { name: \"test\" }) { id } }",
     ];
 
     // Parse and cache all queries
@@ -286,6 +332,8 @@ fn test_high_concurrency_mixed_operations() {
 /// Test reference counting behavior specifically
 #[test]
 fn test_arc_reference_counting() {
+    let _guard = initialize_grasql();
+
     // Setup - parse query and create cached info
     let query = "{ users { id } }";
     let (parsed_info, _) = parse_graphql(query).unwrap();
@@ -322,8 +370,10 @@ fn test_arc_reference_counting() {
 /// Test high concurrency without artificial delays
 #[test]
 fn test_high_concurrency_without_sleeps() {
+    let _guard = initialize_grasql();
+
     // Parse and cache multiple queries
-    let queries = vec!["{ users { id name } }", "{ posts { id title } }"];
+    let queries = ["{ users { id name } }", "{ posts { id title } }"];
 
     let ids: Vec<_> = queries
         .iter()
@@ -383,6 +433,8 @@ fn test_high_concurrency_without_sleeps() {
 /// Test document validity across thread boundaries
 #[test]
 fn test_document_validity_across_threads() {
+    let _guard = initialize_grasql();
+
     // Parse and cache a query
     let query = "{ users { id posts { title comments { content } } } }";
     let (parsed_info, _) = parse_graphql(query).unwrap();
@@ -441,6 +493,8 @@ fn test_document_validity_across_threads() {
 /// Test that ResolutionRequest is properly cached and retrieved
 #[test]
 fn test_resolution_request_caching() {
+    let _guard = initialize_grasql();
+
     // Parse a simple query
     let query = "{ users { id name } }";
     let (parsed_info, resolution_request) = parse_graphql(query).unwrap();
@@ -504,3 +558,138 @@ fn test_resolution_request_caching() {
         "Cached ResolutionRequest should have the same ops"
     );
 }
+
+/// Test that lazy clients can fetch the resolution request on demand
+#[test]
+fn test_lazy_resolution_request_on_demand() {
+    let _guard = initialize_grasql();
+
+    // Eager by default
+    assert!(!grasql::Config::default().lazy_resolution);
+
+    let query = "{ posts { id title author { name } } }";
+    let query_id = generate_query_id(query);
+    assert!(get_resolution_request(&query_id).is_none());
+
+    // A lazy cache hit only needs the operation info held by the cache
+    let (parsed_info, resolution_request) = parse_graphql(query).unwrap();
+    add_to_cache_with_request(&query_id, parsed_info, resolution_request.clone());
+    let cached_info = get_from_cache(&query_id).unwrap();
+    assert_eq!(cached_info.operation_kind, GraphQLOperationKind::Query);
+    assert_eq!(cached_info.operation_name, None);
+
+    // The on-demand call returns the full request
+    let on_demand = get_resolution_request(&query_id).unwrap();
+    assert_eq!(on_demand.query_id, query_id);
+    assert_eq!(on_demand.strings, resolution_request.strings);
+    assert_eq!(on_demand.paths, resolution_request.paths);
+    assert_eq!(on_demand.path_types, resolution_request.path_types);
+    assert_eq!(on_demand.entities, resolution_request.entities);
+    assert_eq!(on_demand.cols, resolution_request.cols);
+    assert_eq!(on_demand.ops, resolution_request.ops);
+}
+
+/// Test that a cache entry stored under a colliding query ID is not returned
+#[test]
+fn test_query_id_collision_reparses() {
+    let _guard = initialize_grasql();
+
+    let cached_query = "{ users { id name } }";
+    let incoming_query = "{ posts { id title } }";
+    let query_id = generate_query_id(incoming_query);
+
+    // Simulate a collision: another query's entry under the incoming query's ID
+    let (parsed_info, _) = parse_graphql(cached_query).unwrap();
+    insert_raw_for_test(&query_id, parsed_info.into());
+
+    assert!(get_matching_from_cache(&query_id, incoming_query).is_none());
+    assert!(get_from_cache(&query_id).is_some());
+
+    // The batch parser re-parses instead of returning the wrong document
+    let results = parse_batch(&[incoming_query], BatchMode::PerItem, None).unwrap();
+    let (info, hit) = results[0].as_ref().unwrap();
+    assert!(!hit);
+    assert_eq!(info.original_query.as_deref(), Some(incoming_query));
+
+    // The re-parsed entry replaces the colliding one
+    let cached = get_matching_from_cache(&query_id, incoming_query).unwrap();
+    assert_eq!(cached.original_query.as_deref(), Some(incoming_query));
+
+    // Formatting differences are not collisions
+    let reformatted = "{\n  posts {\n    id\n    title\n  }\n}";
+    assert!(get_matching_from_cache(&query_id, reformatted).is_some());
+}
+
+/// Test that the memory estimate grows as large queries are cached
+#[test]
+fn test_memory_estimate_grows_with_cached_queries() {
+    let _guard = initialize_grasql();
+
+    let before = memory_estimate();
+
+    // Build several large, distinct queries
+    let mut total_query_bytes = 0;
+    for i in 0..5 {
+        let columns: Vec<String> = (0..200).map(|c| format!("column_{}_{}", i, c)).collect();
+        let query = format!("{{ table_{} {{ {} }} }}", i, columns.join(" "));
+        total_query_bytes += query.len();
+
+        let (parsed_info, resolution_request) = parse_graphql(&query).unwrap();
+        let query_id = generate_query_id(&query);
+        add_to_cache_with_request(&query_id, parsed_info, resolution_request);
+    }
+
+    let after = memory_estimate();
+
+    // At minimum, the original query text of every new entry is counted
+    assert!(
+        after >= before + total_query_bytes,
+        "Memory estimate should grow by at least the cached query text ({} -> {}, {} bytes of queries)",
+        before,
+        after,
+        total_query_bytes
+    );
+}
+
+/// Test parsing persisted queries sent by query ID alone
+#[test]
+fn test_parse_persisted() {
+    let _guard = initialize_grasql();
+
+    // Miss: never parsed, so the client has to send the full text
+    let query = "query Persisted { users { id posts { title } } }";
+    let query_id = generate_query_id(query);
+    assert!(parse_persisted(&query_id).is_none());
+
+    // Hit: the operation info and request of the earlier parse
+    let (parsed_info, resolution_request) = parse_graphql(query).unwrap();
+    add_to_cache_with_request(&query_id, parsed_info.clone(), resolution_request.clone());
+    let (cached_info, request) = parse_persisted(&query_id).unwrap().unwrap();
+    assert_eq!(cached_info.operation_kind, GraphQLOperationKind::Query);
+    assert_eq!(cached_info.operation_name.as_deref(), Some("Persisted"));
+    assert_eq!(request.query_id, query_id);
+    assert_eq!(request.paths, resolution_request.paths);
+
+    // An entry cached without a request gets one rebuilt from its text
+    let other = "{ comments { id author { name } } }";
+    let other_id = generate_query_id(other);
+    add_to_cache(&other_id, parse_graphql(other).unwrap().0);
+    let (_, rebuilt) = parse_persisted(&other_id).unwrap().unwrap();
+    assert_eq!(rebuilt.query_id, other_id);
+    assert_eq!(rebuilt.relationships().len(), 1);
+}
+
+#[test]
+fn test_original_query() {
+    let _guard = initialize_grasql();
+
+    // The text as submitted, not its normalized form
+    let query =
+        "query Original {\n  users(where: { name: { _eq: \"Ann\" } }) {\n    id # key\n  }\n}";
+    let query_id = generate_query_id(query);
+    assert_eq!(original_query(&query_id), None);
+
+    let (parsed_info, resolution_request) = parse_graphql(query).unwrap();
+    add_to_cache_with_request(&query_id, parsed_info, resolution_request);
+    assert_eq!(original_query(&query_id).as_deref(), Some(query));
+}
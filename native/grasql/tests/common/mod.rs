@@ -0,0 +1,189 @@
+//! Shared fixtures for the SQL generation integration tests
+
+use grasql::interning::resolve_str;
+use grasql::parser::parse_graphql;
+use grasql::sql::{generate_sql, SqlStatement};
+use grasql::types::{CachedQueryInfo, ResolutionResponse};
+
+/// Helper function to ensure GraSQL is initialized before running tests
+pub fn initialize_grasql() {
+    let _ = grasql::types::initialize_for_test();
+}
+
+/// Entity type marking a path the schema could not resolve
+const UNRESOLVED: u8 = u8::MAX;
+
+/// Relationship fixture:
+/// (source table, field, target table, type code, source columns, target columns)
+pub type Relationship = (
+    &'static str,
+    &'static str,
+    &'static str,
+    u8,
+    &'static [&'static str],
+    &'static [&'static str],
+);
+
+/// Hand-written resolved schema used in place of the Elixir resolver
+pub struct Schema {
+    /// (table name, GraphQL type name)
+    pub tables: &'static [(&'static str, &'static str)],
+    pub relationships: &'static [Relationship],
+}
+
+pub const SCHEMA: Schema = Schema {
+    tables: &[
+        ("users", "User"),
+        ("posts", "Post"),
+        ("comments", "Comment"),
+        ("profiles", "Profile"),
+        ("categories", "Category"),
+        ("employees", "Employee"),
+        ("orders", "Order"),
+    ],
+    relationships: &[
+        ("users", "posts", "posts", 2, &["id"], &["user_id"]),
+        ("users", "profile", "profiles", 1, &["id"], &["user_id"]),
+        ("posts", "author", "users", 0, &["user_id"], &["id"]),
+        ("posts", "comments", "comments", 2, &["id"], &["post_id"]),
+        (
+            "posts",
+            "category",
+            "categories",
+            0,
+            &["category_id"],
+            &["id"],
+        ),
+        ("comments", "author", "users", 0, &["user_id"], &["id"]),
+        ("comments", "post", "posts", 0, &["post_id"], &["id"]),
+        ("profiles", "user", "users", 0, &["user_id"], &["id"]),
+        ("categories", "posts", "posts", 2, &["id"], &["category_id"]),
+        (
+            "employees",
+            "manager",
+            "employees",
+            0,
+            &["manager_id"],
+            &["id"],
+        ),
+        (
+            "employees",
+            "reports",
+            "employees",
+            2,
+            &["id"],
+            &["manager_id"],
+        ),
+    ],
+};
+
+impl Schema {
+    /// Resolve every path of a parsed query against this schema
+    pub fn resolve(&self, info: &CachedQueryInfo) -> ResolutionResponse {
+        let mut response = ResolutionResponse::new();
+        response.query_id = info.resolution_request.as_ref().unwrap().query_id.clone();
+
+        let string = |value: &str, strings: &mut Vec<String>| -> u32 {
+            match strings.iter().position(|s| s == value) {
+                Some(idx) => idx as u32,
+                None => {
+                    strings.push(value.to_string());
+                    (strings.len() - 1) as u32
+                }
+            }
+        };
+
+        for (name, typename) in self.tables {
+            let schema_idx = string("public", &mut response.strings);
+            let name_idx = string(name, &mut response.strings);
+            let typename_idx = string(typename, &mut response.strings);
+            response.tables.push((schema_idx, name_idx, typename_idx));
+
+            // Every fixture table is keyed by `id`
+            let table_idx = (response.tables.len() - 1) as u32;
+            response
+                .pks
+                .push((table_idx, vec![string("id", &mut response.strings)]));
+        }
+        let table_idx = |name: &str| self.tables.iter().position(|(t, _)| *t == name).unwrap();
+
+        // Mutation root fields resolve to the table behind their prefix
+        // and `_by_pk` suffix; `add_` is the insert prefix override tests use
+        let root_table = |name: &str| {
+            let name = name.strip_suffix("_by_pk").unwrap_or(name);
+            ["", "insert_", "update_", "delete_", "add_"]
+                .iter()
+                .filter_map(|prefix| name.strip_prefix(prefix))
+                .find_map(|name| self.tables.iter().position(|(t, _)| *t == name))
+        };
+
+        let path_index = info.path_index.as_ref().unwrap();
+        // Column filters also produce paths; they stay unresolved
+        response.path_map = vec![(UNRESOLVED, 0); path_index.len()];
+
+        for (path, &path_id) in path_index {
+            let segments: Vec<String> = path.iter().map(|&s| resolve_str(s).unwrap()).collect();
+
+            let Some(root) = root_table(&segments[0]) else {
+                continue;
+            };
+            if segments.len() == 1 {
+                response.path_map[path_id] = (0, root as u32);
+                continue;
+            }
+
+            // Walk the relationships from the root table; a mutation's
+            // `returning` holds rows of that table
+            let mut table = self.tables[root].0;
+            let mut rel = None;
+            for segment in segments[1..].iter().filter(|s| *s != "returning") {
+                rel = self
+                    .relationships
+                    .iter()
+                    .position(|(src, field, ..)| *src == table && field == segment);
+                match rel {
+                    Some(idx) => table = self.relationships[idx].2,
+                    None => break,
+                }
+            }
+            let Some(rel) = rel else { continue };
+
+            let (src, _, tgt, type_code, src_cols, tgt_cols) = self.relationships[rel];
+            let src_cols = src_cols
+                .iter()
+                .map(|c| string(c, &mut response.strings))
+                .collect();
+            let tgt_cols = tgt_cols
+                .iter()
+                .map(|c| string(c, &mut response.strings))
+                .collect();
+            response.rels.push((
+                table_idx(src) as u32,
+                table_idx(tgt) as u32,
+                type_code,
+                -1,
+                src_cols,
+                tgt_cols,
+            ));
+            response.path_map[path_id] = (1, (response.rels.len() - 1) as u32);
+        }
+
+        response
+    }
+}
+
+/// Parse a query and resolve it against `SCHEMA`
+pub fn resolve_query(query: &str) -> (CachedQueryInfo, ResolutionResponse) {
+    let (parsed, request) = parse_graphql(query).unwrap();
+    let mut info: CachedQueryInfo = parsed.into();
+    info.resolution_request = Some(request);
+
+    let response = SCHEMA.resolve(&info);
+    (info, response)
+}
+
+/// Parse a query, resolve it against `SCHEMA` and generate its SQL
+pub fn generate(query: &str) -> Vec<SqlStatement> {
+    let (info, response) = resolve_query(query);
+    generate_sql(&info, &response).unwrap()
+}
@@ -0,0 +1,118 @@
+//! Interner compaction invalidates symbol IDs held elsewhere, so it gets a
+//! test binary of its own rather than sharing one with tests that intern
+
+use grasql::interning::{compact, hold_symbols, interned_count};
+use grasql::parser::parse_graphql;
+use grasql::types::{initialize_for_test, FieldPath};
+use grasql::{
+    add_to_cache_with_request, clear_cache_for_test, get_all_strings, get_from_cache, intern_str,
+    resolve_str, symbol_index,
+};
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Compaction affects every test in the binary, so they take turns
+static SERIAL: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_compact_keeps_retained_symbols() {
+    let _serial = SERIAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    // Cached queries would keep their symbols too
+    clear_cache_for_test();
+
+    let symbols: Vec<_> = (0..1000)
+        .map(|i| intern_str(&format!("compaction_{}", i)))
+        .collect();
+    let retained: Vec<_> = symbols.iter().copied().step_by(100).collect();
+
+    let remap = compact(&retained.iter().copied().collect::<HashSet<_>>()).unwrap();
+
+    assert_eq!(remap.len(), retained.len());
+    assert_eq!(interned_count(), retained.len());
+    for (i, old) in retained.iter().enumerate() {
+        let new = remap[old];
+        assert_eq!(
+            resolve_str(new).as_deref(),
+            Some(format!("compaction_{}", i * 100).as_str())
+        );
+        // Retained strings keep their order and get dense IDs
+        assert_eq!(symbol_index(new) as usize, i);
+    }
+
+    // Dropped strings are gone; interning one again gives it a fresh ID
+    let strings = get_all_strings();
+    assert!(!strings.contains(&"compaction_1".to_string()));
+    assert_eq!(
+        symbol_index(intern_str("compaction_1")) as usize,
+        retained.len()
+    );
+}
+
+#[test]
+fn test_compact_keeps_cached_queries() {
+    let _serial = SERIAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    initialize_for_test().unwrap();
+    clear_cache_for_test();
+
+    let query = "{ compacted_users { compacted_id compacted_posts { compacted_title } } }";
+    let (info, request) = parse_graphql(query).unwrap();
+    let query_id = request.query_id.clone();
+    let paths_before = info.field_paths.clone().unwrap();
+    add_to_cache_with_request(&query_id, info, request);
+    intern_str("compaction_dropped");
+
+    compact(&HashSet::new()).unwrap();
+
+    // The cached query keeps its strings under their new IDs
+    assert!(!get_all_strings().contains(&"compaction_dropped".to_string()));
+    let cached = get_from_cache(&query_id).unwrap();
+    let resolve = |paths: &HashSet<FieldPath>| {
+        let mut paths: Vec<Vec<String>> = paths
+            .iter()
+            .map(|path| path.iter().map(|&id| resolve_str(id).unwrap()).collect())
+            .collect();
+        paths.sort();
+        paths
+    };
+    let paths_after = cached.field_paths.clone().unwrap();
+    assert_eq!(
+        resolve(&paths_after),
+        vec![
+            vec!["compacted_users".to_string()],
+            vec!["compacted_users".to_string(), "compacted_posts".to_string()],
+        ]
+    );
+    assert_ne!(paths_after, paths_before);
+    let users = paths_after.iter().find(|path| path.len() == 1).unwrap();
+    let columns = &cached.column_usage.as_ref().unwrap()[users];
+    assert!(columns.contains(&intern_str("compacted_id")));
+
+    // Parsing the query again interns to the same IDs
+    let (reparsed, _) = parse_graphql(query).unwrap();
+    assert_eq!(reparsed.field_paths.unwrap(), paths_after);
+
+    // The cached request is still consistent with its own string table
+    let request = cached.resolution_request.unwrap();
+    assert_eq!(request.validate(), Ok(()));
+
+    clear_cache_for_test();
+}
+
+#[test]
+fn test_compact_refuses_while_holding_symbols() {
+    let _serial = SERIAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let symbols = hold_symbols();
+    assert!(compact(&HashSet::new()).is_err());
+    drop(symbols);
+
+    assert!(compact(&HashSet::new()).is_ok());
+}
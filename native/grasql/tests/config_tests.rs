@@ -0,0 +1,73 @@
+use grasql::{Config, PartialConfig};
+use std::collections::HashMap;
+
+#[test]
+fn test_partial_config_fills_defaults() {
+    // An older initializer that only knows about a few keys
+    let partial = PartialConfig {
+        aggregate_field_suffix: Some("_aggregate".to_string()),
+        query_cache_max_size: Some(50),
+        max_columns_per_table: Some(Some(8)),
+        ..PartialConfig::default()
+    };
+
+    let config = partial.into_config();
+    let defaults = Config::default();
+
+    // Provided keys are kept
+    assert_eq!(config.aggregate_field_suffix, "_aggregate");
+    assert_eq!(config.query_cache_max_size, 50);
+    assert_eq!(config.max_columns_per_table, Some(8));
+
+    // Missing keys fall back to the defaults
+    assert_eq!(config.insert_prefix, defaults.insert_prefix);
+    assert_eq!(
+        config.query_cache_ttl_seconds,
+        defaults.query_cache_ttl_seconds
+    );
+    assert_eq!(config.edges_field, defaults.edges_field);
+    assert_eq!(config.operators, defaults.operators);
+}
+
+#[test]
+fn test_partial_config_explicit_values_override_defaults() {
+    let mut operators = HashMap::new();
+    operators.insert("eq".to_string(), "equals".to_string());
+
+    let partial = PartialConfig {
+        operators: Some(operators.clone()),
+        // An explicit nil keeps the limit disabled
+        max_columns_per_table: Some(None),
+        edges_field: Some(String::new()),
+        ..PartialConfig::default()
+    };
+
+    let config = partial.into_config();
+    assert_eq!(config.operators, operators);
+    assert_eq!(config.max_columns_per_table, None);
+    assert!(config.edges_field.is_empty());
+
+    // An empty partial config is exactly the default config
+    let empty = PartialConfig::default().into_config();
+    assert_eq!(
+        empty.string_interner_capacity,
+        Config::default().string_interner_capacity
+    );
+}
+
+#[test]
+fn test_current_config_after_initialization() {
+    grasql::types::initialize_for_test().unwrap();
+
+    let config = grasql::current_config().expect("GraSQL should be initialized");
+    let defaults = Config::default();
+
+    // initialize_for_test overrides these two settings
+    assert_eq!(config.aggregate_field_suffix, "_aggregate");
+    assert_eq!(config.query_cache_ttl_seconds, 3600);
+
+    assert_eq!(config.insert_prefix, defaults.insert_prefix);
+    assert_eq!(config.update_prefix, defaults.update_prefix);
+    assert_eq!(config.delete_prefix, defaults.delete_prefix);
+    assert_eq!(config.max_query_depth, defaults.max_query_depth);
+}
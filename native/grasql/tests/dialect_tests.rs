@@ -0,0 +1,148 @@
+use grasql::sql::{MySqlDialect, ParamType, PostgresDialect, SqlDialect};
+use grasql::NullOrdering;
+
+#[test]
+fn test_postgres_dialect() {
+    let dialect = PostgresDialect;
+
+    assert_eq!(dialect.quote_ident("users"), r#""users""#);
+    assert_eq!(dialect.quote_ident(r#"odd"name"#), r#""odd""name""#);
+    assert_eq!(dialect.placeholder(1), "$1");
+    assert_eq!(dialect.placeholder(12), "$12");
+    assert_eq!(dialect.json_agg("t1.x"), "coalesce(json_agg(t1.x), '[]')");
+    assert_eq!(
+        dialect.json_object(&[("it's".to_string(), "t1.x".to_string())]),
+        "json_build_object('it''s', t1.x)"
+    );
+    assert_eq!(dialect.json_field("t0.meta", "city"), "t0.meta -> 'city'");
+    assert_eq!(dialect.cast_type("Int"), "integer");
+    assert_eq!(dialect.cast_type("geometry"), "geometry");
+    assert_eq!(
+        dialect.array_membership("t0.a", "$1", false),
+        "t0.a = ANY($1)"
+    );
+    assert_eq!(
+        dialect.array_membership("t0.a", "$1", true),
+        "t0.a <> ALL($1)"
+    );
+    assert_eq!(
+        dialect.on_conflict_clause(r#""pkey""#, &[], None),
+        Ok(r#"ON CONFLICT ON CONSTRAINT "pkey" DO NOTHING"#.to_string())
+    );
+    assert!(dialect.supports_returning());
+    assert_eq!(dialect.param_type(ParamType::Float), "double precision");
+    assert_eq!(dialect.list_param_type("integer"), "integer[]");
+    assert_eq!(
+        dialect.limit_offset_clause(Some("$1"), Some("$2")),
+        " LIMIT $1 OFFSET $2"
+    );
+    assert_eq!(dialect.limit_offset_clause(None, Some("$1")), " OFFSET $1");
+    assert_eq!(dialect.limit_offset_clause(None, None), "");
+    assert_eq!(dialect.bool_literal(true), "TRUE");
+    assert_eq!(dialect.bool_literal(false), "FALSE");
+    assert_eq!(
+        dialect.distinct_on_clause(&["t0.a".to_string(), "t0.b".to_string()]),
+        Some("DISTINCT ON (t0.a, t0.b)".to_string())
+    );
+    assert_eq!(
+        dialect.order_item("t0.a", false, NullOrdering::DbDefault),
+        "t0.a ASC"
+    );
+    assert_eq!(
+        dialect.order_item("t0.a", true, NullOrdering::NullsFirst),
+        "t0.a DESC NULLS FIRST"
+    );
+    assert_eq!(
+        dialect.order_item("t0.a", false, NullOrdering::NullsLast),
+        "t0.a ASC NULLS LAST"
+    );
+}
+
+#[test]
+fn test_mysql_dialect() {
+    let dialect = MySqlDialect;
+
+    assert_eq!(dialect.quote_ident("users"), "`users`");
+    assert_eq!(dialect.quote_ident("odd`name"), "`odd``name`");
+    assert_eq!(dialect.placeholder(1), "?");
+    assert_eq!(dialect.placeholder(12), "?");
+    assert_eq!(
+        dialect.json_agg("t1.x"),
+        "COALESCE(JSON_ARRAYAGG(t1.x), JSON_ARRAY())"
+    );
+    assert_eq!(
+        dialect.json_object(&[("a".to_string(), "t1.x".to_string())]),
+        "JSON_OBJECT('a', t1.x)"
+    );
+    assert_eq!(
+        dialect.json_field("t0.meta", "city"),
+        r#"JSON_EXTRACT(t0.meta, '$."city"')"#
+    );
+    assert_eq!(dialect.cast_type("String"), "CHAR");
+    assert_eq!(
+        dialect.array_membership("t0.a", "?", true),
+        "NOT (t0.a MEMBER OF (CAST(? AS JSON)))"
+    );
+    assert_eq!(
+        dialect.on_conflict_clause("`pkey`", &["`a`".to_string()], None),
+        Ok("ON DUPLICATE KEY UPDATE `a` = VALUES(`a`)".to_string())
+    );
+    assert!(dialect
+        .on_conflict_clause("`pkey`", &["`a`".to_string()], Some("t0.`b` = ?"))
+        .is_err());
+    assert!(!dialect.supports_returning());
+    assert_eq!(dialect.param_type(ParamType::BigInt), "BIGINT");
+    assert_eq!(dialect.list_param_type("INT"), "JSON");
+    assert_eq!(
+        dialect.limit_offset_clause(Some("?"), Some("?")),
+        " LIMIT ? OFFSET ?"
+    );
+    assert_eq!(dialect.limit_offset_clause(Some("?"), None), " LIMIT ?");
+    assert_eq!(
+        dialect.limit_offset_clause(None, Some("?")),
+        " LIMIT 18446744073709551615 OFFSET ?"
+    );
+    assert_eq!(dialect.bool_literal(true), "1");
+    assert_eq!(dialect.bool_literal(false), "0");
+    assert_eq!(dialect.distinct_on_clause(&["t0.a".to_string()]), None);
+    assert_eq!(
+        dialect.order_item("t0.a", true, NullOrdering::DbDefault),
+        "t0.a DESC"
+    );
+    // NULL placement is emulated by sorting on IS NULL first
+    assert_eq!(
+        dialect.order_item("t0.a", true, NullOrdering::NullsFirst),
+        "t0.a IS NULL DESC, t0.a DESC"
+    );
+    assert_eq!(
+        dialect.order_item("t0.a", false, NullOrdering::NullsLast),
+        "t0.a IS NULL ASC, t0.a ASC"
+    );
+}
+
+#[test]
+fn test_reserved_words_are_always_quoted() {
+    let postgres = PostgresDialect;
+    let mysql = MySqlDialect;
+
+    assert!(postgres.is_reserved("order"));
+    assert!(postgres.is_reserved("SELECT"));
+    assert!(postgres.is_reserved("user"));
+    assert!(!postgres.is_reserved("users"));
+    // Reserved words differ between databases
+    assert!(!mysql.is_reserved("user"));
+    assert!(mysql.is_reserved("key"));
+
+    assert_eq!(postgres.ident("order", false), r#""order""#);
+    assert_eq!(mysql.ident("order", false), "`order`");
+
+    // Other plain names are only quoted on request
+    assert_eq!(postgres.ident("title", false), "title");
+    assert_eq!(postgres.ident("title", true), r#""title""#);
+    assert_eq!(mysql.ident("title", false), "title");
+
+    // Names the database would fold or reject stay quoted
+    assert_eq!(postgres.ident("userName", false), r#""userName""#);
+    assert_eq!(postgres.ident("2fa", false), r#""2fa""#);
+    assert_eq!(postgres.ident("a-b", false), r#""a-b""#);
+}
@@ -101,6 +101,7 @@ fn test_document_access_with_reparse_fallback() {
     let query = "{ users { id name } }";
 
     // Create an AST context
+    #[allow(clippy::arc_with_non_send_sync)]
     let ctx = Arc::new(ASTContext::new());
 
     // Create a ParsedQueryInfo with no document_ptr but with original_query and ast_context
@@ -113,6 +114,17 @@ fn test_document_access_with_reparse_fallback() {
         original_query: Some(query.to_string()),
         document_ptr: None, // Force re-parsing
         column_usage: None,
+        column_arguments: None,
+        function_args: None,
+        column_aliases: None,
+        json_paths: None,
+        args: None,
+        argument_names: None,
+        set_columns: None,
+        filter_columns: None,
+        insert_tree: None,
+        mutation_prefixes: None,
+        warnings: Vec::new(),
         _phantom: std::marker::PhantomData,
     };
 
@@ -251,3 +263,121 @@ fn test_multiple_document_accesses() {
         );
     }
 }
+
+#[test]
+fn test_printed_query_is_canonical() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let compact =
+        r#"query GetUsers{users(where:{name:{_eq:"John"}},limit:10){id name posts{title}}}"#;
+    let spaced = r#"
+    # Fetch users with their posts
+    query GetUsers {
+        users(
+            where: { name: { _eq: "John" } }
+            limit: 10
+        ) {
+            id
+            name
+            posts {
+                title
+            }
+        }
+    }
+    "#;
+
+    let (compact_info, _) = parse_graphql(compact).unwrap();
+    let (spaced_info, _) = parse_graphql(spaced).unwrap();
+
+    let compact_printed = compact_info.printed().expect("Printed query should exist");
+    let spaced_printed = spaced_info.printed().expect("Printed query should exist");
+
+    // Differently formatted equivalent queries print identically
+    assert_eq!(compact_printed, spaced_printed);
+
+    // The printed form is itself valid GraphQL with the same content
+    assert!(compact_printed.contains("GetUsers"));
+    assert!(parse_graphql(&compact_printed).is_ok());
+}
+
+#[test]
+fn test_document_access_without_pointer_or_original_query() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = "{ users(where: { active: true }) { name id posts { title } } }";
+    let (parsed_info, _) = parse_graphql(query).unwrap();
+
+    // Simulate an entry built without the document pointer or the query text
+    let mut cached_info = CachedQueryInfo::from(parsed_info);
+    cached_info.document_ptr = None;
+    cached_info.original_query = None;
+
+    // The selection is rebuilt from the cached paths, sorted and without arguments
+    let reconstructed = cached_info.reconstruct_query().unwrap();
+    assert_eq!(reconstructed, "query { users { id name posts { title } } }");
+
+    // The rebuilt document lacks arguments, so it never stands in for the original
+    assert!(cached_info.document().is_none());
+
+    // It is only available explicitly, and parsed just once
+    let document = cached_info
+        .reconstructed_document()
+        .expect("Document should be rebuilt from cached paths");
+    check_document_content(document, GraphQLOperationKind::Query);
+    assert!(std::ptr::eq(
+        document,
+        cached_info.reconstructed_document().unwrap()
+    ));
+
+    let operation = document.operation(None).unwrap();
+    let users = operation.selection_set.selections[0].field().unwrap();
+    assert_eq!(users.name, "users");
+
+    // Without cached paths there is nothing to rebuild from
+    let mut cached_info = CachedQueryInfo::from(parse_graphql(query).unwrap().0);
+    cached_info.document_ptr = None;
+    cached_info.original_query = None;
+    cached_info.field_paths = None;
+    cached_info.column_usage = None;
+    assert!(cached_info.reconstruct_query().is_none());
+    assert!(cached_info.reconstructed_document().is_none());
+}
+
+#[test]
+fn test_structural_signature_ignores_literals() {
+    initialize_grasql();
+
+    let signature = |query: &str| parse_graphql(query).unwrap().0.structural_signature();
+
+    let young = signature("{ users(where: { age: { _gt: 30 } }) { id name } }");
+    let old = signature(
+        r#"
+        {
+            users(where: {age: {_gt: 99}}) {
+                name
+                id
+            }
+        }
+        "#,
+    );
+    assert_eq!(
+        young, old,
+        "Literal values and formatting should not matter"
+    );
+
+    let list_short = signature("{ users(where: { id: { _in: [1, 2] } }) { id } }");
+    let list_long = signature("{ users(where: { id: { _in: [3, 4, 5] } }) { id } }");
+    assert_eq!(list_short, list_long, "List lengths should not matter");
+
+    let other_operator = signature("{ users(where: { age: { _lt: 30 } }) { id name } }");
+    assert_ne!(young, other_operator, "Different filters should not match");
+
+    let other_columns = signature("{ users(where: { age: { _gt: 30 } }) { id email } }");
+    assert_ne!(young, other_columns, "Different columns should not match");
+
+    let variable =
+        signature("query($age: Int) { users(where: { age: { _gt: $age } }) { id name } }");
+    assert_ne!(young, variable, "Variables are part of the structure");
+}
@@ -0,0 +1,278 @@
+//! End-to-end snapshots of the parse → resolve → generate pipeline
+//!
+//! Each sample query is parsed, resolved against the hand-written schema in
+//! `common` and turned into SQL. The statements and their bind parameters are
+//! snapshotted with insta, so a change anywhere in the pipeline shows up as a
+//! snapshot diff.
+
+mod common;
+
+use common::{generate, initialize_grasql};
+use grasql::sql::SqlStatement;
+
+/// Sample queries covering the users/posts/comments/profiles/categories schema
+const SAMPLES: &[(&str, &str)] = &[
+    ("simple_select", "{ users { id name } }"),
+    (
+        "named_operation_with_variables",
+        r#"
+        query GetUser($id: Int!) {
+            users(where: { id: { _eq: $id } }) {
+                id
+                name
+                email
+            }
+        }
+        "#,
+    ),
+    (
+        "aliased_root_fields",
+        r#"
+        {
+            admins: users(where: { role: { _eq: "admin" } }) { id }
+            editors: users(where: { role: { _eq: "editor" } }) { id }
+        }
+        "#,
+    ),
+    (
+        "aliased_columns",
+        "{ users { userId: id displayName: name } }",
+    ),
+    (
+        "comparison_operators",
+        r#"
+        {
+            posts(where: {
+                views: { _gt: 100, _lte: 1000 },
+                rating: { _gte: 3.5 },
+                status: { _neq: "draft" }
+            }) {
+                id
+                title
+            }
+        }
+        "#,
+    ),
+    (
+        "pattern_matching",
+        r#"
+        {
+            users(where: {
+                name: { _like: "%John%" },
+                email: { _ilike: "%@example.com" }
+            }) {
+                id
+            }
+        }
+        "#,
+    ),
+    (
+        "in_literal_list",
+        r#"
+        {
+            posts(where: { status: { _in: ["published", "archived"] } }) {
+                id
+            }
+        }
+        "#,
+    ),
+    (
+        "in_variable_list",
+        r#"
+        query Posts($ids: [Int!]) {
+            posts(where: { id: { _in: $ids }, category_id: { _nin: $ids } }) {
+                id
+            }
+        }
+        "#,
+    ),
+    (
+        "null_checks",
+        r#"
+        {
+            users(where: { deleted_at: { _is_null: true }, email: { _is_null: false } }) {
+                id
+            }
+        }
+        "#,
+    ),
+    (
+        "logical_operators",
+        r#"
+        {
+            users(where: {
+                _and: [
+                    { _or: [{ age: { _lt: 18 } }, { age: { _gt: 65 } }] },
+                    { _not: { status: { _eq: "banned" } } }
+                ]
+            }) {
+                id
+            }
+        }
+        "#,
+    ),
+    (
+        "has_many_selection",
+        r#"
+        {
+            users {
+                id
+                posts {
+                    id
+                    title
+                }
+            }
+        }
+        "#,
+    ),
+    (
+        "has_one_selection",
+        r#"
+        {
+            users {
+                name
+                profile {
+                    bio
+                    avatar
+                }
+            }
+        }
+        "#,
+    ),
+    (
+        "belongs_to_selection",
+        r#"
+        {
+            posts {
+                title
+                author { name }
+                category { name }
+            }
+        }
+        "#,
+    ),
+    (
+        "deeply_nested_selection",
+        r#"
+        {
+            categories {
+                name
+                posts {
+                    title
+                    comments {
+                        body
+                        author {
+                            name
+                            profile { bio }
+                        }
+                    }
+                }
+            }
+        }
+        "#,
+    ),
+    (
+        "nested_relationship_filter",
+        r#"
+        {
+            users {
+                id
+                posts(where: { published: { _eq: true } }) {
+                    title
+                    comments(where: { approved: { _eq: true } }) { body }
+                }
+            }
+        }
+        "#,
+    ),
+    (
+        "to_many_exists_filter",
+        r#"
+        {
+            categories(where: { posts: { comments: { body: { _ilike: "%great%" } } } }) {
+                id
+                name
+            }
+        }
+        "#,
+    ),
+    (
+        "to_one_key_filter",
+        r#"
+        {
+            comments(where: { post: { category: { name: { _eq: $category } } } }) {
+                id
+                body
+            }
+        }
+        "#,
+    ),
+    (
+        "typename_selection",
+        r#"
+        {
+            posts {
+                __typename
+                id
+                author { __typename name }
+            }
+        }
+        "#,
+    ),
+    (
+        "insert_mutation",
+        r#"
+        mutation {
+            insert_posts(objects: [
+                { title: "Hello", body: "World", category_id: 1 },
+                { title: "Draft" }
+            ]) {
+                returning { id title }
+            }
+        }
+        "#,
+    ),
+    (
+        "upsert_mutation",
+        r#"
+        mutation UpsertCategory($name: String!) {
+            insert_categories(
+                object: { slug: "news", name: $name },
+                on_conflict: { constraint: categories_slug_key, update_columns: [name] }
+            ) {
+                returning { id slug name }
+            }
+        }
+        "#,
+    ),
+];
+
+/// Render statements and their parameters as a stable snapshot body
+fn render(statements: &[SqlStatement]) -> String {
+    statements
+        .iter()
+        .map(|statement| {
+            let params = statement
+                .params
+                .iter()
+                .enumerate()
+                .map(|(i, param)| format!("${} = {:?}", i + 1, param))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("-- {}\n{}\n{}", statement.name, statement.sql, params)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[test]
+fn test_sample_query_snapshots() {
+    initialize_grasql();
+
+    assert_eq!(SAMPLES.len(), 20);
+    for (name, query) in SAMPLES {
+        let statements = generate(query);
+        assert!(!statements.is_empty(), "{} produced no statements", name);
+        insta::assert_snapshot!(*name, render(&statements), query.trim());
+    }
+}
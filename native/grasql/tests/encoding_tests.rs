@@ -0,0 +1,284 @@
+use grasql::encoding::{
+    decode_path, decode_paths, encode_paths, validate_path_dir, EncodedPaths, PATH_FORMAT_VERSION,
+    PATH_TYPE_RELATIONSHIP, PATH_TYPE_TABLE,
+};
+use grasql::parser::parse_graphql;
+use proptest::prelude::*;
+
+// Helper function to ensure GraSQL is initialized before running tests
+fn initialize_grasql() {
+    // Ignore errors if already initialized
+    let _ = grasql::types::initialize_for_test();
+}
+
+#[test]
+fn test_encode_paths_layout() {
+    let encoded = encode_paths(vec![
+        (vec![0], PATH_TYPE_TABLE),
+        (vec![0, 3], PATH_TYPE_RELATIONSHIP),
+        (vec![0, 3, 5], PATH_TYPE_RELATIONSHIP),
+    ]);
+
+    assert_eq!(encoded.paths, vec![1, 0, 2, 0, 3, 3, 0, 3, 5]);
+    assert_eq!(encoded.path_dir, vec![0, 2, 5]);
+    assert_eq!(encoded.path_types, vec![0, 1, 1]);
+
+    assert_eq!(
+        decode_path(&encoded.paths, &encoded.path_dir, 1),
+        Some(&[0, 3][..])
+    );
+    assert_eq!(decode_path(&encoded.paths, &encoded.path_dir, 3), None);
+}
+
+#[test]
+fn test_decode_paths_rejects_other_versions() {
+    let encoded = encode_paths(vec![(vec![0], PATH_TYPE_TABLE)]);
+
+    let err = decode_paths(PATH_FORMAT_VERSION + 1, &encoded).unwrap_err();
+    assert_eq!(
+        err,
+        format!(
+            "Unsupported path format version {} (expected {})",
+            PATH_FORMAT_VERSION + 1,
+            PATH_FORMAT_VERSION
+        )
+    );
+}
+
+#[test]
+fn test_decode_paths_rejects_inconsistent_arrays() {
+    let truncated = EncodedPaths {
+        paths: vec![2, 0],
+        path_dir: vec![0],
+        path_types: vec![PATH_TYPE_RELATIONSHIP],
+    };
+    assert_eq!(
+        decode_paths(PATH_FORMAT_VERSION, &truncated).unwrap_err(),
+        "Path 0 is out of bounds"
+    );
+
+    let untyped = EncodedPaths {
+        paths: vec![1, 0],
+        path_dir: vec![0],
+        path_types: vec![],
+    };
+    assert_eq!(
+        decode_paths(PATH_FORMAT_VERSION, &untyped).unwrap_err(),
+        "Path directory has 1 entries but 0 path types"
+    );
+}
+
+#[test]
+fn test_parsed_request_decodes() {
+    initialize_grasql();
+
+    let (_, request) = parse_graphql("{ users { id posts { title } } }").unwrap();
+    assert_eq!(request.version, PATH_FORMAT_VERSION);
+
+    let encoded = EncodedPaths {
+        paths: request.paths.clone(),
+        path_dir: request.path_dir.clone(),
+        path_types: request.path_types.clone(),
+    };
+    let mut decoded: Vec<(Vec<&str>, u8)> = decode_paths(request.version, &encoded)
+        .unwrap()
+        .into_iter()
+        .map(|(segments, path_type)| {
+            let names = segments
+                .iter()
+                .map(|&idx| request.strings[idx as usize].as_str())
+                .collect();
+            (names, path_type)
+        })
+        .collect();
+    decoded.sort();
+
+    assert_eq!(
+        decoded,
+        vec![
+            (vec!["users"], PATH_TYPE_TABLE),
+            (vec!["users", "posts"], PATH_TYPE_RELATIONSHIP),
+        ]
+    );
+}
+
+#[test]
+fn test_encode_paths_independent_of_presizing() {
+    let paths = vec![
+        (vec![0], PATH_TYPE_TABLE),
+        (vec![0, 3, 5, 7, 9], PATH_TYPE_RELATIONSHIP),
+        (vec![1], PATH_TYPE_TABLE),
+    ];
+
+    // Sizing comes from the iterator's length hint; an iterator without one
+    // and paths longer than the assumed two segments encode the same
+    let exact = encode_paths(paths.clone());
+    let unhinted = encode_paths(paths.clone().into_iter().filter(|_| true));
+    assert_eq!(exact, unhinted);
+    assert_eq!(exact.paths, vec![1, 0, 5, 0, 3, 5, 7, 9, 1, 1]);
+    assert_eq!(exact.path_dir, vec![0, 2, 8]);
+}
+
+#[test]
+fn test_large_request_is_consistent() {
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users(limit: 10) { id name posts { title comments { body author { name } } } profile { bio } }
+        posts(where: { published: { _eq: true } }) { id title categories { name } }
+        comments { id body }
+        profiles { id avatar }
+    }
+    "#;
+    let (info, request) = parse_graphql(query).unwrap();
+    let field_paths = info.field_paths.as_ref().unwrap();
+
+    // Every per-path array has one entry per path
+    assert_eq!(request.path_dir.len(), field_paths.len());
+    assert_eq!(request.path_types.len(), field_paths.len());
+    assert_eq!(request.entities.len(), field_paths.len());
+
+    let decoded = request.decode_paths().unwrap();
+    for (path_id, names) in decoded.iter().enumerate() {
+        let expected_type = if names.len() == 1 {
+            PATH_TYPE_TABLE
+        } else {
+            PATH_TYPE_RELATIONSHIP
+        };
+        assert_eq!(request.path_types[path_id], expected_type);
+        assert_eq!(
+            request.strings[request.entities[path_id] as usize],
+            *names.last().unwrap()
+        );
+    }
+
+    // Each root table with columns has exactly one cols entry
+    let column_usage = info.column_usage.as_ref().unwrap();
+    let mut tables: Vec<&str> = request
+        .cols
+        .iter()
+        .map(|(table_idx, _)| request.strings[*table_idx as usize].as_str())
+        .collect();
+    tables.sort();
+    assert_eq!(tables, vec!["comments", "posts", "profiles", "users"]);
+    for (table_idx, columns) in &request.cols {
+        let table = request.strings[*table_idx as usize].as_str();
+        let expected = column_usage
+            .iter()
+            .find(|(path, _)| {
+                path.len() == 1 && grasql::resolve_str(path[0]).as_deref() == Some(table)
+            })
+            .map(|(_, columns)| columns.len());
+        assert_eq!(Some(columns.len()), expected);
+    }
+    assert_eq!(request.ops.len(), 4);
+}
+
+proptest! {
+    #[test]
+    fn prop_encode_decode_round_trip(
+        paths in prop::collection::vec(
+            (prop::collection::vec(any::<u32>(), 0..6), 0u8..2),
+            0..12,
+        )
+    ) {
+        let encoded = encode_paths(paths.clone());
+        prop_assert_eq!(decode_paths(PATH_FORMAT_VERSION, &encoded).unwrap(), paths);
+    }
+}
+
+#[test]
+fn test_validate_path_dir() {
+    let encoded = encode_paths(vec![
+        (vec![0], PATH_TYPE_TABLE),
+        (vec![0, 1], PATH_TYPE_RELATIONSHIP),
+    ]);
+    assert_eq!(validate_path_dir(&encoded.paths, &encoded.path_dir), Ok(()));
+
+    // Offsets that repeat or go backwards
+    assert_eq!(
+        validate_path_dir(&encoded.paths, &[0, 0]).unwrap_err(),
+        "Path 1 starts at offset 0, not after path 0 at offset 0"
+    );
+    assert_eq!(
+        validate_path_dir(&encoded.paths, &[2, 0]).unwrap_err(),
+        "Path 1 starts at offset 0, not after path 0 at offset 2"
+    );
+
+    // An offset past the end of the paths
+    assert_eq!(
+        validate_path_dir(&encoded.paths, &[0, 5]).unwrap_err(),
+        "Path 1 is out of bounds"
+    );
+}
+
+#[test]
+fn test_request_validate() {
+    initialize_grasql();
+
+    let (_, request) =
+        parse_graphql("{ users { id posts { title comments { body } } } profiles { bio } }")
+            .unwrap();
+    assert_eq!(request.validate(), Ok(()));
+
+    let mut reordered = request.clone();
+    reordered.path_dir.swap(0, 1);
+    assert!(reordered
+        .validate()
+        .unwrap_err()
+        .contains("not after path 0"));
+
+    let mut shifted = request.clone();
+    *shifted.path_dir.last_mut().unwrap() = shifted.paths.len() as u32;
+    assert_eq!(
+        shifted.validate().unwrap_err(),
+        format!("Path {} is out of bounds", shifted.path_dir.len() - 1)
+    );
+
+    let mut untyped = request.clone();
+    untyped.path_types.pop();
+    assert!(untyped.validate().is_err());
+
+    let mut dangling = request.clone();
+    dangling.paths[1] = dangling.strings.len() as u32;
+    assert_eq!(
+        dangling.validate().unwrap_err(),
+        format!(
+            "Path segment index {} is outside the string table of {} entries",
+            dangling.strings.len(),
+            dangling.strings.len()
+        )
+    );
+}
+
+#[test]
+fn test_request_validate_ops_and_cols() {
+    initialize_grasql();
+
+    let (_, request) = parse_graphql("{ users { id } posts { title } }").unwrap();
+    assert_eq!(request.validate(), Ok(()));
+    let string_count = request.strings.len() as u32;
+
+    // A corrupted index is reported instead of panicking on lookup
+    let mut corrupted = request.clone();
+    corrupted.ops[1].0 = string_count;
+    assert_eq!(
+        corrupted.validate().unwrap_err(),
+        format!(
+            "Operation field index {} is outside the string table of {} entries",
+            string_count, string_count
+        )
+    );
+
+    let mut corrupted = request.clone();
+    corrupted.cols[0].0 = string_count + 1;
+    assert_eq!(
+        corrupted.validate().unwrap_err(),
+        format!(
+            "Table index {} is outside the string table of {} entries",
+            string_count + 1,
+            string_count
+        )
+    );
+}
@@ -0,0 +1,82 @@
+use grasql::etf::{decode_request, encode_request};
+use grasql::parser::parse_graphql_with_config;
+use grasql::types::ResolutionRequest;
+use grasql::{add_to_cache_with_request, generate_query_id, get_from_cache};
+
+fn initialize_grasql() {
+    let _ = grasql::types::initialize_for_test();
+}
+
+#[test]
+fn test_request_round_trip() {
+    initialize_grasql();
+
+    let query = r#"
+    query GetUserWithData($userId: ID!) {
+        users(where: { id: { _eq: $userId } }, limit: 5) {
+            id @bind(type: "uuid")
+            name
+            posts(where: { published: { _eq: true } }) {
+                title
+                comments { body author { name } }
+            }
+        }
+    }
+    "#;
+    let config = grasql::Config {
+        field_directives: vec!["bind".to_string()],
+        ..grasql::Config::default()
+    };
+    let (_, request) = parse_graphql_with_config(query, &config).unwrap();
+    assert!(!request.directives.is_empty());
+
+    let encoded = encode_request(&request);
+    assert_eq!(decode_request(&encoded).unwrap(), request);
+}
+
+#[test]
+fn test_request_term_layout() {
+    let mut request = ResolutionRequest::new();
+    request.query_id = "q".to_string();
+    // A string table long enough for indices beyond a byte
+    request.strings = (0..=300).map(|i| format!("s{}", i)).collect();
+    request.paths = vec![1, 0];
+    request.path_dir = vec![0];
+    request.path_types = vec![0];
+    request.entities = vec![0];
+    // Integers beyond a byte, and negative ones
+    request.directives = vec![(0, -1, 300, vec![(0, "\"uuid\"".to_string())])];
+    assert_eq!(request.validate(), Ok(()));
+    request.warnings = vec!["Ignored unknown filter operator '_eqq'".to_string()];
+    request.argument_names = vec!["where".to_string()];
+
+    let encoded = encode_request(&request);
+
+    // Version byte, a 24-tuple, then the `:query_id` atom and its binary
+    let mut prefix = vec![131, 104, 24, 119, 8];
+    prefix.extend_from_slice(b"query_id");
+    prefix.extend_from_slice(&[109, 0, 0, 0, 1, b'q']);
+    assert!(encoded.starts_with(&prefix));
+
+    assert_eq!(decode_request(&encoded).unwrap(), request);
+
+    // Corrupt input is rejected rather than misread
+    assert!(decode_request(&encoded[..encoded.len() - 1]).is_err());
+    assert!(decode_request(&encoded[1..]).is_err());
+}
+
+#[test]
+fn test_cached_request_is_encoded() {
+    initialize_grasql();
+
+    let query = "{ users { id posts { title } } }";
+    let query_id = generate_query_id(query);
+    let (info, request) = grasql::parser::parse_graphql(query).unwrap();
+    add_to_cache_with_request(&query_id, info, request.clone());
+
+    let cached = get_from_cache(&query_id).unwrap();
+    let encoded = cached
+        .encoded_request
+        .expect("cached request should be encoded");
+    assert_eq!(decode_request(&encoded).unwrap(), request);
+}
@@ -14,104 +14,6 @@ fn create_path(segments: &[&str]) -> FieldPath {
     path
 }
 
-// Generator for valid field names (GraphQL identifiers)
-fn field_name_strategy() -> impl Strategy<Value = String> {
-    // GraphQL identifiers start with a letter or underscore and can contain letters, numbers, and underscores
-    r"[a-zA-Z_][a-zA-Z0-9_]{0,15}".prop_map(|s| s)
-}
-
-// Generator for simple GraphQL leaf fields
-fn leaf_field_strategy() -> impl Strategy<Value = String> {
-    field_name_strategy()
-}
-
-// Generator for simple value literals
-fn simple_value_strategy() -> impl Strategy<Value = String> {
-    prop_oneof![
-        // String value
-        r#""[a-zA-Z0-9_\s]{0,10}""#.prop_map(|s| s),
-        // Integer value
-        r"-?[0-9]{1,5}".prop_map(|s| s),
-        // Boolean value
-        prop_oneof![Just("true".to_string()), Just("false".to_string())],
-    ]
-}
-
-// Generator for simple comparison operators
-fn operator_strategy() -> impl Strategy<Value = String> {
-    prop_oneof![
-        Just("_eq".to_string()),
-        Just("_neq".to_string()),
-        Just("_gt".to_string()),
-        Just("_lt".to_string()),
-        Just("_gte".to_string()),
-        Just("_lte".to_string()),
-        Just("_like".to_string()),
-        Just("_ilike".to_string()),
-        Just("_in".to_string()),
-        Just("_nin".to_string()),
-        Just("_is_null".to_string()),
-    ]
-}
-
-// Generator for simple filter conditions
-fn filter_strategy() -> impl Strategy<Value = String> {
-    (
-        field_name_strategy(),
-        operator_strategy(),
-        simple_value_strategy(),
-    )
-        .prop_map(|(field, op, value)| format!("{{ {}: {{ {}: {} }} }}", field, op, value))
-}
-
-// Recursive generator for nested object fields
-fn nested_fields_strategy(depth: usize) -> impl Strategy<Value = String> {
-    if depth == 0 {
-        // Base case: generate only leaf fields at max depth
-        leaf_fields_strategy(2).prop_map(|s| s).boxed()
-    } else {
-        // Recursive case: generate a mix of leaf fields and nested objects
-        prop_oneof![
-            // Simple leaf fields
-            leaf_fields_strategy(3),
-            // Nested object with child fields
-            (field_name_strategy(), nested_fields_strategy(depth - 1))
-                .prop_map(|(name, fields)| format!("{} {{ {} }}", name, fields))
-        ]
-        .boxed()
-    }
-}
-
-// Generator for a list of leaf fields
-fn leaf_fields_strategy(max_fields: usize) -> impl Strategy<Value = String> {
-    prop::collection::vec(leaf_field_strategy(), 1..max_fields).prop_map(|fields| fields.join(" "))
-}
-
-// Generator for simple argument values
-fn simple_arg_strategy() -> impl Strategy<Value = String> {
-    prop_oneof![
-        // Limit argument
-        (1..100).prop_map(|n| format!("limit: {}", n)),
-        // Offset argument
-        (0..100).prop_map(|n| format!("offset: {}", n)),
-        // Where argument with simple filter
-        filter_strategy().prop_map(|f| format!("where: {}", f)),
-        // Order by argument
-        field_name_strategy().prop_map(|field| format!("order_by: {{ {}: asc }}", field)),
-    ]
-}
-
-// Generator for field arguments
-fn args_strategy() -> impl Strategy<Value = String> {
-    prop::collection::vec(simple_arg_strategy(), 0..3).prop_map(|args| {
-        if args.is_empty() {
-            "".to_string()
-        } else {
-            format!("({})", args.join(", "))
-        }
-    })
-}
-
 // Generator for a valid GraphQL query with controlled nesting
 fn valid_query_strategy() -> impl Strategy<Value = String> {
     r#"[ \t\n]*\{[ \t\n]*[A-Za-z0-9_]+[ \t\n]*\{[ \t\n]*[A-Za-z0-9_]+[ \t\n]*\}[ \t\n]*\}[ \t\n]*"#
@@ -122,7 +24,12 @@ fn valid_query_strategy() -> impl Strategy<Value = String> {
 fn invalid_query_strategy() -> impl Strategy<Value = String> {
     prop_oneof![
         // Missing closing brace
-        valid_query_strategy().prop_map(|s| s.replace("}", "}")),
+        valid_query_strategy().prop_map(|mut s| {
+            if let Some(pos) = s.rfind('}') {
+                s.remove(pos);
+            }
+            s
+        }),
         // Missing closing field brace
         valid_query_strategy().prop_map(|s| {
             let mut chars: Vec<char> = s.chars().collect();
@@ -167,7 +74,7 @@ proptest! {
         let ctx = ASTContext::new();
         if let Ok(document) = Document::parse(&ctx, &query) {
             let mut extractor = FieldPathExtractor::new();
-            let _ = extractor.extract(&document);
+            let _ = extractor.extract(document);
         }
     }
 
@@ -183,11 +90,43 @@ proptest! {
         let ctx = ASTContext::new();
         let document = Document::parse(&ctx, &query).unwrap();
         let mut extractor = FieldPathExtractor::new();
-        let (paths, _) = extractor.extract(&document).unwrap();
+        let (paths, _) = extractor.extract(document).unwrap();
 
         // Ensure we extracted at least one path
         prop_assert!(!paths.is_empty());
     }
+
+    #[test]
+    fn test_resolution_request_paths_round_trip(query in valid_query_strategy()) {
+        // Initialize GraSQL config
+        let _ = grasql::types::initialize_for_test();
+
+        if let Ok((info, request)) = parse_graphql(&query) {
+            // Every encoded index must point into the string table
+            let decoded = request.decode_paths();
+            prop_assert!(decoded.is_some(), "Paths failed to decode: {:?}", request);
+            let decoded = decoded.unwrap();
+            prop_assert_eq!(decoded.len(), info.field_paths.as_ref().unwrap().len());
+
+            for (path_id, path) in decoded.iter().enumerate() {
+                prop_assert!(!path.is_empty());
+                for segment in path {
+                    // Decoded names must be real fields of the query
+                    prop_assert!(
+                        query.contains(segment),
+                        "Decoded segment {:?} not found in {:?}",
+                        segment,
+                        query
+                    );
+                }
+
+                // Decoding agrees with the extracted field path and its path_id
+                let field_path = create_path(path);
+                prop_assert!(info.field_paths.as_ref().unwrap().contains(&field_path));
+                prop_assert_eq!(info.path_index.as_ref().unwrap().get(&field_path), Some(&path_id));
+            }
+        }
+    }
 }
 
 // Property test for parse_graphql with invalid queries (shouldn't panic)
@@ -210,7 +149,7 @@ fn snapshot_test_field_extraction_basic_queries() {
     // Initialize GraSQL config
     let _ = grasql::types::initialize_for_test();
 
-    let queries = vec![
+    let queries = [
         "{ users { id name } }",
         "{ users { id profile { avatar } posts { title } } }",
         "{ users(where: { profile: { avatar: \"something\" } }) { id } }",
@@ -237,7 +176,7 @@ fn snapshot_test_field_extraction_basic_queries() {
         let ctx = ASTContext::new();
         if let Ok(document) = Document::parse(&ctx, query) {
             let mut extractor = FieldPathExtractor::new();
-            let (paths, _) = extractor.extract(&document).unwrap();
+            let (paths, _) = extractor.extract(document).unwrap();
 
             // Use basic assertions instead of snapshots for now
             // The user can run cargo insta review manually to accept snapshots
@@ -339,7 +278,7 @@ fn test_all_operators() {
     let ctx = ASTContext::new();
     if let Ok(document) = Document::parse(&ctx, query) {
         let mut extractor = FieldPathExtractor::new();
-        let (paths, _) = extractor.extract(&document).unwrap();
+        let (paths, _) = extractor.extract(document).unwrap();
 
         // Check that we extract the expected paths
         assert!(!paths.is_empty(), "Paths shouldn't be empty");
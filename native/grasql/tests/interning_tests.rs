@@ -0,0 +1,20 @@
+use grasql::{get_all_strings, intern_str, resolve_str, symbol_index};
+
+#[test]
+fn test_all_strings_in_symbol_key_order() {
+    let names = ["interning_alpha", "interning_beta", "interning_gamma"];
+    let symbols: Vec<_> = names.iter().map(|name| intern_str(name)).collect();
+
+    let strings = get_all_strings();
+    for (name, &symbol) in names.iter().zip(&symbols) {
+        // A symbol's index is its position in the list
+        assert_eq!(strings[symbol_index(symbol) as usize], *name);
+        assert_eq!(resolve_str(symbol).as_deref(), Some(*name));
+    }
+
+    // Interning again neither duplicates nor reorders
+    intern_str("interning_alpha");
+    let again = get_all_strings();
+    assert_eq!(&again[..strings.len()], &strings[..]);
+    assert_eq!(again.iter().filter(|s| *s == "interning_alpha").count(), 1);
+}
@@ -1,6 +1,9 @@
 use graphql_query::ast::{ASTContext, Document, ParseNode};
 use grasql::interning::intern_str;
-use grasql::types::FieldPath;
+use grasql::parser::{parse_graphql, parse_graphql_with_config, parse_graphql_with_prefixes};
+use grasql::types::GraphQLOperationKind;
+use grasql::types::{FieldPath, InsertNode};
+use grasql::{generate_query_id, generate_query_id_with_prefixes, MutationPrefixes};
 
 /// Helper function to initialize GraSQL for tests
 fn initialize_grasql() {
@@ -44,7 +47,7 @@ fn test_insert_mutation_extraction() {
     let mut extractor = grasql::extraction::FieldPathExtractor::new();
     let ctx = ASTContext::new();
     let document = Document::parse(&ctx, query).unwrap();
-    let (_, column_usage) = extractor.extract(&document).unwrap();
+    let (_, column_usage) = extractor.extract(document).unwrap();
 
     // Find users table path
     let users_path = create_path(&["insert_users"]);
@@ -86,7 +89,7 @@ fn test_insert_with_object_param() {
     let mut extractor = grasql::extraction::FieldPathExtractor::new();
     let ctx = ASTContext::new();
     let document = Document::parse(&ctx, query).unwrap();
-    let (_, column_usage) = extractor.extract(&document).unwrap();
+    let (_, column_usage) = extractor.extract(document).unwrap();
 
     // Find user table path
     let user_path = create_path(&["insert_user"]);
@@ -120,7 +123,7 @@ fn test_insert_with_variable() {
 
     // We need to ensure the path is added to field_paths even if no columns
     // are extracted from the variable (since we're just trusting the user)
-    let (field_paths, _) = extractor.extract(&document).unwrap();
+    let (field_paths, _) = extractor.extract(document).unwrap();
 
     // Find user table path
     let user_path = create_path(&["insert_user"]);
@@ -157,7 +160,7 @@ fn test_update_mutation_extraction() {
     let mut extractor = grasql::extraction::FieldPathExtractor::new();
     let ctx = ASTContext::new();
     let document = Document::parse(&ctx, query).unwrap();
-    let (_, column_usage) = extractor.extract(&document).unwrap();
+    let (_, column_usage) = extractor.extract(document).unwrap();
 
     // Find users table path
     let users_path = create_path(&["update_users"]);
@@ -206,7 +209,7 @@ fn test_batch_insert_extraction() {
     let mut extractor = grasql::extraction::FieldPathExtractor::new();
     let ctx = ASTContext::new();
     let document = Document::parse(&ctx, query).unwrap();
-    let (_, column_usage) = extractor.extract(&document).unwrap();
+    let (_, column_usage) = extractor.extract(document).unwrap();
 
     // Find users table path
     let users_path = create_path(&["insert_users"]);
@@ -218,3 +221,264 @@ fn test_batch_insert_extraction() {
     assert!(columns.contains(&intern_str("phone")));
     assert!(columns.contains(&intern_str("status")));
 }
+
+#[test]
+fn test_per_call_mutation_prefix_override() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    mutation {
+        add_users(objects: { name: "John" }) {
+            returning {
+                id
+            }
+        }
+    }
+    "#;
+
+    // Under the global prefixes the root field is not a known mutation
+    assert!(parse_graphql(query).is_err());
+
+    let prefixes = MutationPrefixes {
+        insert_prefix: Some("add_".to_string()),
+        ..MutationPrefixes::default()
+    };
+    let (info, request) = parse_graphql_with_prefixes(query, Some(&prefixes)).unwrap();
+    assert_eq!(info.operation_kind, GraphQLOperationKind::InsertMutation);
+    assert_eq!(request.ops.len(), 1);
+    assert_eq!(request.ops[0].1, 1, "add_users should be an insert");
+
+    // The override applies to this parse only
+    assert!(parse_graphql(query).is_err());
+
+    // Overridden parses never share a cache key with default parses
+    assert_ne!(request.query_id, generate_query_id(query));
+    assert_eq!(
+        generate_query_id_with_prefixes(query, None),
+        generate_query_id(query)
+    );
+}
+
+#[test]
+fn test_on_conflict_update_columns_extraction() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    mutation {
+        insert_users(
+            objects: { id: 1, name: "John" },
+            on_conflict: { constraint: users_pkey, update_columns: [name, email] }
+        ) {
+            affected_rows
+        }
+    }
+    "#;
+
+    let (info, _) = parse_graphql(query).unwrap();
+    let columns = &info.column_usage.as_ref().unwrap()[&create_path(&["insert_users"])];
+
+    // Update columns are resolvable columns of the table
+    assert!(columns.contains(&intern_str("name")));
+    assert!(columns.contains(&intern_str("email")));
+
+    // The constraint name is not a column
+    assert!(!columns.contains(&intern_str("users_pkey")));
+}
+
+#[test]
+fn test_update_set_and_filter_columns() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    mutation {
+        update_users(
+            where: { _and: [{ active: { _eq: false } }, { posts: { views: { _lt: 10 } } }] },
+            _set: { active: true, updated_at: "now()" }
+        ) {
+            affected_rows
+        }
+    }
+    "#;
+
+    let (info, _) = parse_graphql(query).unwrap();
+    let users_path = create_path(&["users"]);
+    let update_path = create_path(&["update_users"]);
+    assert!(info.field_paths.as_ref().unwrap().contains(&update_path));
+
+    // Written columns come only from `_set`
+    let set_columns = &info.set_columns.as_ref().unwrap()[&update_path];
+    assert_eq!(set_columns.len(), 2);
+    assert!(set_columns.contains(&intern_str("active")));
+    assert!(set_columns.contains(&intern_str("updated_at")));
+
+    // Predicate columns come only from `where`, including relationship filters
+    let filter_columns = info.filter_columns.as_ref().unwrap();
+    assert_eq!(filter_columns[&update_path].len(), 1);
+    assert!(filter_columns[&update_path].contains(&intern_str("active")));
+    assert!(!filter_columns[&update_path].contains(&intern_str("updated_at")));
+    let posts_path = create_path(&["update_users", "posts"]);
+    assert!(filter_columns[&posts_path].contains(&intern_str("views")));
+    assert!(!filter_columns.contains_key(&users_path));
+
+    // A bare value is shorthand for `_eq` and names a column, not a relationship
+    let (info, _) =
+        parse_graphql("mutation { update_users(where: { active: true }, _set: { name: \"x\" }) { affected_rows } }")
+            .unwrap();
+    let filter_columns = &info.filter_columns.as_ref().unwrap()[&update_path];
+    assert!(filter_columns.contains(&intern_str("active")));
+    let active_path = create_path(&["update_users", "active"]);
+    assert!(!info.field_paths.as_ref().unwrap().contains(&active_path));
+    assert!(!info
+        .filter_columns
+        .as_ref()
+        .unwrap()
+        .contains_key(&active_path));
+
+    // Primary key columns identify the updated row
+    let query = r#"
+    mutation {
+        update_users_by_pk(pk_columns: { id: 1 }, _set: { name: "Jane" }) {
+            id
+        }
+    }
+    "#;
+    let (info, _) = parse_graphql(query).unwrap();
+    let path = create_path(&["update_users_by_pk"]);
+    let filter_columns = &info.filter_columns.as_ref().unwrap()[&path];
+    assert!(filter_columns.contains(&intern_str("id")));
+    assert!(!filter_columns.contains(&intern_str("name")));
+    assert!(info.set_columns.as_ref().unwrap()[&path].contains(&intern_str("name")));
+
+    // Queries have no update roles
+    let (info, _) = parse_graphql("{ users(where: { active: { _eq: true } }) { id } }").unwrap();
+    assert!(info.set_columns.as_ref().unwrap().is_empty());
+    assert!(info.filter_columns.as_ref().unwrap().is_empty());
+}
+
+#[test]
+fn test_validate_mutation_shape() {
+    initialize_grasql();
+
+    let config = grasql::Config {
+        validate_mutation_shape: true,
+        ..grasql::Config::default()
+    };
+    let check = |query: &str| parse_graphql_with_config(query, &config).map(|_| ());
+
+    // Insert needs rows
+    assert_eq!(
+        check("mutation { insert_users { affected_rows } }"),
+        Err("Insert mutation 'insert_users' requires a 'objects' or 'object' argument".to_string())
+    );
+    assert!(
+        check(r#"mutation { insert_users(objects: [{ name: "a" }]) { affected_rows } }"#).is_ok()
+    );
+    assert!(check(r#"mutation { insert_users_one(object: { name: "a" }) { id } }"#).is_ok());
+
+    // Update needs `_set`
+    assert_eq!(
+        check("mutation { update_users(where: { id: { _eq: 1 } }) { affected_rows } }"),
+        Err("Update mutation 'update_users' requires a '_set' argument".to_string())
+    );
+    assert!(check(
+        r#"mutation { update_users(where: { id: { _eq: 1 } }, _set: { name: "b" }) { affected_rows } }"#
+    )
+    .is_ok());
+
+    // Delete needs a filter or the primary key
+    assert_eq!(
+        check("mutation { delete_users { affected_rows } }"),
+        Err("Delete mutation 'delete_users' requires a 'where' or 'id' argument".to_string())
+    );
+    assert!(
+        check("mutation { delete_users(where: { id: { _eq: 1 } }) { affected_rows } }").is_ok()
+    );
+    assert!(check("mutation { delete_users_by_pk(id: 1) { id } }").is_ok());
+
+    // Every root field is checked, not just the first
+    assert!(check(
+        r#"mutation { insert_users(objects: []) { affected_rows } delete_posts { affected_rows } }"#
+    )
+    .unwrap_err()
+    .contains("delete_posts"));
+
+    // Off by default
+    assert!(parse_graphql_with_config(
+        "mutation { delete_comments { affected_rows } }",
+        &grasql::Config::default()
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_nested_insert_tree() {
+    initialize_grasql();
+
+    let query = r#"
+    mutation {
+        insert_users(objects: [
+            {
+                name: "Ada",
+                posts: { data: [
+                    { title: "First", comments: { data: { body: "Nice" } } },
+                    { title: "Second" }
+                ] },
+                profile: { data: { bio: "Engineer" } }
+            },
+            { name: "Grace", posts: { data: [{ title: "Third" }] } }
+        ]) {
+            affected_rows
+        }
+    }
+    "#;
+    let (parsed_info, _) = parse_graphql(query).unwrap();
+
+    // The tree follows the nesting, each child listed once in order of appearance
+    let node = |path: &[&str], children: Vec<InsertNode>| InsertNode {
+        path: create_path(path),
+        children,
+    };
+    assert_eq!(
+        parsed_info.insert_tree.as_ref().unwrap(),
+        &vec![node(
+            &["insert_users"],
+            vec![
+                node(
+                    &["insert_users", "posts"],
+                    vec![node(&["insert_users", "posts", "comments"], vec![])]
+                ),
+                node(&["insert_users", "profile"], vec![]),
+            ]
+        )]
+    );
+
+    // Nested rows are columns of their relationship, not of the parent table
+    let column_usage = parsed_info.column_usage.as_ref().unwrap();
+    let users_columns = &column_usage[&create_path(&["insert_users"])];
+    assert!(users_columns.contains(&intern_str("name")));
+    assert!(!users_columns.contains(&intern_str("posts")));
+    assert!(column_usage[&create_path(&["insert_users", "posts"])].contains(&intern_str("title")));
+    assert!(
+        column_usage[&create_path(&["insert_users", "posts", "comments"])]
+            .contains(&intern_str("body"))
+    );
+
+    // Each nested table is a path for schema resolution
+    let field_paths = parsed_info.field_paths.as_ref().unwrap();
+    assert!(field_paths.contains(&create_path(&["insert_users", "posts", "comments"])));
+    assert!(field_paths.contains(&create_path(&["insert_users", "profile"])));
+
+    // Queries and flat inserts have no nested inserts
+    let (flat, _) =
+        parse_graphql(r#"mutation { insert_users(objects: { name: "Linus" }) { affected_rows } }"#)
+            .unwrap();
+    assert_eq!(
+        flat.insert_tree.unwrap(),
+        vec![node(&["insert_users"], vec![])]
+    );
+    let (select, _) = parse_graphql("{ users { id } }").unwrap();
+    assert!(select.insert_tree.unwrap().is_empty());
+}
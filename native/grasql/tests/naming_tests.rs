@@ -0,0 +1,103 @@
+use grasql::naming::base_entity_name;
+use grasql::parser::parse_graphql;
+use grasql::Config;
+
+/// Helper function to ensure GraSQL is initialized before running tests
+fn initialize_grasql() {
+    let _ = grasql::types::initialize_for_test();
+}
+
+fn config() -> Config {
+    Config {
+        aggregate_field_suffix: "_aggregate".to_string(),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn test_base_entity_name_plain_field() {
+    assert_eq!(base_entity_name("users", &config()), "users");
+}
+
+#[test]
+fn test_base_entity_name_suffixes() {
+    let config = config();
+    assert_eq!(base_entity_name("users_by_pk", &config), "users");
+    assert_eq!(base_entity_name("users_aggregate", &config), "users");
+    // Only one suffix is stripped
+    assert_eq!(
+        base_entity_name("users_aggregate_by_pk", &config),
+        "users_aggregate"
+    );
+}
+
+#[test]
+fn test_base_entity_name_mutation_prefixes() {
+    let config = config();
+    assert_eq!(base_entity_name("insert_users", &config), "users");
+    assert_eq!(base_entity_name("update_users", &config), "users");
+    assert_eq!(base_entity_name("delete_users", &config), "users");
+}
+
+#[test]
+fn test_base_entity_name_prefix_and_suffix() {
+    let config = config();
+    assert_eq!(base_entity_name("update_users_by_pk", &config), "users");
+    assert_eq!(base_entity_name("delete_users_by_pk", &config), "users");
+    assert_eq!(base_entity_name("insert_users_aggregate", &config), "users");
+}
+
+#[test]
+fn test_base_entity_name_uses_configured_affixes() {
+    let config = Config {
+        insert_prefix: "create".to_string(),
+        by_pk_suffix: "ById".to_string(),
+        aggregate_field_suffix: "Stats".to_string(),
+        ..Config::default()
+    };
+    assert_eq!(base_entity_name("createUsers", &config), "Users");
+    assert_eq!(base_entity_name("usersById", &config), "users");
+    assert_eq!(base_entity_name("usersStats", &config), "users");
+    // Default affixes no longer apply
+    assert_eq!(base_entity_name("insert_users", &config), "insert_users");
+}
+
+#[test]
+fn test_base_entity_name_keeps_bare_affixes() {
+    let config = config();
+    assert_eq!(base_entity_name("insert_", &config), "insert_");
+    assert_eq!(base_entity_name("_by_pk", &config), "_by_pk");
+    assert_eq!(base_entity_name("update__by_pk", &config), "_by_pk");
+}
+
+#[test]
+fn test_resolution_request_entities() {
+    initialize_grasql();
+
+    let (_, request) = parse_graphql(
+        "{ users_by_pk(id: 1) { id } users_aggregate { nodes { id } } users { posts { id } } }",
+    )
+    .unwrap();
+    assert_eq!(request.entities.len(), request.path_types.len());
+
+    // Every root table path resolves the same base entity
+    let entities: Vec<String> = request
+        .path_types
+        .iter()
+        .zip(&request.entities)
+        .filter(|(&path_type, _)| path_type == 0)
+        .map(|(_, &idx)| request.strings[idx as usize].clone())
+        .collect();
+    assert_eq!(entities.len(), 3);
+    assert!(entities.iter().all(|entity| entity == "users"));
+
+    // Relationship paths keep their field name
+    let relationships: Vec<&str> = request
+        .path_types
+        .iter()
+        .zip(&request.entities)
+        .filter(|(&path_type, _)| path_type == 1)
+        .map(|(_, &idx)| request.strings[idx as usize].as_str())
+        .collect();
+    assert!(relationships.contains(&"posts"));
+}
@@ -0,0 +1,75 @@
+use graphql_query::ast::OperationKind;
+use grasql::outline::{query_outline, FieldOutline};
+
+/// Build an expected field outline
+fn field(name: &str, arguments: &[&str], children: Vec<FieldOutline>) -> FieldOutline {
+    FieldOutline {
+        name: name.to_string(),
+        alias: None,
+        arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+        children,
+    }
+}
+
+#[test]
+fn test_nested_query_outline() {
+    let query = r#"
+    query Dashboard($id: Int!) {
+        users(where: { id: { _eq: $id } }, limit: 1) {
+            id
+            recent: posts(order_by: { created_at: desc }) {
+                title
+                comments { body }
+            }
+        }
+    }
+    "#;
+
+    let outline = query_outline(query).unwrap();
+    assert_eq!(outline.len(), 1);
+    assert_eq!(outline[0].kind, OperationKind::Query);
+    assert_eq!(outline[0].name.as_deref(), Some("Dashboard"));
+
+    let mut posts = field(
+        "posts",
+        &["order_by"],
+        vec![
+            field("title", &[], vec![]),
+            field("comments", &[], vec![field("body", &[], vec![])]),
+        ],
+    );
+    posts.alias = Some("recent".to_string());
+
+    assert_eq!(
+        outline[0].fields,
+        vec![field(
+            "users",
+            &["where", "limit"],
+            vec![field("id", &[], vec![]), posts]
+        )]
+    );
+}
+
+#[test]
+fn test_outline_lists_every_operation() {
+    let query = r#"
+    query A { users { id } }
+    mutation B { insert_users(objects: []) { affected_rows } }
+    fragment F on users { name }
+    "#;
+
+    let outline = query_outline(query).unwrap();
+    let kinds: Vec<_> = outline
+        .iter()
+        .map(|op| (op.kind, op.name.as_deref()))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (OperationKind::Query, Some("A")),
+            (OperationKind::Mutation, Some("B")),
+        ]
+    );
+
+    assert!(query_outline("{ users { id ").is_err());
+}
@@ -1,8 +1,15 @@
-use graphql_query::ast::{ASTContext, Document, ParseNode};
-use grasql::extraction::FieldPathExtractor;
+use graphql_query::ast::{ASTContext, Document, ParseNode, PrintNode};
+use grasql::extraction::{extract_simple_selection, FieldPathExtractor, PathKind};
 use grasql::interning::intern_str;
-use grasql::parser::parse_graphql;
-use grasql::types::FieldPath;
+use grasql::parser::{
+    is_blank_query, parse_batch, parse_graphql, parse_graphql_with_config,
+    parse_graphql_with_overrides, parse_graphql_with_variables, validate_query,
+    EMPTY_MUTATION_ERROR, EMPTY_QUERY_ERROR,
+};
+use grasql::types::{ArgValue, BatchMode, FieldArgs, FieldPath, GraphQLOperationKind};
+use grasql::{
+    generate_query_id, generate_query_id_with_prefixes, get_from_cache, MutationPrefixes,
+};
 use std::collections::HashSet;
 
 // Helper function to ensure GraSQL is initialized before running tests
@@ -26,7 +33,7 @@ fn extract_field_paths(query: &str) -> HashSet<FieldPath> {
     let document = Document::parse(&ctx, query).unwrap();
     let mut extractor = FieldPathExtractor::new();
     // Extract only the field paths component from the tuple
-    let (field_paths, _) = extractor.extract(&document).unwrap();
+    let (field_paths, _) = extractor.extract(document).unwrap();
     field_paths
 }
 
@@ -156,6 +163,67 @@ fn test_complex_filters() {
     assert_path_exists(&paths, &["users", "posts", "comments"]);
 }
 
+#[test]
+fn test_null_literal_filter_captures_column() {
+    initialize_grasql();
+
+    // An explicit null is a filter on the column, unlike omitting it
+    let paths = extract_field_paths(
+        "{ users(where: { deleted_at: { _eq: null }, posts: { archived_at: { _neq: null } } }) { id } }",
+    );
+
+    assert_path_exists(&paths, &["users", "deleted_at"]);
+    assert_path_exists(&paths, &["users", "posts"]);
+    assert_path_exists(&paths, &["users", "posts", "archived_at"]);
+}
+
+#[test]
+fn test_cast_filter_captures_column() {
+    initialize_grasql();
+
+    // The type key inside `_cast` is neither a column nor a relationship
+    let paths = extract_field_paths(
+        r#"{ users(where: { zip: { _cast: { String: { _like: "9%" } } } }) { id } }"#,
+    );
+
+    assert_path_exists(&paths, &["users", "zip"]);
+    assert!(!paths
+        .iter()
+        .any(|path| path.contains(&intern_str("String"))));
+    assert_eq!(paths.len(), 2);
+
+    // An UPDATE matching on a cast column filters on the column itself
+    let (info, _) = parse_graphql(
+        r#"mutation { update_users(where: { zip: { _cast: { String: { _eq: "1" } } } }, _set: { active: true }) { affected_rows } }"#,
+    )
+    .unwrap();
+    let filter_columns = &info.filter_columns.as_ref().unwrap()[&create_path(&["update_users"])];
+    assert!(filter_columns.contains(&intern_str("zip")));
+    assert!(!filter_columns.contains(&intern_str("String")));
+}
+
+#[test]
+fn test_enum_operand_captures_column() {
+    initialize_grasql();
+
+    let paths = extract_field_paths(
+        "{ users(where: { status: { _eq: ACTIVE }, role: { _in: [ADMIN, EDITOR] } }) { id } }",
+    );
+    assert_path_exists(&paths, &["users", "status"]);
+    assert_path_exists(&paths, &["users", "role"]);
+    assert!(!paths
+        .iter()
+        .any(|path| path.contains(&intern_str("ACTIVE"))));
+
+    let (info, _) = parse_graphql(
+        "mutation { update_users(where: { status: { _eq: PENDING } }, _set: { status: ACTIVE }) { affected_rows } }",
+    )
+    .unwrap();
+    let update_path = create_path(&["update_users"]);
+    assert!(info.filter_columns.as_ref().unwrap()[&update_path].contains(&intern_str("status")));
+    assert!(info.set_columns.as_ref().unwrap()[&update_path].contains(&intern_str("status")));
+}
+
 #[test]
 fn test_aggregations() {
     // Initialize GraSQL config
@@ -352,6 +420,39 @@ fn test_variables() {
     assert_path_exists(&paths, &["users"]);
 }
 
+#[test]
+fn test_pagination_variables_captured() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    query PagedUsers($limit: Int!, $offset: Int) {
+        users(limit: $limit, offset: $offset) {
+            id
+            posts(limit: 3) { title }
+        }
+    }
+    "#;
+
+    let (info, _) = parse_graphql(query).unwrap();
+    let args = info.args.as_ref().unwrap();
+
+    assert_eq!(
+        args[&create_path(&["users"])],
+        FieldArgs {
+            limit: Some(ArgValue::Variable("limit".to_string())),
+            offset: Some(ArgValue::Variable("offset".to_string())),
+        }
+    );
+    assert_eq!(
+        args[&create_path(&["users", "posts"])],
+        FieldArgs {
+            limit: Some(ArgValue::Literal(3)),
+            offset: None,
+        }
+    );
+}
+
 #[test]
 fn test_aliases() {
     // Initialize GraSQL config
@@ -757,3 +858,1605 @@ fn test_multiple_operations() {
         "Document pointer should be preserved for caching"
     );
 }
+
+#[test]
+fn test_block_string_arguments() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let block = "\"\"\"\n      First line\n        indented,   second line\n    \"\"\"";
+    let query = format!(
+        "{{\n  users(where: {{ bio: {{ _eq: {} }} }}) {{\n    id\n  }}\n}}",
+        block
+    );
+
+    // The filter column is captured despite the multiline literal
+    let paths = extract_field_paths(&query);
+    assert_path_exists(&paths, &["users"]);
+    assert_path_exists(&paths, &["users", "bio"]);
+
+    // The block string survives parsing and prints back with its content intact
+    let ctx = ASTContext::new();
+    let document = Document::parse(&ctx, &query).unwrap();
+    let operation = document.operation(None).unwrap();
+    let users = operation.selection_set.selections[0].field().unwrap();
+    let printed = users.arguments.children[0].value.print();
+    assert!(
+        printed.contains("First line") && printed.contains("  indented,   second line"),
+        "Block string content should round-trip, got: {}",
+        printed
+    );
+
+    // Normalization collapses whitespace outside the literal but leaves it untouched
+    let normalized = grasql::normalize_query(&query);
+    assert!(
+        normalized.contains(block),
+        "Block string should be preserved verbatim, got: {}",
+        normalized
+    );
+    assert!(
+        normalized.starts_with("{ users(where: { bio: { _eq: \"\"\""),
+        "Whitespace outside the literal should be collapsed, got: {}",
+        normalized
+    );
+
+    // Reformatting outside the literal keeps the query ID, changing the literal does not
+    let reformatted = format!(
+        "{{ users(where: {{ bio: {{ _eq: {} }} }}) {{ id }} }}",
+        block
+    );
+    assert_eq!(
+        grasql::generate_query_id(&query),
+        grasql::generate_query_id(&reformatted)
+    );
+
+    let changed = reformatted.replace("indented,   second", "indented, second");
+    assert_ne!(
+        grasql::generate_query_id(&reformatted),
+        grasql::generate_query_id(&changed)
+    );
+    assert!(parse_graphql(&changed).is_ok());
+}
+
+#[test]
+fn test_max_columns_per_table() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let config = grasql::Config {
+        max_columns_per_table: Some(5),
+        ..grasql::Config::default()
+    };
+
+    // A synthetic wide selection trips the limit and names the table
+    let columns: Vec<String> = (0..20).map(|i| format!("col_{}", i)).collect();
+    let wide_query = format!("{{ users {{ id posts {{ {} }} }} }}", columns.join(" "));
+    let err = parse_graphql_with_config(&wide_query, &config).unwrap_err();
+    assert!(
+        err.contains("users.posts") && err.contains("20 columns"),
+        "Error should name the offending table, got: {}",
+        err
+    );
+
+    // A normal selection passes
+    let normal_query = "{ users { id name email posts { id title } } }";
+    assert!(parse_graphql_with_config(normal_query, &config).is_ok());
+
+    // Without a limit the wide selection is accepted
+    assert!(parse_graphql_with_config(&wide_query, &grasql::Config::default()).is_ok());
+}
+
+#[test]
+fn test_json_operator_naming_variants() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users(where: {
+            _and: [
+                { tags: { _has_keys_any: ["a", "b"] } },
+                { labels: { _json_has_any_keys: ["a", "b"] } },
+                { _not: { settings: { _has_key: "theme" } } },
+                { prefs: { _json_has_key: "theme" } },
+                { flags: { _has_keys_all: ["x"] } },
+                { options: { _json_has_all_keys: ["x"] } }
+            ]
+        }) {
+            id
+        }
+    }
+    "#;
+
+    // Each naming variant captures its column
+    let paths = extract_field_paths(query);
+    for column in ["tags", "labels", "settings", "prefs", "flags", "options"] {
+        assert_path_exists(&paths, &["users", column]);
+    }
+
+    // Both naming schemes map to the same SQL operator
+    let config = grasql::Config::default();
+    let pairs = [
+        ("_has_key", "_json_has_key", "?"),
+        ("_has_keys_any", "_json_has_any_keys", "?|"),
+        ("_has_keys_all", "_json_has_all_keys", "?&"),
+        ("_contains", "_json_contains", "@>"),
+        ("_contained_in", "_json_contained_in", "<@"),
+    ];
+    for (hasura, json, sql) in pairs {
+        assert_eq!(config.translate_operator(hasura), Some(sql));
+        assert_eq!(config.translate_operator(json), Some(sql));
+    }
+
+    // Names outside the configured table are not operators
+    assert_eq!(config.translate_operator("_unknown"), None);
+}
+
+#[test]
+fn test_column_arguments() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users {
+            id
+            avatar(size: 100, format: "png")
+            posts(limit: 5) {
+                title
+            }
+        }
+    }
+    "#;
+
+    let (info, request) = parse_graphql(query).unwrap();
+    let users_path = create_path(&["users"]);
+
+    // A scalar field with arguments is still a column, not a relationship
+    let columns = &info.column_usage.as_ref().unwrap()[&users_path];
+    assert!(columns.contains(&intern_str("avatar")));
+    assert!(!info
+        .field_paths
+        .as_ref()
+        .unwrap()
+        .contains(&create_path(&["users", "avatar"])));
+    assert_eq!(
+        request.path_dir.len(),
+        2,
+        "Only users and users.posts should be resolved as paths"
+    );
+
+    // Its arguments are recorded against the column
+    let column_arguments = info.column_arguments.as_ref().unwrap();
+    assert_eq!(
+        column_arguments[&users_path]["avatar"],
+        (
+            intern_str("avatar"),
+            vec![
+                (intern_str("size"), "100".to_string()),
+                (intern_str("format"), "\"png\"".to_string()),
+            ]
+        )
+    );
+
+    // Relationship arguments are not column arguments
+    assert!(!column_arguments[&users_path].contains_key("posts"));
+
+    // Aliases of one column keep their own arguments
+    let (info, _) =
+        parse_graphql("{ users { small: avatar(size: 50) big: avatar(size: 200) } }").unwrap();
+    let column_arguments = &info.column_arguments.as_ref().unwrap()[&users_path];
+    assert_eq!(
+        column_arguments["small"],
+        (
+            intern_str("avatar"),
+            vec![(intern_str("size"), "50".to_string())]
+        )
+    );
+    assert_eq!(
+        column_arguments["big"],
+        (
+            intern_str("avatar"),
+            vec![(intern_str("size"), "200".to_string())]
+        )
+    );
+}
+
+#[test]
+fn test_parse_batch_partial_results() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let valid_a = "{ batch_users_a { id name } }";
+    let invalid = "{ batch_users_b { id ";
+    let valid_c = "{ batch_users_c { id email } }";
+
+    // Per-item mode (the default) returns a result for every query
+    let results = parse_batch(&[valid_a, invalid, valid_c], BatchMode::default(), None).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+    assert!(results[2].is_ok());
+
+    // Only the valid queries were cached
+    assert!(get_from_cache(&generate_query_id(valid_a)).is_some());
+    assert!(get_from_cache(&generate_query_id(invalid)).is_none());
+    assert!(get_from_cache(&generate_query_id(valid_c)).is_some());
+
+    // Fail-fast mode aborts the batch and caches nothing
+    let valid_d = "{ batch_users_d { id } }";
+    let err = parse_batch(&[valid_d, invalid], BatchMode::FailFast, None).unwrap_err();
+    assert!(
+        err.contains("index 1"),
+        "Error should name the index: {}",
+        err
+    );
+    assert!(get_from_cache(&generate_query_id(valid_d)).is_none());
+}
+
+#[test]
+fn test_parse_batch_matches_single_parses() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"mutation { add_batch_users(objects: [{ name: "a" }]) { affected_rows } }"#;
+    let prefixes = MutationPrefixes {
+        insert_prefix: Some("add_".to_string()),
+        ..MutationPrefixes::default()
+    };
+
+    // Prefix overrides apply to every query of the batch, as to a single parse
+    let results = parse_batch(&[query], BatchMode::PerItem, Some(&prefixes)).unwrap();
+    let (info, hit) = results[0].as_ref().unwrap();
+    assert!(!hit);
+    assert_eq!(info.operation_kind, GraphQLOperationKind::InsertMutation);
+    assert_eq!(info.mutation_prefixes.as_ref(), Some(&prefixes));
+
+    // The batch cached the query under the ID a single parse looks up
+    let (_, request) = parse_graphql_with_overrides(query, Some(&prefixes), None).unwrap();
+    assert_eq!(
+        request.query_id,
+        generate_query_id_with_prefixes(query, Some(&prefixes))
+    );
+    assert!(get_from_cache(&request.query_id).is_some());
+
+    // A second batch is served from that entry
+    let results = parse_batch(&[query], BatchMode::PerItem, Some(&prefixes)).unwrap();
+    assert!(results[0].as_ref().unwrap().1);
+
+    // Without the override the query is not a known mutation
+    let results = parse_batch(&[query], BatchMode::PerItem, None).unwrap();
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn test_relay_connection_shape() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users(first: 10) {
+            edges {
+                cursor
+                node {
+                    id
+                    name
+                    posts {
+                        edges {
+                            node {
+                                title
+                            }
+                        }
+                    }
+                }
+            }
+            pageInfo {
+                hasNextPage
+                endCursor
+            }
+        }
+    }
+    "#;
+
+    let (info, _) = parse_graphql(query).unwrap();
+    let paths = info.field_paths.as_ref().unwrap();
+
+    // Wrappers do not form paths of their own
+    assert_eq!(paths.len(), 2, "Only users and users.posts are paths");
+    assert_path_exists(paths, &["users"]);
+    assert_path_exists(paths, &["users", "posts"]);
+
+    // Node fields are attributed to the enclosing table
+    let column_usage = info.column_usage.as_ref().unwrap();
+    let users_columns = &column_usage[&create_path(&["users"])];
+    for column in ["id", "name", "cursor"] {
+        assert!(
+            users_columns.contains(&intern_str(column)),
+            "users should select {}",
+            column
+        );
+    }
+    assert!(!users_columns.contains(&intern_str("hasNextPage")));
+
+    let posts_columns = &column_usage[&create_path(&["users", "posts"])];
+    assert!(posts_columns.contains(&intern_str("title")));
+
+    // A root field named like a wrapper is still a table
+    let paths = extract_field_paths("{ node { id } }");
+    assert_path_exists(&paths, &["node"]);
+}
+
+#[test]
+fn test_introspection_query_kind() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = "{ __schema { types { name } } }";
+    let (info, request) = parse_graphql(query).unwrap();
+
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Introspection);
+    assert!(info.field_paths.as_ref().unwrap().is_empty());
+    assert!(info.column_usage.as_ref().unwrap().is_empty());
+    assert!(request.paths.is_empty());
+    assert!(request.cols.is_empty());
+    assert_eq!(request.ops.len(), 1);
+    assert_eq!(request.ops[0].1, 5);
+
+    // Introspection fields may be combined, and fragments are tolerated
+    let query = r#"
+    query IntrospectionQuery {
+        __type(name: "users") { ...TypeFields }
+        __typename
+    }
+    fragment TypeFields on __Type { name kind }
+    "#;
+    let (info, _) = parse_graphql(query).unwrap();
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Introspection);
+
+    // Mixing introspection with table fields is a regular query
+    let (info, _) = parse_graphql("{ __typename users { id } }").unwrap();
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Query);
+}
+
+#[test]
+fn test_blank_queries_rejected() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let blank_queries = [
+        "",
+        "   \n\t  ",
+        ",,,",
+        "# only a comment\n   # and another one",
+    ];
+
+    for query in blank_queries {
+        assert!(is_blank_query(query), "{:?} should be blank", query);
+        assert_eq!(parse_graphql(query).unwrap_err(), EMPTY_QUERY_ERROR);
+        assert_eq!(
+            parse_graphql_with_config(query, &grasql::Config::default()).unwrap_err(),
+            EMPTY_QUERY_ERROR
+        );
+    }
+
+    // Batches report the same error per item
+    let results = parse_batch(&["", "{ users { id } }"], BatchMode::PerItem, None).unwrap();
+    assert_eq!(results[0].as_ref().unwrap_err(), EMPTY_QUERY_ERROR);
+    assert!(results[1].is_ok());
+
+    // A comment before real content is not blank
+    assert!(!is_blank_query("# users\n{ users { id } }"));
+}
+
+#[test]
+fn test_extract_all_paths_includes_columns() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = "{ users { id name posts { title } } }";
+    let ctx = ASTContext::new();
+    let document = Document::parse(&ctx, query).unwrap();
+
+    let all_paths = FieldPathExtractor::new()
+        .extract_all_paths(document)
+        .unwrap();
+
+    assert_eq!(all_paths[&create_path(&["users"])], PathKind::Entity);
+    assert_eq!(
+        all_paths[&create_path(&["users", "posts"])],
+        PathKind::Entity
+    );
+    assert_eq!(all_paths[&create_path(&["users", "id"])], PathKind::Column);
+    assert_eq!(
+        all_paths[&create_path(&["users", "name"])],
+        PathKind::Column
+    );
+    assert_eq!(
+        all_paths[&create_path(&["users", "posts", "title"])],
+        PathKind::Column
+    );
+    assert_eq!(all_paths.len(), 5);
+
+    // The default extraction leaves columns out of the path set
+    let paths = extract_field_paths(query);
+    assert!(!paths.contains(&create_path(&["users", "id"])));
+    assert_eq!(paths.len(), 2);
+}
+
+#[test]
+fn test_aliased_duplicate_columns() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let (info, request) = parse_graphql("{ users { a: name b: name id id } }").unwrap();
+    let users_path = create_path(&["users"]);
+
+    // The underlying column is captured once
+    let columns = &info.column_usage.as_ref().unwrap()[&users_path];
+    assert_eq!(columns.len(), 2);
+    assert!(columns.contains(&intern_str("name")));
+    assert_eq!(request.cols[0].1.len(), 2);
+
+    // Both aliases are recorded; plain duplicates need no entry
+    let aliases = &info.column_aliases.as_ref().unwrap()[&users_path];
+    assert_eq!(aliases[&intern_str("name")], vec!["a", "b"]);
+    assert!(!aliases.contains_key(&intern_str("id")));
+
+    // An alias alongside the plain column keeps both keys
+    let (info, _) = parse_graphql("{ users { name fullName: name } }").unwrap();
+    let aliases = &info.column_aliases.as_ref().unwrap()[&users_path];
+    assert_eq!(aliases[&intern_str("name")], vec!["name", "fullName"]);
+}
+
+#[test]
+fn test_scalar_json_field_sub_selection() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let config = grasql::Config {
+        scalar_fields: vec!["metadata".to_string()],
+        ..grasql::Config::default()
+    };
+    let query = "{ users { id metadata { foo address { city } } } }";
+    let (info, request) = parse_graphql_with_config(query, &config).unwrap();
+    let users_path = create_path(&["users"]);
+
+    // `metadata` is a column of users, not a relationship
+    let columns = &info.column_usage.as_ref().unwrap()[&users_path];
+    assert!(columns.contains(&intern_str("metadata")));
+    let paths = info.field_paths.as_ref().unwrap();
+    assert_eq!(paths.len(), 1);
+    assert!(!paths.contains(&create_path(&["users", "metadata"])));
+    assert!(request.relationships().is_empty());
+
+    // Its sub-fields are JSON paths
+    let json_paths = &info.json_paths.as_ref().unwrap()[&users_path];
+    assert_eq!(
+        json_paths[&intern_str("metadata")],
+        vec![
+            vec!["foo".to_string()],
+            vec!["address".to_string(), "city".to_string()]
+        ]
+    );
+
+    // Without the declaration the sub-selection reads as a relationship
+    let (info, _) =
+        parse_graphql_with_config("{ users { metadata { bar } } }", &grasql::Config::default())
+            .unwrap();
+    assert!(info
+        .field_paths
+        .as_ref()
+        .unwrap()
+        .contains(&create_path(&["users", "metadata"])));
+}
+
+#[test]
+fn test_resolution_request_relationships() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    // The complex benchmark sample
+    let query = r#"
+    query GetUserWithData($userId: ID!) {
+      user(id: $userId) {
+        id
+        profile { avatar bio }
+        posts(first: 10, where: { published: { _eq: true } }) {
+          id
+          tags { id name }
+          comments(first: 5) {
+            id
+            author { id name }
+          }
+        }
+        followers(first: 10) { id }
+        following(first: 10) { id }
+      }
+    }
+    "#;
+    let (_, request) = parse_graphql(query).unwrap();
+    let relationships: HashSet<(String, String)> = request.relationships().into_iter().collect();
+
+    let pair = |parent: &str, child: &str| (parent.to_string(), child.to_string());
+    let expected: HashSet<(String, String)> = [
+        pair("user", "profile"),
+        pair("user", "posts"),
+        pair("posts", "tags"),
+        pair("posts", "comments"),
+        pair("comments", "author"),
+        pair("user", "followers"),
+        pair("user", "following"),
+        // Filter objects are encoded as relationship paths until resolved
+        pair("posts", "published"),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(relationships, expected);
+
+    // The deeply nested benchmark sample yields one pair per level
+    let query = r#"
+    {
+      organizations {
+        departments {
+          teams {
+            projects {
+              tasks {
+                subtasks {
+                  assignee {
+                    skills { id name level }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+    "#;
+    let (_, request) = parse_graphql(query).unwrap();
+    let relationships = request.relationships();
+    assert_eq!(relationships.len(), 7);
+    for expected in [
+        pair("organizations", "departments"),
+        pair("departments", "teams"),
+        pair("teams", "projects"),
+        pair("projects", "tasks"),
+        pair("tasks", "subtasks"),
+        pair("subtasks", "assignee"),
+        pair("assignee", "skills"),
+    ] {
+        assert!(
+            relationships.contains(&expected),
+            "Missing relationship {:?} in {:?}",
+            expected,
+            relationships
+        );
+    }
+
+    // Root tables are not relationships
+    let (_, request) = parse_graphql("{ users { id } }").unwrap();
+    assert!(request.relationships().is_empty());
+}
+
+#[test]
+fn test_max_new_symbols_per_query() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let config = grasql::Config {
+        max_new_symbols_per_query: Some(50),
+        ..grasql::Config::default()
+    };
+
+    // A synthetic query full of never-seen field names trips the guard
+    let fields: Vec<String> = (0..200)
+        .map(|i| format!("runaway_symbol_field_{}", i))
+        .collect();
+    let runaway_query = format!("{{ users {{ {} }} }}", fields.join(" "));
+    let err = parse_graphql_with_config(&runaway_query, &config).unwrap_err();
+    assert!(
+        err.contains("new identifiers") && err.contains("limit of 50"),
+        "Error should report the symbol limit, got: {}",
+        err
+    );
+
+    // Once interned, the same identifiers no longer count as new
+    assert!(parse_graphql_with_config(&runaway_query, &config).is_ok());
+
+    // Without a limit any number of identifiers is accepted
+    let fields: Vec<String> = (0..200)
+        .map(|i| format!("unlimited_symbol_field_{}", i))
+        .collect();
+    let query = format!("{{ users {{ {} }} }}", fields.join(" "));
+    assert!(parse_graphql_with_config(&query, &grasql::Config::default()).is_ok());
+}
+
+#[test]
+fn test_named_query_with_trailing_fragment() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let named_query = r#"
+    query GetUsers($minAge: Int, $limit: Int) {
+        users(where: { age: { _gt: $minAge } }, limit: $limit) {
+            id
+            posts { title }
+        }
+    }
+    "#;
+
+    // A named query with variable definitions extracts like the shorthand form
+    let (info, request) = parse_graphql(named_query).unwrap();
+    assert_eq!(info.operation_name, Some("GetUsers".to_string()));
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Query);
+    let paths = info.field_paths.as_ref().unwrap();
+    assert!(paths.contains(&create_path(&["users"])));
+    assert!(paths.contains(&create_path(&["users", "posts"])));
+    assert_eq!(request.ops.len(), 1);
+    assert_eq!(request.strings[request.ops[0].0 as usize], "users");
+
+    // A trailing fragment definition is rejected cleanly, even when unused
+    let with_fragment = format!("{}\nfragment UserFields on User {{ id name }}", named_query);
+    let err = parse_graphql(&with_fragment).unwrap_err();
+    assert_eq!(err, "GraphQL fragments are not supported");
+
+    // The extractor itself only walks the operation
+    let paths = extract_field_paths(&with_fragment);
+    assert_path_exists(&paths, &["users"]);
+    assert_path_exists(&paths, &["users", "posts"]);
+}
+
+#[test]
+fn test_typename_only_query_needs_no_sql() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    // Valid GraphQL without any table is answered without SQL
+    let (info, request) = parse_graphql("{ __typename }").unwrap();
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Introspection);
+    assert!(!info.operation_kind.requires_sql());
+    assert!(request.paths.is_empty());
+
+    // Anything touching a table still needs SQL
+    for query in [
+        "{ __typename users { id } }",
+        "mutation { insert_users(objects: { name: \"A\" }) { affected_rows } }",
+    ] {
+        let (info, _) = parse_graphql(query).unwrap();
+        assert!(info.operation_kind.requires_sql(), "{}", query);
+    }
+}
+
+#[test]
+fn test_expand_variable_filters() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    query FilteredUsers($filter: users_bool_exp) {
+        users(where: $filter) {
+            id
+        }
+    }
+    "#;
+    let variables = serde_json::json!({
+        "filter": {
+            "_or": [
+                { "name": { "_eq": "John" } },
+                { "posts": { "comments": { "approved": { "_eq": true } } } }
+            ]
+        }
+    });
+    let variables = variables.as_object().unwrap();
+
+    let config = grasql::Config {
+        expand_variable_filters: true,
+        ..grasql::Config::default()
+    };
+    let (info, request) = parse_graphql_with_variables(query, &config, variables).unwrap();
+    let paths = info.field_paths.as_ref().unwrap();
+    assert!(paths.contains(&create_path(&["users", "posts"])));
+    assert!(paths.contains(&create_path(&["users", "posts", "comments"])));
+    assert!(request
+        .relationships()
+        .contains(&("posts".to_string(), "comments".to_string())));
+
+    // Different variable values extract different paths, so they get their own ID
+    let other = serde_json::json!({ "filter": { "profile": { "bio": { "_is_null": false } } } });
+    let (other_info, other_request) =
+        parse_graphql_with_variables(query, &config, other.as_object().unwrap()).unwrap();
+    assert!(other_info
+        .field_paths
+        .as_ref()
+        .unwrap()
+        .contains(&create_path(&["users", "profile"])));
+    assert_ne!(request.query_id, other_request.query_id);
+
+    // Without expansion the variable filter stays opaque
+    let (info, request) =
+        parse_graphql_with_variables(query, &grasql::Config::default(), variables).unwrap();
+    assert_eq!(info.field_paths.as_ref().unwrap().len(), 1);
+    assert_eq!(request.query_id, grasql::generate_query_id(query));
+}
+
+#[test]
+fn test_expand_variable_filter_default() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    query FilteredUsers(
+        $filter: users_bool_exp = { active: { _eq: true }, posts: { title: { _eq: "x" } } }
+    ) {
+        users(where: $filter) {
+            id
+        }
+    }
+    "#;
+    let config = grasql::Config {
+        expand_variable_filters: true,
+        ..grasql::Config::default()
+    };
+
+    // An omitted variable is expanded from its declared default
+    let (info, _) = parse_graphql_with_variables(query, &config, &serde_json::Map::new()).unwrap();
+    let paths = info.field_paths.as_ref().unwrap();
+    assert!(paths.contains(&create_path(&["users", "active"])));
+    assert!(paths.contains(&create_path(&["users", "posts"])));
+    assert!(paths.contains(&create_path(&["users", "posts", "title"])));
+
+    // A supplied value takes precedence over the default
+    let supplied = serde_json::json!({ "filter": { "profile": { "bio": { "_is_null": false } } } });
+    let (info, _) =
+        parse_graphql_with_variables(query, &config, supplied.as_object().unwrap()).unwrap();
+    let paths = info.field_paths.as_ref().unwrap();
+    assert!(paths.contains(&create_path(&["users", "profile"])));
+    assert!(!paths.contains(&create_path(&["users", "posts"])));
+}
+
+#[test]
+fn test_parse_with_overrides_variables() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    query FilteredUsers($filter: users_bool_exp) {
+        users(where: $filter) {
+            id
+        }
+    }
+    "#;
+    let variables = serde_json::json!({ "filter": { "posts": { "title": { "_eq": "x" } } } });
+    let variables = variables.as_object().unwrap();
+
+    // Without expand_variable_filters the values are ignored, ID included
+    let (info, request) = parse_graphql_with_overrides(query, None, Some(variables)).unwrap();
+    assert_eq!(info.field_paths.as_ref().unwrap().len(), 1);
+    assert_eq!(request.query_id, grasql::generate_query_id(query));
+
+    // Expanded parses are keyed like parse_graphql_with_variables, plus any prefixes
+    assert_eq!(
+        grasql::generate_query_id_with_overrides(query, None, Some(variables)),
+        grasql::generate_query_id_with_variables(query, variables)
+    );
+    let prefixes = grasql::MutationPrefixes {
+        insert_prefix: Some("add_".to_string()),
+        ..grasql::MutationPrefixes::default()
+    };
+    assert_ne!(
+        grasql::generate_query_id_with_overrides(query, Some(&prefixes), Some(variables)),
+        grasql::generate_query_id_with_variables(query, variables)
+    );
+}
+
+#[test]
+fn test_unknown_operator_warning() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users(where: { name: { _eqq: "John" }, _or: [{ id: { _eqq: 1 } }] }) {
+            id
+        }
+    }
+    "#;
+
+    // The unknown operator is skipped, reported once, and the query still parses
+    let (info, request) = parse_graphql(query).unwrap();
+    let expected = vec!["Ignored unknown filter operator '_eqq'".to_string()];
+    assert_eq!(info.warnings, expected);
+    assert_eq!(request.warnings, expected);
+
+    let (info, request) = parse_graphql("{ users(where: { id: { _eq: 1 } }) { id } }").unwrap();
+    assert!(info.warnings.is_empty());
+    assert!(request.warnings.is_empty());
+}
+
+#[test]
+fn test_add_field_path_keeps_existing_ids() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let (parsed, _) = parse_graphql("{ users { id posts { title } } }").unwrap();
+    let mut info: grasql::CachedQueryInfo = parsed.into();
+    let before = info.path_index.clone().unwrap();
+    assert_eq!(before.len(), 2);
+
+    // A new path gets the next id and old paths are not renumbered
+    let profile_path = create_path(&["users", "profile"]);
+    assert_eq!(info.add_field_path(profile_path.clone()), 2);
+    let index = info.path_index.as_ref().unwrap();
+    assert_eq!(index[&profile_path], 2);
+    for (path, id) in &before {
+        assert_eq!(index[path], *id);
+    }
+    assert!(info.field_paths.as_ref().unwrap().contains(&profile_path));
+
+    // Adding a known path returns its existing id
+    let posts_path = create_path(&["users", "posts"]);
+    assert_eq!(info.add_field_path(posts_path.clone()), before[&posts_path]);
+    assert_eq!(info.path_index.as_ref().unwrap().len(), 3);
+
+    // Incremental inserts agree with a full rebuild on the set of ids
+    let mut ids: Vec<usize> = info
+        .path_index
+        .as_ref()
+        .unwrap()
+        .values()
+        .copied()
+        .collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_validate_query() {
+    initialize_grasql();
+
+    let config = grasql::Config {
+        max_query_depth: 3,
+        ..grasql::Config::default()
+    };
+
+    let query = "{ users { id posts { title } } }";
+    assert_eq!(validate_query(query, &config), Ok(()));
+
+    // Validation leaves the cache alone
+    assert!(get_from_cache(&generate_query_id(query)).is_none());
+
+    let deep = "{ users { posts { comments { author { name } } } } }";
+    let err = validate_query(deep, &config).unwrap_err();
+    assert_eq!(err, "Query depth 5 exceeds the maximum of 3");
+
+    // A full parse applies the same guard
+    assert_eq!(parse_graphql_with_config(deep, &config).unwrap_err(), err);
+
+    assert_eq!(
+        validate_query("  ", &config).unwrap_err(),
+        EMPTY_QUERY_ERROR
+    );
+    assert!(validate_query("{ users { id ", &config)
+        .unwrap_err()
+        .starts_with("Failed to parse GraphQL query"));
+}
+
+#[test]
+fn test_resolution_request_columns_by_table() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users(where: { age: { _gt: 21 } }) { name id age }
+        posts { title published content }
+    }
+    "#;
+    let (_, request) = parse_graphql(query).unwrap();
+
+    let columns = request.columns_by_table();
+    assert_eq!(columns.len(), 2);
+    assert_eq!(columns["users"], vec!["age", "id", "name"]);
+    assert_eq!(columns["posts"], vec!["content", "published", "title"]);
+}
+
+#[test]
+fn test_collect_argument_names() {
+    // Initialize GraSQL config
+    initialize_grasql();
+
+    let config = grasql::Config {
+        collect_argument_names: true,
+        ..grasql::Config::default()
+    };
+    let query = r#"
+    query Feed($limit: Int, $offset: Int) {
+        posts(
+            where: { published: { _eq: true } },
+            order_by: { created_at: desc },
+            limit: $limit,
+            offset: $offset
+        ) {
+            title
+            thumbnail(size: 64)
+            comments(distinct_on: author_id) { body }
+        }
+    }
+    "#;
+    let (info, request) = parse_graphql_with_config(query, &config).unwrap();
+
+    let names: Vec<&str> = info
+        .argument_names
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "distinct_on",
+            "limit",
+            "offset",
+            "order_by",
+            "size",
+            "where"
+        ]
+    );
+
+    // The resolution request carries them to Elixir
+    assert_eq!(request.argument_names, names);
+
+    // Not collected unless enabled
+    let (info, request) = parse_graphql_with_config(query, &grasql::Config::default()).unwrap();
+    assert!(info.argument_names.is_none());
+    assert!(request.argument_names.is_empty());
+}
+
+#[test]
+fn test_request_indices_are_symbol_keys() {
+    initialize_grasql();
+
+    let (_, request) = parse_graphql(
+        "{ users(where: { posts: { title: { _eq: \"x\" } } }) { id profile { bio } } }",
+    )
+    .unwrap();
+
+    // Each index must match the position the old string-table lookup assigned
+    let position = |name: &str| request.strings.iter().position(|s| s == name).unwrap() as u32;
+
+    let mut decoded = HashSet::new();
+    for &offset in request.path_dir.iter() {
+        let offset = offset as usize;
+        let len = request.paths[offset] as usize;
+        let path: Vec<&str> = request.paths[offset + 1..offset + 1 + len]
+            .iter()
+            .map(|&idx| request.strings[idx as usize].as_str())
+            .collect();
+        for name in &path {
+            assert_eq!(grasql::symbol_index(intern_str(name)), position(name));
+        }
+        decoded.insert(path.join("."));
+    }
+    assert!(decoded.contains("users"));
+    assert!(decoded.contains("users.posts"));
+    assert!(decoded.contains("users.profile"));
+
+    for &(table_idx, ref columns) in request.cols.iter() {
+        assert_eq!(table_idx, position(&request.strings[table_idx as usize]));
+        for &column in columns {
+            assert_eq!(column, position(&request.strings[column as usize]));
+        }
+    }
+    assert_eq!(request.ops, vec![(position("users"), 0)]);
+}
+
+#[test]
+fn test_mixed_pagination_styles_rejected() {
+    initialize_grasql();
+
+    let err = parse_graphql("{ users(limit: 10, first: 5) { id } }").unwrap_err();
+    assert_eq!(
+        err,
+        "Field 'users' mixes offset pagination ('limit') with cursor pagination ('first')"
+    );
+
+    // Nested relationships are checked too
+    let err = parse_graphql(r#"{ users { posts(offset: 20, after: "abc") { id } } }"#).unwrap_err();
+    assert!(err.contains("Field 'posts' mixes"), "{}", err);
+
+    // Either style on its own is fine, as are different styles on different fields
+    assert!(parse_graphql("{ users(limit: 10, offset: 20) { id } }").is_ok());
+    assert!(parse_graphql(r#"{ users(first: 5, after: "abc") { id } }"#).is_ok());
+    assert!(parse_graphql("{ users(limit: 10) { posts(first: 5) { id } } }").is_ok());
+}
+
+#[test]
+fn test_pagination_variables_must_be_integers() {
+    initialize_grasql();
+
+    let err = parse_graphql("query ($limit: String) { users(limit: $limit) { id } }").unwrap_err();
+    assert_eq!(
+        err,
+        "Variable '$limit' used in 'limit' must be an Int, not String"
+    );
+
+    let err =
+        parse_graphql("query ($n: [Int!]) { users { posts(first: $n) { id } } }").unwrap_err();
+    assert_eq!(
+        err,
+        "Variable '$n' used in 'first' must be an Int, not [Int!]"
+    );
+
+    let err = parse_graphql("{ users(offset: $offset) { id } }").unwrap_err();
+    assert_eq!(err, "Variable '$offset' used in 'offset' is not declared");
+
+    assert!(parse_graphql(
+        "query ($limit: Int!, $offset: Int) { users(limit: $limit, offset: $offset) { id } }"
+    )
+    .is_ok());
+
+    // Supplied values are checked as well
+    let config = grasql::Config {
+        expand_variable_filters: true,
+        ..grasql::Config::default()
+    };
+    let query = "query ($limit: Int) { users(limit: $limit) { id } }";
+    let variables = serde_json::json!({ "limit": "10" });
+    let err =
+        parse_graphql_with_variables(query, &config, variables.as_object().unwrap()).unwrap_err();
+    assert_eq!(
+        err,
+        "Variable '$limit' used in 'limit' must be an integer, got \"10\""
+    );
+    for ok in [
+        serde_json::json!({ "limit": 10 }),
+        serde_json::json!({ "limit": null }),
+    ] {
+        assert!(parse_graphql_with_variables(query, &config, ok.as_object().unwrap()).is_ok());
+    }
+}
+
+#[test]
+fn test_max_operations() {
+    initialize_grasql();
+
+    let config = grasql::Config {
+        max_operations: Some(2),
+        ..grasql::Config::default()
+    };
+
+    let two = "query A { users { id } } query B { posts { id } }";
+    assert!(parse_graphql_with_config(two, &config).is_ok());
+
+    let three = format!("{} query C {{ comments {{ id }} }}", two);
+    let err = parse_graphql_with_config(&three, &config).unwrap_err();
+    assert_eq!(
+        err,
+        "Document defines 3 operations, exceeding the limit of 2"
+    );
+    assert_eq!(validate_query(&three, &config).unwrap_err(), err);
+
+    // Unlimited by default
+    assert!(parse_graphql_with_config(&three, &grasql::Config::default()).is_ok());
+}
+
+#[test]
+fn test_aggregate_where_extraction() {
+    initialize_grasql();
+
+    let query = r#"
+    {
+        posts_aggregate(where: { author: { name: { _eq: "John" } } }) {
+            aggregate { count }
+        }
+        users {
+            id
+            posts_aggregate(where: { _and: [{ published: { _eq: true } }, { category: { slug: "news" } }] }) {
+                aggregate { count }
+            }
+        }
+    }
+    "#;
+    let (info, _) = parse_graphql(query).unwrap();
+    let paths = info.field_paths.as_ref().unwrap();
+    let usage = info.column_usage.as_ref().unwrap();
+    let columns = |segments: &[&str]| -> HashSet<String> {
+        usage[&create_path(segments)]
+            .iter()
+            .map(|&column| grasql::resolve_str(column).unwrap())
+            .collect()
+    };
+
+    // Root aggregate: the filtered relationship and the column it compares
+    assert!(paths.contains(&create_path(&["posts_aggregate", "author"])));
+    assert_eq!(
+        columns(&["posts_aggregate", "author"]),
+        HashSet::from(["name".to_string()])
+    );
+
+    // Nested aggregate relationship, through a logical operator
+    assert!(paths.contains(&create_path(&["users", "posts_aggregate", "category"])));
+    assert_eq!(
+        columns(&["users", "posts_aggregate"]),
+        HashSet::from(["published".to_string()])
+    );
+    assert_eq!(
+        columns(&["users", "posts_aggregate", "category"]),
+        HashSet::from(["slug".to_string()])
+    );
+}
+
+#[test]
+fn test_five_level_nested_boolean_filter() {
+    initialize_grasql();
+
+    // Alternating `_and`/`_or`, five levels deep, comparing the same
+    // relationships and columns at several levels
+    let query = r#"
+    {
+        posts_aggregate(where: {
+            _and: [
+                { author: { name: { _eq: "a" } } },
+                { _or: [
+                    { author: { name: { _eq: "b" } } },
+                    { _and: [
+                        { category: { slug: { _eq: "news" } } },
+                        { _or: [
+                            { author: { profile: { bio: { _ilike: "%rust%" } } } },
+                            { _and: [
+                                { author: { name: { _eq: "c" } } },
+                                { category: { slug: { _eq: "tech" } } }
+                            ] }
+                        ] }
+                    ] }
+                ] }
+            ]
+        }) {
+            aggregate { count }
+        }
+    }
+    "#;
+
+    let expected: HashSet<FieldPath> = [
+        create_path(&["posts_aggregate"]),
+        create_path(&["posts_aggregate", "aggregate"]),
+        create_path(&["posts_aggregate", "author"]),
+        create_path(&["posts_aggregate", "author", "profile"]),
+        create_path(&["posts_aggregate", "category"]),
+        // Without schema information, compared columns look like relationships too
+        create_path(&["posts_aggregate", "author", "name"]),
+        create_path(&["posts_aggregate", "author", "profile", "bio"]),
+        create_path(&["posts_aggregate", "category", "slug"]),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(extract_field_paths(query), expected);
+
+    let (info, _) = parse_graphql(query).unwrap();
+    let usage = info.column_usage.as_ref().unwrap();
+    let columns = |segments: &[&str]| -> Vec<String> {
+        let mut columns: Vec<String> = usage[&create_path(segments)]
+            .iter()
+            .map(|&column| grasql::resolve_str(column).unwrap())
+            .collect();
+        columns.sort();
+        columns
+    };
+    assert_eq!(columns(&["posts_aggregate", "author"]), ["name"]);
+    assert_eq!(columns(&["posts_aggregate", "author", "profile"]), ["bio"]);
+    assert_eq!(columns(&["posts_aggregate", "category"]), ["slug"]);
+}
+
+#[test]
+fn test_require_relationship_selection() {
+    initialize_grasql();
+
+    let query = "{ users { id posts } }";
+    let config = grasql::Config {
+        relationship_fields: vec!["posts".to_string()],
+        require_relationship_selection: true,
+        ..grasql::Config::default()
+    };
+    let err = parse_graphql_with_config(query, &config).unwrap_err();
+    assert_eq!(err, "Relationship 'posts' must select at least one field");
+
+    // With a sub-selection the relationship is fine
+    assert!(parse_graphql_with_config("{ users { id posts { id } } }", &config).is_ok());
+
+    // Without the flag, the known relationship is treated as a column
+    let lenient = grasql::Config {
+        require_relationship_selection: false,
+        ..config
+    };
+    let (info, _) = parse_graphql_with_config(query, &lenient).unwrap();
+    let columns = &info.column_usage.as_ref().unwrap()[&create_path(&["users"])];
+    assert!(columns.contains(&intern_str("posts")));
+}
+
+#[test]
+fn test_repeated_relationship_selections_merge() {
+    initialize_grasql();
+
+    let (info, request) = parse_graphql("{ users { posts { title } posts { body } } }").unwrap();
+    let posts_path = create_path(&["users", "posts"]);
+
+    let paths = info.field_paths.as_ref().unwrap();
+    assert_eq!(paths.len(), 2);
+    assert!(paths.contains(&posts_path));
+    assert_eq!(request.relationships().len(), 1);
+
+    let columns = &info.column_usage.as_ref().unwrap()[&posts_path];
+    assert_eq!(columns.len(), 2);
+    assert!(columns.contains(&intern_str("title")));
+    assert!(columns.contains(&intern_str("body")));
+}
+
+#[test]
+fn test_function_field_arguments() {
+    initialize_grasql();
+
+    let query = r#"{ search_posts(args: { search: "rust", max_results: 10 }, where: { published: { _eq: true } }) { id title } }"#;
+    let config = grasql::Config {
+        function_fields: vec!["search_posts".to_string()],
+        ..grasql::Config::default()
+    };
+    let (info, _) = parse_graphql_with_config(query, &config).unwrap();
+    let path = create_path(&["search_posts"]);
+
+    let function_args = &info.function_args.as_ref().unwrap()[&path];
+    assert_eq!(
+        function_args,
+        &vec![
+            (intern_str("search"), "\"rust\"".to_string()),
+            (intern_str("max_results"), "10".to_string()),
+        ]
+    );
+
+    // Function arguments are not columns of the function's result
+    let columns = &info.column_usage.as_ref().unwrap()[&path];
+    assert_eq!(columns.len(), 2);
+    assert!(!columns.contains(&intern_str("search")));
+    assert!(!columns.contains(&intern_str("max_results")));
+
+    // Undeclared fields keep `args` as an ordinary argument
+    let (info, _) = parse_graphql_with_config(query, &grasql::Config::default()).unwrap();
+    assert!(info.function_args.as_ref().unwrap().is_empty());
+}
+
+#[test]
+fn test_pagination_edge_values() {
+    initialize_grasql();
+
+    // `limit: 0` asks for no rows and is not the same as no limit
+    let (info, _) = parse_graphql("{ users(limit: 0) { id } }").unwrap();
+    assert_eq!(
+        info.args.as_ref().unwrap()[&create_path(&["users"])],
+        FieldArgs {
+            limit: Some(ArgValue::Literal(0)),
+            offset: None,
+        }
+    );
+
+    let (info, _) = parse_graphql("{ users(limit: 10, offset: 5) { id } }").unwrap();
+    assert_eq!(
+        info.args.as_ref().unwrap()[&create_path(&["users"])],
+        FieldArgs {
+            limit: Some(ArgValue::Literal(10)),
+            offset: Some(ArgValue::Literal(5)),
+        }
+    );
+
+    let err = parse_graphql("{ users(offset: -5) { id } }").unwrap_err();
+    assert_eq!(err, "'offset' must not be negative, got -5");
+    let err = parse_graphql("{ users { posts(limit: -1) { id } } }").unwrap_err();
+    assert_eq!(err, "'limit' must not be negative, got -1");
+
+    // Negative values supplied through variables are rejected too
+    let config = grasql::Config {
+        expand_variable_filters: true,
+        ..grasql::Config::default()
+    };
+    let query = "query ($offset: Int) { users(offset: $offset) { id } }";
+    let variables = serde_json::json!({ "offset": -5 });
+    let err =
+        parse_graphql_with_variables(query, &config, variables.as_object().unwrap()).unwrap_err();
+    assert_eq!(
+        err,
+        "Variable '$offset' used in 'offset' must not be negative, got -5"
+    );
+    let variables = serde_json::json!({ "offset": 0 });
+    assert!(parse_graphql_with_variables(query, &config, variables.as_object().unwrap()).is_ok());
+}
+
+#[test]
+fn test_field_directive_annotations() {
+    initialize_grasql();
+
+    let query =
+        r#"{ users @hint(index: "users_active") { id @bind(type: "uuid") name posts { title } } }"#;
+
+    // Directives are rejected unless configured as annotations
+    let err = parse_graphql_with_config(query, &grasql::Config::default()).unwrap_err();
+    assert_eq!(err, "GraphQL directives are not supported");
+
+    let config = grasql::Config {
+        field_directives: vec!["bind".to_string(), "hint".to_string()],
+        ..grasql::Config::default()
+    };
+    let (_, request) = parse_graphql_with_config(query, &config).unwrap();
+
+    let string = |idx: u32| request.strings[idx as usize].as_str();
+    let mut directives: Vec<_> = request
+        .directives
+        .iter()
+        .map(|(path_id, column_idx, name_idx, arguments)| {
+            let path = request.decode_path(*path_id as usize).unwrap().join(".");
+            let column = (*column_idx >= 0).then(|| string(*column_idx as u32));
+            let arguments: Vec<_> = arguments
+                .iter()
+                .map(|(arg_idx, value)| (string(*arg_idx), value.as_str()))
+                .collect();
+            (path, column, string(*name_idx), arguments)
+        })
+        .collect();
+    directives.sort();
+
+    assert_eq!(
+        directives,
+        vec![
+            (
+                "users".to_string(),
+                None,
+                "hint",
+                vec![("index", "\"users_active\"")]
+            ),
+            (
+                "users".to_string(),
+                Some("id"),
+                "bind",
+                vec![("type", "\"uuid\"")]
+            ),
+        ]
+    );
+
+    // Directives left out of the list are still rejected
+    let config = grasql::Config {
+        field_directives: vec!["bind".to_string()],
+        ..grasql::Config::default()
+    };
+    let err = parse_graphql_with_config(query, &config).unwrap_err();
+    assert_eq!(err, "GraphQL directives are not supported");
+}
+
+#[test]
+fn test_aggregated_columns_extraction() {
+    initialize_grasql();
+
+    let query = r#"
+    {
+        users_aggregate {
+            aggregate {
+                count
+                sum { age }
+                avg { score }
+                max { age }
+            }
+        }
+        users {
+            posts_aggregate { aggregate { min { created_at } } }
+        }
+    }
+    "#;
+    let (info, request) = parse_graphql(query).unwrap();
+
+    // Columns under aggregate functions are columns of the aggregated table
+    let cols: Vec<(&str, HashSet<&str>)> = request
+        .cols
+        .iter()
+        .map(|(table_idx, columns)| {
+            (
+                request.strings[*table_idx as usize].as_str(),
+                columns
+                    .iter()
+                    .map(|&idx| request.strings[idx as usize].as_str())
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        cols,
+        vec![("users_aggregate", ["age", "score"].into_iter().collect())]
+    );
+
+    // Nested aggregates record them on their own path
+    let column_usage = info.column_usage.as_ref().unwrap();
+    assert!(column_usage[&create_path(&["users", "posts_aggregate"])]
+        .contains(&intern_str("created_at")));
+}
+
+#[test]
+fn test_subscription_mode() {
+    initialize_grasql();
+
+    let subscription = "subscription { users(where: { active: { _eq: true } }) { id name } }";
+
+    // Rejected by default
+    let err = parse_graphql_with_config(subscription, &grasql::Config::default()).unwrap_err();
+    assert!(
+        err.starts_with("Subscriptions are not supported"),
+        "{}",
+        err
+    );
+    assert_eq!(
+        validate_query(subscription, &grasql::Config::default()).unwrap_err(),
+        err
+    );
+
+    // Analyzed like the equivalent query when opted in
+    let config = grasql::Config {
+        subscription_mode: grasql::SubscriptionMode::AsQuery,
+        ..grasql::Config::default()
+    };
+    let (info, _) = parse_graphql_with_config(subscription, &config).unwrap();
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Query);
+    let (query_info, _) = parse_graphql_with_config(
+        "query { users(where: { active: { _eq: true } }) { id name } }",
+        &config,
+    )
+    .unwrap();
+    assert_eq!(info.field_paths, query_info.field_paths);
+    assert_eq!(info.column_usage, query_info.column_usage);
+    let field_paths = info.field_paths.as_ref().unwrap();
+    assert!(field_paths.contains(&create_path(&["users"])));
+}
+
+#[test]
+fn test_byte_order_mark_prefixed_query() {
+    initialize_grasql();
+
+    let plain = "{ users { id name } }";
+    let with_bom = "\u{FEFF}\n\t  { users { id name } }";
+
+    let (info, request) = parse_graphql(with_bom).unwrap();
+    assert_eq!(info.operation_kind, GraphQLOperationKind::Query);
+    assert!(info
+        .field_paths
+        .as_ref()
+        .unwrap()
+        .contains(&create_path(&["users"])));
+
+    // Normalization treats the mark as whitespace, so both share a cache entry
+    assert_eq!(grasql::normalize_query(with_bom), plain);
+    assert_eq!(generate_query_id(with_bom), generate_query_id(plain));
+    let (_, plain_request) = parse_graphql(plain).unwrap();
+    assert_eq!(request.query_id, plain_request.query_id);
+
+    assert!(validate_query(with_bom, &grasql::Config::default()).is_ok());
+    assert!(is_blank_query("\u{FEFF}  "));
+}
+
+#[test]
+fn test_empty_mutation_rejected() {
+    initialize_grasql();
+
+    for query in [
+        "mutation { }",
+        "mutation {}",
+        "mutation Empty($id: Int) {\n}",
+    ] {
+        let err = parse_graphql(query).unwrap_err();
+        assert_eq!(err, EMPTY_MUTATION_ERROR, "{}", query);
+        assert_eq!(
+            validate_query(query, &grasql::Config::default()).unwrap_err(),
+            EMPTY_MUTATION_ERROR
+        );
+    }
+
+    // Other syntax errors keep the parser's message
+    let err = parse_graphql("query { }").unwrap_err();
+    assert!(err.starts_with("Failed to parse GraphQL query"), "{}", err);
+    let err = parse_graphql("mutation { insert_users(objects: {}) { }").unwrap_err();
+    assert!(err.starts_with("Failed to parse GraphQL query"), "{}", err);
+}
+
+#[test]
+fn test_simple_selection_fast_path() {
+    initialize_grasql();
+
+    let config = grasql::Config::default();
+    let extract_general = |document: &Document| {
+        let mut extractor = FieldPathExtractor::new();
+        let paths = extractor.extract_with_config(document, &config).unwrap();
+
+        // Nothing the fast path leaves out is collected for these shapes
+        assert!(extractor.take_column_aliases().is_empty());
+        assert!(extractor.take_column_arguments().is_empty());
+        assert!(extractor.take_function_args().is_empty());
+        assert!(extractor.take_json_paths().is_empty());
+        assert!(extractor.take_args().is_empty());
+        assert!(extractor.take_directives().is_empty());
+        assert!(extractor.take_insert_tree().is_empty());
+        paths
+    };
+
+    for query in [
+        "{ users { id name } }",
+        "query { users { id } }",
+        "query ListUsers { users { id name email name } }",
+        "{ posts { __typename title } }",
+    ] {
+        let ctx = ASTContext::new();
+        let document = Document::parse(&ctx, query).unwrap();
+        let fast = extract_simple_selection(document, &config).expect(query);
+        assert_eq!(fast, extract_general(document), "{}", query);
+    }
+
+    // Anything beyond plain columns of one table takes the general path
+    for query in [
+        "{ users(limit: 10) { id } }",
+        "{ users { id posts { title } } }",
+        "{ users { key: id } }",
+        "{ u: users { id } }",
+        "{ users { id @skip(if: true) } }",
+        "{ users { id } posts { id } }",
+        "{ users { ...UserFields } } fragment UserFields on users { id }",
+        "{ users { ... on users { id } } }",
+        "{ users_agg { count } }",
+        "{ users_connection { edges { node { id } } } }",
+        "mutation { delete_users(where: {}) { affected_rows } }",
+    ] {
+        let ctx = ASTContext::new();
+        let document = Document::parse(&ctx, query).unwrap();
+        assert_eq!(
+            extract_simple_selection(document, &config),
+            None,
+            "{}",
+            query
+        );
+    }
+
+    // The fast path feeds the usual resolution request
+    let (info, request) = parse_graphql("{ users { id name } }").unwrap();
+    assert_eq!(
+        info.field_paths.unwrap(),
+        HashSet::from([create_path(&["users"])])
+    );
+    assert_eq!(request.cols.len(), 1);
+    assert_eq!(request.validate(), Ok(()));
+}
+
+#[test]
+fn test_custom_list_of_objects_argument_ignored() {
+    initialize_grasql();
+
+    // Unlike `_and`/`_or` in a filter, a custom argument's objects are not
+    // filters, so their keys are neither relationships nor columns
+    let query = r#"
+    {
+        users(bulk: [{ posts: { title: { _eq: "a" } } }, { profile: { bio: "b" }, age: 3 }]) {
+            id
+            comments(extra: [{ author: { name: "c" } }]) { body }
+        }
+    }
+    "#;
+
+    let (info, request) = parse_graphql(query).unwrap();
+    assert_eq!(
+        info.field_paths.unwrap(),
+        HashSet::from([create_path(&["users"]), create_path(&["users", "comments"])])
+    );
+
+    let column_usage = info.column_usage.unwrap();
+    assert_eq!(column_usage.len(), 2);
+    assert_eq!(
+        column_usage[&create_path(&["users"])],
+        HashSet::from([intern_str("id")])
+    );
+    assert_eq!(
+        column_usage[&create_path(&["users", "comments"])],
+        HashSet::from([intern_str("body")])
+    );
+    assert_eq!(request.path_dir.len(), 2);
+}
@@ -0,0 +1,39 @@
+//! Recent parse error buffer
+//!
+//! Kept in its own test binary so errors from other parser tests don't
+//! interleave with the ones recorded here.
+#![cfg(feature = "recent-errors")]
+
+use grasql::parser::{parse_graphql, recent_errors, RECENT_ERRORS_CAPACITY};
+
+#[test]
+fn test_recent_errors_recorded_in_order() {
+    let _ = grasql::types::initialize_for_test();
+
+    let first = parse_graphql("{ users { id ").unwrap_err();
+    let second = parse_graphql("query { ").unwrap_err();
+    assert!(parse_graphql("{ users { id } }").is_ok());
+
+    let errors = recent_errors();
+    assert_eq!(
+        errors,
+        vec![
+            ("{ users { id".to_string(), first),
+            ("query {".to_string(), second),
+        ]
+    );
+
+    // Only the newest errors are kept, and long queries are truncated
+    let long_query = format!("{{ {} ", "users { id } ".repeat(50));
+    for _ in 0..RECENT_ERRORS_CAPACITY {
+        assert!(parse_graphql(&long_query).is_err());
+    }
+    let errors = recent_errors();
+    assert_eq!(errors.len(), RECENT_ERRORS_CAPACITY);
+    assert!(errors
+        .iter()
+        .all(|(prefix, _)| long_query.starts_with(prefix)));
+    assert!(errors
+        .iter()
+        .all(|(prefix, _)| prefix.len() < long_query.len()));
+}
@@ -0,0 +1,45 @@
+use grasql::testing::{replay, ReplayResult};
+
+/// Helper function to ensure GraSQL is initialized before running tests
+fn initialize_grasql() {
+    let _ = grasql::types::initialize_for_test();
+}
+
+/// Queries in the committed corpus, split on `---` separator lines
+fn corpus() -> Vec<&'static str> {
+    include_str!("corpus/replay.graphql")
+        .split("\n---\n")
+        .map(str::trim)
+        .filter(|query| !query.is_empty())
+        .collect()
+}
+
+#[test]
+fn test_replay_corpus() {
+    initialize_grasql();
+
+    let queries = corpus();
+    let report = replay(&queries);
+    assert_eq!(report.outcomes.len(), queries.len());
+    assert!(report.panics().is_empty(), "{:?}", report.panics());
+
+    for outcome in &report.outcomes {
+        let expect_error = outcome
+            .query
+            .lines()
+            .any(|line| line.trim() == "# expect-error");
+        match &outcome.result {
+            ReplayResult::Parsed(request) => {
+                assert!(!expect_error, "Expected an error for:\n{}", outcome.query);
+                assert!(!request.query_id.is_empty());
+            }
+            ReplayResult::Failed(error) => assert!(
+                expect_error,
+                "Unexpected error for:\n{}\n{}",
+                outcome.query, error
+            ),
+            ReplayResult::Panicked(_) => unreachable!(),
+        }
+    }
+    assert_eq!(report.parsed() + report.failures().len(), queries.len());
+}
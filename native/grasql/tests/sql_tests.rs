@@ -0,0 +1,1307 @@
+mod common;
+
+use common::{generate, initialize_grasql, resolve_query, SCHEMA};
+use graphql_query::ast::OperationKind;
+use grasql::interning::intern_str;
+use grasql::parser::{parse_graphql, parse_graphql_with_prefixes};
+use grasql::sql::{
+    explain_sql, generate_prepared_sql_with_config, generate_sql, generate_sql_with_config,
+    ParamValue, PlanJoin, SqlParam, StatementKind,
+};
+use grasql::types::FieldPath;
+use grasql::{
+    add_to_cache_with_request, cache_resolved_schema, get_from_cache, get_resolved_schema, Config,
+    Dialect, MutationPrefixes, NullOrdering, SqlKeywordCase,
+};
+
+#[test]
+fn test_simple_select() {
+    initialize_grasql();
+
+    let statements = generate("{ users { id name } }");
+
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].name, "users");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", t0."name" AS "name" FROM "public"."users" AS t0"#
+    );
+    assert!(statements[0].params.is_empty());
+}
+
+#[test]
+fn test_relationship_filter_uses_exists() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        {
+            users(where: { posts: { published: { _eq: true } } }) {
+                id
+                name
+            }
+        }
+        "#,
+    );
+
+    let sql = &statements[0].sql;
+    assert_eq!(
+        sql,
+        r#"SELECT t0."id" AS "id", t0."name" AS "name" FROM "public"."users" AS t0 WHERE EXISTS (SELECT 1 FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id" AND t1."published" = $1)"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::Boolean(true))]
+    );
+}
+
+#[test]
+fn test_to_one_relationship_filter_uses_key_comparison() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        {
+            posts(where: { author: { name: { _eq: $name } } }) {
+                title
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."title" AS "title" FROM "public"."posts" AS t0 WHERE (t0."user_id") IN (SELECT t1."id" FROM "public"."users" AS t1 WHERE t1."name" = $1)"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Variable("name".to_string())]
+    );
+}
+
+#[test]
+fn test_nested_relationship_filters_and_selection() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        {
+            users(where: {
+                _or: [
+                    { name: { _like: "%John%" } },
+                    { posts: { comments: { body: { _ilike: "%great%" } } } }
+                ]
+            }) {
+                id
+                posts(where: { published: { _eq: true } }) {
+                    title
+                }
+            }
+        }
+        "#,
+    );
+
+    let sql = &statements[0].sql;
+    assert!(
+        sql.contains(r#"EXISTS (SELECT 1 FROM "public"."posts" AS t2 WHERE t2."user_id" = t0."id" AND EXISTS (SELECT 1 FROM "public"."comments" AS t3 WHERE t3."post_id" = t2."id" AND t3."body" ILIKE $3))"#),
+        "Nested relationship filters should nest EXISTS subqueries: {}",
+        sql
+    );
+    assert!(
+        sql.contains(r#"(SELECT coalesce(json_agg(json_build_object('title', t1."title")), '[]') FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id" AND t1."published" = $1) AS "posts""#),
+        "Selected relationships should be JSON subqueries: {}",
+        sql
+    );
+    assert_eq!(statements[0].params.len(), 3);
+}
+
+#[test]
+fn test_upsert_on_conflict_constraint() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        mutation {
+            insert_users(
+                objects: [
+                    { id: 1, name: "John", email: "john@example.com" },
+                    { id: 2, name: "Jane" }
+                ],
+                on_conflict: { constraint: users_pkey, update_columns: [name, email] }
+            ) {
+                returning {
+                    id
+                    name
+                }
+                affected_rows
+            }
+        }
+        "#,
+    );
+
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].name, "insert_users");
+    assert_eq!(
+        statements[0].sql,
+        r#"INSERT INTO "public"."users" AS t0 ("id", "name", "email") VALUES ($1, $2, $3), ($4, $5, DEFAULT) ON CONFLICT ON CONSTRAINT "users_pkey" DO UPDATE SET "name" = EXCLUDED."name", "email" = EXCLUDED."email" RETURNING t0."id" AS "id", t0."name" AS "name""#
+    );
+    assert_eq!(statements[0].params.len(), 5);
+
+    // An empty update list leaves existing rows untouched
+    let statements = generate(
+        r#"
+        mutation {
+            insert_users(
+                object: { name: "John" },
+                on_conflict: { constraint: users_email_key, update_columns: [] }
+            ) {
+                affected_rows
+            }
+        }
+        "#,
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"INSERT INTO "public"."users" AS t0 ("name") VALUES ($1) ON CONFLICT ON CONSTRAINT "users_email_key" DO NOTHING"#
+    );
+}
+
+#[test]
+fn test_insert_with_prefix_override() {
+    initialize_grasql();
+
+    let prefixes = MutationPrefixes {
+        insert_prefix: Some("add_".to_string()),
+        ..MutationPrefixes::default()
+    };
+    let (parsed, request) = parse_graphql_with_prefixes(
+        r#"mutation { add_users(objects: [{ name: "John" }]) { affected_rows } }"#,
+        Some(&prefixes),
+    )
+    .unwrap();
+    let mut info: grasql::CachedQueryInfo = parsed.into();
+    info.resolution_request = Some(request);
+    assert_eq!(info.mutation_prefixes, Some(prefixes));
+
+    // SQL generation classifies the field with the prefixes it was parsed with
+    let response = SCHEMA.resolve(&info);
+    let statements = generate_sql(&info, &response).unwrap();
+    assert_eq!(statements[0].kind, StatementKind::Insert);
+    assert_eq!(
+        statements[0].sql,
+        r#"INSERT INTO "public"."users" AS t0 ("name") VALUES ($1)"#
+    );
+}
+
+#[test]
+fn test_delete_by_composite_pk() {
+    initialize_grasql();
+
+    let query = r#"
+    mutation {
+        delete_orders_by_pk(order_id: 1, product_id: $product) {
+            order_id
+            quantity
+        }
+    }
+    "#;
+
+    // Every key argument is a column the delete matches its row on
+    let (info, response) = resolve_query(query);
+    let mut path = FieldPath::new();
+    path.push(intern_str("delete_orders_by_pk"));
+    let filter_columns = &info.filter_columns.as_ref().unwrap()[&path];
+    assert_eq!(filter_columns.len(), 2);
+    assert!(filter_columns.contains(&intern_str("order_id")));
+    assert!(filter_columns.contains(&intern_str("product_id")));
+
+    let statements = generate_sql(&info, &response).unwrap();
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].kind, StatementKind::Delete);
+    assert_eq!(
+        statements[0].sql,
+        r#"DELETE FROM "public"."orders" AS t0 WHERE t0."order_id" = $1 AND t0."product_id" = $2 RETURNING t0."order_id" AS "order_id", t0."quantity" AS "quantity""#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::Int(1)),
+            SqlParam::Variable("product".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_multiple_root_fields_generate_separate_statements() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        {
+            users(where: { name: { _eq: "John" } }) { id }
+            recent: posts(where: { published: { _eq: true } }) { id }
+        }
+        "#,
+    );
+
+    // One independently executable statement per root field, in document order
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[0].name, "users");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE t0."name" = $1"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::String("John".to_string()))]
+    );
+
+    // Placeholders and aliases restart for each statement
+    assert_eq!(statements[1].name, "recent");
+    assert_eq!(
+        statements[1].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."posts" AS t0 WHERE t0."published" = $1"#
+    );
+    assert_eq!(
+        statements[1].params,
+        vec![SqlParam::Literal(ParamValue::Boolean(true))]
+    );
+}
+
+#[test]
+fn test_self_referential_relationship_aliases() {
+    initialize_grasql();
+
+    let query = "{ employees { name manager { name } reports { name } } }";
+
+    // The resolver's table indices are enough to spot self-references
+    let (_, response) = resolve_query(query);
+    assert_eq!(response.rels.len(), 2);
+    assert!((0..response.rels.len()).all(|idx| response.is_self_referential(idx)));
+
+    let statements = generate(query);
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."name" AS "name", (SELECT json_build_object('name', t1."name") FROM "public"."employees" AS t1 WHERE t1."id" = t0."manager_id") AS "manager", (SELECT coalesce(json_agg(json_build_object('name', t2."name")), '[]') FROM "public"."employees" AS t2 WHERE t2."manager_id" = t0."id") AS "reports" FROM "public"."employees" AS t0"#
+    );
+
+    // Relationships between different tables are not self-referential
+    let (_, response) = resolve_query("{ users { posts { id } } }");
+    assert!(!response.is_self_referential(0));
+    assert!(!response.is_self_referential(99));
+}
+
+#[test]
+fn test_always_include_primary_key() {
+    initialize_grasql();
+
+    let query = "{ users { name posts { title } profile { userId: id bio } } }";
+    let (info, response) = resolve_query(query);
+
+    let config = Config {
+        always_include_pk: true,
+        ..Config::default()
+    };
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."name" AS "name", (SELECT coalesce(json_agg(json_build_object('title', t1."title", 'id', t1."id")), '[]') FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id") AS "posts", (SELECT json_build_object('userId', t2."id", 'bio', t2."bio") FROM "public"."profiles" AS t2 WHERE t2."user_id" = t0."id") AS "profile", t0."id" AS "id" FROM "public"."users" AS t0"#
+    );
+
+    // Disabled by default
+    let statements = generate_sql_with_config(&info, &response, &Config::default()).unwrap();
+    assert!(!statements[0].sql.contains(r#"t0."id" AS "id""#));
+}
+
+#[test]
+fn test_null_literal_comparisons() {
+    initialize_grasql();
+
+    let statements =
+        generate("{ users(where: { deleted_at: { _eq: null }, email: { _neq: null } }) { id } }");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE t0."deleted_at" IS NULL AND t0."email" IS NOT NULL"#
+    );
+    assert!(statements[0].params.is_empty());
+
+    // Ordering against null has no meaning
+    let (info, response) = resolve_query("{ users(where: { age: { _gt: null } }) { id } }");
+    let err = generate_sql_with_config(&info, &response, &Config::default()).unwrap_err();
+    assert!(err.contains("cannot compare against null"), "{}", err);
+}
+
+#[test]
+fn test_scalar_json_field_projection() {
+    initialize_grasql();
+
+    let config = Config {
+        scalar_fields: vec!["metadata".to_string()],
+        ..Config::default()
+    };
+    let query = "{ users { id settings: metadata { theme address { city } } } }";
+    let (parsed, request) = grasql::parser::parse_graphql_with_config(query, &config).unwrap();
+    let mut info: grasql::CachedQueryInfo = parsed.into();
+    info.resolution_request = Some(request);
+    let response = SCHEMA.resolve(&info);
+
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", json_build_object('theme', t0."metadata" -> 'theme', 'address', json_build_object('city', t0."metadata" -> 'address' -> 'city')) AS "settings" FROM "public"."users" AS t0"#
+    );
+}
+
+#[test]
+fn test_cast_comparisons() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"{ users(where: { zip: { _cast: { String: { _like: "9%", _neq: null } } }, age: { _gt: 1 } }) { id } }"#,
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE CAST(t0."zip" AS text) LIKE $1 AND CAST(t0."zip" AS text) IS NOT NULL AND t0."age" > $2"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::String("9%".to_string())),
+            SqlParam::Literal(ParamValue::Int(1)),
+        ]
+    );
+
+    // Each type key needs its own operators
+    let (info, response) =
+        resolve_query(r#"{ users(where: { zip: { _cast: { String: "9" } } }) { id } }"#);
+    let err = generate_sql_with_config(&info, &response, &Config::default()).unwrap_err();
+    assert!(err.contains("expects an object of operators"), "{}", err);
+}
+
+#[test]
+fn test_pagination_arguments() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        query PagedUsers($limit: Int!, $offset: Int) {
+            users(where: { active: { _eq: true } }, limit: $limit, offset: $offset) { id }
+        }
+        "#,
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE t0."active" = $1 LIMIT $2 OFFSET $3"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::Boolean(true)),
+            SqlParam::Variable("limit".to_string()),
+            SqlParam::Variable("offset".to_string()),
+        ]
+    );
+
+    let statements = generate("{ posts(limit: 10) { id } }");
+    assert!(statements[0].sql.ends_with(" LIMIT $1"));
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::Int(10))]
+    );
+
+    // A zero limit is still emitted
+    let statements = generate("{ posts(limit: 0) { id } }");
+    assert!(statements[0].sql.ends_with(" LIMIT $1"));
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::Int(0))]
+    );
+}
+
+#[test]
+fn test_max_bind_params() {
+    initialize_grasql();
+
+    let ids = (1..=20)
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "{{ users(where: {{ id: {{ _in: [{}] }} }}) {{ id }} }}",
+        ids
+    );
+    let (info, response) = resolve_query(&query);
+
+    let config = Config {
+        max_bind_params: Some(10),
+        ..Config::default()
+    };
+    let err = generate_sql_with_config(&info, &response, &config).unwrap_err();
+    assert_eq!(
+        err,
+        "Statement for 'users' binds 20 parameters, exceeding the limit of 10"
+    );
+
+    // Exactly at the limit is fine, and there is no limit by default
+    let config = Config {
+        max_bind_params: Some(20),
+        ..Config::default()
+    };
+    assert!(generate_sql_with_config(&info, &response, &config).is_ok());
+    assert!(generate_sql_with_config(&info, &response, &Config::default()).is_ok());
+
+    // Multi-row inserts count every bound value
+    let (info, response) = resolve_query(
+        r#"mutation { insert_posts(objects: [{ title: "a", body: "b" }, { title: "c", body: "d" }]) { affected_rows } }"#,
+    );
+    let config = Config {
+        max_bind_params: Some(3),
+        ..Config::default()
+    };
+    let err = generate_sql_with_config(&info, &response, &config).unwrap_err();
+    assert!(err.contains("binds 4 parameters"), "{}", err);
+}
+
+#[test]
+fn test_enum_operands_bind_as_strings() {
+    initialize_grasql();
+
+    let statements = generate(
+        "{ users(where: { status: { _eq: ACTIVE }, role: { _in: [ADMIN, EDITOR] } }) { id } }",
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE t0."status" = $1 AND t0."role" IN ($2, $3)"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::String("ACTIVE".to_string())),
+            SqlParam::Literal(ParamValue::String("ADMIN".to_string())),
+            SqlParam::Literal(ParamValue::String("EDITOR".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn test_order_by_relationship_column_joins_relationship() {
+    initialize_grasql();
+
+    let statements = generate(
+        "{ users(order_by: [{ profile: { bio: asc } }, { name: desc_nulls_last }]) { id } }",
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 LEFT JOIN "public"."profiles" AS t1 ON t1."user_id" = t0."id" ORDER BY t1."bio" ASC, t0."name" DESC NULLS LAST"#
+    );
+
+    let (info, response) = resolve_query("{ users(order_by: { posts: { title: asc } }) { id } }");
+    let err = generate_sql_with_config(&info, &response, &Config::default()).unwrap_err();
+    assert_eq!(err, "Cannot order by to-many relationship 'posts'");
+}
+
+#[test]
+fn test_null_ordering() {
+    initialize_grasql();
+
+    let (info, response) = resolve_query(
+        "{ users(order_by: [{ name: asc }, { email: desc_nulls_first }, { id: desc }]) { id } }",
+    );
+    let order_by = |null_ordering| {
+        let config = Config {
+            null_ordering,
+            ..Config::default()
+        };
+        let sql = generate_sql_with_config(&info, &response, &config).unwrap()[0]
+            .sql
+            .clone();
+        sql.split_once(" ORDER BY ").unwrap().1.to_string()
+    };
+
+    // Plain directions follow the config; explicit placements are kept
+    assert_eq!(
+        order_by(NullOrdering::DbDefault),
+        r#"t0."name" ASC, t0."email" DESC NULLS FIRST, t0."id" DESC"#
+    );
+    assert_eq!(
+        order_by(NullOrdering::NullsFirst),
+        r#"t0."name" ASC NULLS FIRST, t0."email" DESC NULLS FIRST, t0."id" DESC NULLS FIRST"#
+    );
+    assert_eq!(
+        order_by(NullOrdering::NullsLast),
+        r#"t0."name" ASC NULLS LAST, t0."email" DESC NULLS FIRST, t0."id" DESC NULLS LAST"#
+    );
+}
+
+#[test]
+fn test_bare_value_filter_is_equality() {
+    initialize_grasql();
+
+    let statements = generate("{ users(where: { active: true, deleted_at: null }) { id } }");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE t0."active" = $1 AND t0."deleted_at" IS NULL"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::Boolean(true))]
+    );
+}
+
+#[test]
+fn test_column_arguments_are_rejected() {
+    initialize_grasql();
+
+    // Each alias would need its own expression, which a plain column can't give
+    let (info, response) =
+        resolve_query("{ users { small: avatar(size: 50) big: avatar(size: 200) } }");
+    assert_eq!(
+        generate_sql(&info, &response).unwrap_err(),
+        "Arguments on column 'small' are not supported in SQL generation"
+    );
+}
+
+#[test]
+fn test_unknown_operator_is_ignored() {
+    initialize_grasql();
+
+    let (info, response) =
+        resolve_query(r#"{ users(where: { _foo: { _eq: 1 }, name: { _eq: "x" } }) { id } }"#);
+    let statements = generate_sql(&info, &response).unwrap();
+
+    // Extraction warned about `_foo`; it isn't compared as a column either
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 WHERE t0."name" = $1"#
+    );
+}
+
+#[test]
+fn test_sql_keyword_case() {
+    initialize_grasql();
+
+    let query = r#"{ users(where: { name: { _ilike: "A%" }, profile: { bio: { _is_null: false } } }, order_by: { name: desc }, limit: 5) { userName: name posts { title } } }"#;
+    let (info, response) = resolve_query(query);
+
+    let upper = generate_sql_with_config(&info, &response, &Config::default()).unwrap();
+    let config = Config {
+        sql_keyword_case: SqlKeywordCase::Lower,
+        ..Config::default()
+    };
+    let lower = generate_sql_with_config(&info, &response, &config).unwrap();
+
+    assert_eq!(
+        lower[0].sql,
+        r#"select t0."name" as "userName", (select coalesce(json_agg(json_build_object('title', t1."title")), '[]') from "public"."posts" as t1 where t1."user_id" = t0."id") as "posts" from "public"."users" as t0 where t0."name" ilike $1 and (t0."id") in (select t2."user_id" from "public"."profiles" as t2 where t2."bio" is not null) order by t0."name" desc limit $2"#
+    );
+    assert!(upper[0]
+        .sql
+        .starts_with(r#"SELECT t0."name" AS "userName""#));
+
+    // Only the case of the keywords differs; quoted identifiers keep theirs
+    assert_ne!(upper[0].sql, lower[0].sql);
+    assert!(upper[0].sql.eq_ignore_ascii_case(&lower[0].sql));
+    assert_eq!(upper[0].params, lower[0].params);
+}
+
+#[test]
+fn test_prepared_statement_names() {
+    initialize_grasql();
+
+    let prepare = |query: &str| {
+        let (info, response) = resolve_query(query);
+        generate_prepared_sql_with_config(&info, &response, &Config::default()).unwrap()
+    };
+
+    let first = prepare(r#"{ users(where: { name: { _eq: "Alice" } }, limit: 5) { id name } }"#);
+    let second = prepare(r#"{ users(where: { name: { _eq: "Bob" } }, limit: 50) { id name } }"#);
+    assert_eq!(first[0].name, second[0].name);
+    assert!(first[0].name.starts_with("grasql_"));
+    assert_eq!(first[0].statement.sql, second[0].statement.sql);
+    assert_eq!(first[0].statement.params.len(), 2);
+    assert_eq!(
+        first[0].param_types,
+        [Some("text".to_string()), Some("bigint".to_string())]
+    );
+    assert_eq!(first[0].param_types, second[0].param_types);
+
+    // A different shape gets a different statement
+    let other = prepare(r#"{ users(where: { name: { _neq: "Alice" } }, limit: 5) { id name } }"#);
+    assert_ne!(first[0].name, other[0].name);
+
+    // Variables are typed by their declaration
+    let variables = prepare("query($ids: [Int!]!) { users(where: { id: { _in: $ids } }) { id } }");
+    assert_eq!(variables[0].param_types, [Some("integer[]".to_string())]);
+}
+
+#[test]
+fn test_mysql_prepared_statement_types() {
+    initialize_grasql();
+
+    let config = Config {
+        sql_dialect: Dialect::Mysql,
+        ..Config::default()
+    };
+    let prepare = |query: &str| {
+        let (info, response) = resolve_query(query);
+        generate_prepared_sql_with_config(&info, &response, &config).unwrap()
+    };
+
+    let literals = prepare(r#"{ users(where: { name: { _eq: "Alice" } }, limit: 5) { id name } }"#);
+    assert_eq!(
+        literals[0].param_types,
+        [Some("TEXT".to_string()), Some("BIGINT".to_string())]
+    );
+
+    // Lists are bound as JSON arrays
+    let variables = prepare("query($ids: [Int!]!) { users(where: { id: { _in: $ids } }) { id } }");
+    assert_eq!(variables[0].param_types, [Some("JSON".to_string())]);
+}
+
+#[test]
+fn test_function_field_select() {
+    initialize_grasql();
+
+    let (info, response) =
+        resolve_query(r#"{ posts(args: { search: "rust", tag: $tag }, limit: 3) { id title } }"#);
+    let config = Config {
+        function_fields: vec!["posts".to_string()],
+        ..Config::default()
+    };
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", t0."title" AS "title" FROM "public"."posts"("search" => $1, "tag" => $2) AS t0 LIMIT $3"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::String("rust".to_string())),
+            SqlParam::Variable("tag".to_string()),
+            SqlParam::Literal(ParamValue::Int(3)),
+        ]
+    );
+}
+
+#[test]
+fn test_mutation_returning_cte() {
+    initialize_grasql();
+
+    let query = r#"
+    mutation {
+        insert_users(objects: [{ name: "John" }, { name: "Jane" }]) {
+            returning {
+                id
+                name
+            }
+            affected_rows
+        }
+    }
+    "#;
+    let (info, response) = resolve_query(query);
+    let config = Config {
+        mutation_returning_cte: true,
+        ..Config::default()
+    };
+
+    // The insert returns whole rows and the selection reads them from the CTE
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].kind, StatementKind::Insert);
+    assert_eq!(
+        statements[0].sql,
+        r#"WITH mutation AS (INSERT INTO "public"."users" AS t0 ("name") VALUES ($1), ($2) RETURNING t0.*) SELECT t1."id" AS "id", t1."name" AS "name" FROM mutation AS t1"#
+    );
+    assert_eq!(statements[0].params.len(), 2);
+
+    // Relationships are correlated with the returned rows
+    let (info, response) = resolve_query(
+        r#"mutation { insert_users(objects: [{ name: "John" }]) { returning { id posts { title } } } }"#,
+    );
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"WITH mutation AS (INSERT INTO "public"."users" AS t0 ("name") VALUES ($1) RETURNING t0.*) SELECT t1."id" AS "id", (SELECT coalesce(json_agg(json_build_object('title', t2."title")), '[]') FROM "public"."posts" AS t2 WHERE t2."user_id" = t1."id") AS "posts" FROM mutation AS t1"#
+    );
+
+    // Without a returning selection there is nothing to wrap
+    let (info, response) =
+        resolve_query(r#"mutation { insert_users(objects: [{ name: "A" }]) { affected_rows } }"#);
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"INSERT INTO "public"."users" AS t0 ("name") VALUES ($1)"#
+    );
+}
+
+#[test]
+fn test_empty_insert() {
+    initialize_grasql();
+
+    let query = r#"mutation { insert_users(objects: []) { returning { id } affected_rows } }"#;
+    let (info, response) = resolve_query(query);
+
+    // Rejected by default, since there is nothing to insert
+    let err = generate_sql_with_config(&info, &response, &Config::default()).unwrap_err();
+    assert_eq!(
+        err,
+        "'insert_users' has an empty objects list; set allow_empty_insert to insert zero rows"
+    );
+
+    // Allowed, it inserts no rows and returns none
+    let config = Config {
+        allow_empty_insert: true,
+        ..Config::default()
+    };
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"INSERT INTO "public"."users" AS t0 SELECT * FROM "public"."users" WHERE FALSE RETURNING t0."id" AS "id""#
+    );
+    assert!(statements[0].params.is_empty());
+
+    // A non-empty list is unaffected by the setting
+    let (info, response) =
+        resolve_query(r#"mutation { insert_users(objects: [{ name: "A" }]) { affected_rows } }"#);
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"INSERT INTO "public"."users" AS t0 ("name") VALUES ($1)"#
+    );
+}
+
+#[test]
+fn test_reserved_word_identifiers_quoted() {
+    initialize_grasql();
+
+    let (info, response) = resolve_query("{ users { id order userName: name } }");
+    let config = Config {
+        quote_all_identifiers: false,
+        ..Config::default()
+    };
+    let statements = generate_sql_with_config(&info, &response, &config).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0.id AS id, t0."order" AS "order", t0.name AS "userName" FROM public.users AS t0"#
+    );
+
+    // Every identifier is quoted by default
+    let statements = generate_sql_with_config(&info, &response, &Config::default()).unwrap();
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", t0."order" AS "order", t0."name" AS "userName" FROM "public"."users" AS t0"#
+    );
+}
+
+#[test]
+fn test_cached_resolved_schema() {
+    initialize_grasql();
+
+    // Cache the parsed query the way the parse NIF does
+    let (parsed, request) =
+        parse_graphql("query CachedSchema { users(limit: 5) { id posts { title } } }").unwrap();
+    let query_id = request.query_id.clone();
+    add_to_cache_with_request(&query_id, parsed, request);
+
+    let info = get_from_cache(&query_id).unwrap();
+    let response = SCHEMA.resolve(&info);
+    let first = generate_sql(&info, &response).unwrap();
+
+    // Nothing is stored until the schema is cached explicitly
+    assert!(get_resolved_schema(&query_id).is_none());
+    assert!(cache_resolved_schema(response));
+
+    // The second generation needs only the query ID
+    let cached_info = get_from_cache(&query_id).unwrap();
+    let schema = get_resolved_schema(&query_id).unwrap();
+    let second = generate_sql(&cached_info, &schema).unwrap();
+    assert_eq!(second, first);
+
+    // Storing the schema left the cached query itself untouched
+    assert!(std::ptr::eq(
+        cached_info.ast_context.as_deref().unwrap(),
+        info.ast_context.as_deref().unwrap()
+    ));
+
+    // Caching the query afresh drops the schema stored for the old entry
+    let (parsed, request) =
+        parse_graphql("query CachedSchema { users(limit: 5) { id posts { title } } }").unwrap();
+    add_to_cache_with_request(&query_id, parsed, request);
+    assert!(get_resolved_schema(&query_id).is_none());
+
+    // Schemas for queries that aren't cached are not stored
+    let mut orphan = (*schema).clone();
+    orphan.query_id = "not-a-cached-query".to_string();
+    assert!(!cache_resolved_schema(orphan));
+    assert!(get_resolved_schema("not-a-cached-query").is_none());
+}
+
+#[test]
+fn test_column_aliases_in_select() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+    {
+        active_users: users(where: { status: { _eq: "ACTIVE" } }) {
+            id
+            full_name: name
+            contact_info: profile {
+                email
+                phone
+            }
+            recent_posts: posts(limit: 5, order_by: { created_at: desc }) {
+                id
+                headline: title
+            }
+        }
+    }
+    "#,
+    );
+
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].name, "active_users");
+    let sql = &statements[0].sql;
+
+    // Aliased columns and relationships are selected under their alias
+    assert!(sql.starts_with(r#"SELECT t0."id" AS "id", t0."name" AS "full_name", "#));
+    assert!(sql.contains(r#") AS "contact_info", "#));
+    assert!(sql.contains(r#") AS "recent_posts" FROM "public"."users" AS t0"#));
+
+    // Inside relationship objects the alias is the JSON key
+    assert!(sql.contains(r#"json_build_object('email', t1."email", 'phone', t1."phone")"#));
+    assert!(sql.contains(r#"json_build_object('id', t2."id", 'headline', t2."title")"#));
+    assert!(!sql.contains(r#"AS "name""#));
+}
+
+#[test]
+fn test_explain_query_plan() {
+    initialize_grasql();
+
+    // The nested_relationship_filter end-to-end sample, with pagination added
+    let (info, response) = resolve_query(
+        r#"
+        {
+            users(limit: 10, offset: 20, order_by: { name: asc }) {
+                id
+                posts(where: { published: { _eq: true } }) {
+                    title
+                    comments(where: { approved: { _eq: true } }) { body }
+                }
+            }
+        }
+        "#,
+    );
+
+    let explained = explain_sql(&info, &response).unwrap();
+    assert_eq!(explained.len(), 1);
+
+    // The statements are the ones generate_sql produces
+    assert_eq!(
+        explained[0].statement,
+        generate_sql(&info, &response).unwrap().remove(0)
+    );
+
+    let users = &explained[0].plan;
+    assert_eq!(users.name, "users");
+    assert_eq!(users.table, r#""public"."users""#);
+    assert_eq!(users.join, None);
+    assert_eq!(users.filter, None);
+    assert_eq!(users.order_by, vec![r#"t0."name" ASC"#]);
+    assert_eq!(users.limit.as_deref(), Some("$3"));
+    assert_eq!(users.offset.as_deref(), Some("$4"));
+
+    assert_eq!(users.children.len(), 1);
+    let posts = &users.children[0];
+    assert_eq!(posts.name, "posts");
+    assert_eq!(posts.table, r#""public"."posts""#);
+    assert_eq!(posts.join, Some(PlanJoin::HasMany));
+    assert_eq!(posts.filter.as_deref(), Some(r#"t1."published" = $2"#));
+    assert_eq!(posts.limit, None);
+
+    assert_eq!(posts.children.len(), 1);
+    let comments = &posts.children[0];
+    assert_eq!(comments.table, r#""public"."comments""#);
+    assert_eq!(comments.join, Some(PlanJoin::HasMany));
+    assert_eq!(comments.filter.as_deref(), Some(r#"t2."approved" = $1"#));
+    assert!(comments.children.is_empty());
+}
+
+#[test]
+fn test_max_relationship_chain() {
+    initialize_grasql();
+
+    // employees appears five times along the manager chain
+    let query = "{ employees { name manager { manager { manager { manager { name } } } } } }";
+    let (info, response) = resolve_query(query);
+
+    let config = Config {
+        max_relationship_chain: Some(3),
+        ..Config::default()
+    };
+    let err = generate_sql_with_config(&info, &response, &config).unwrap_err();
+    assert_eq!(
+        err,
+        "Field 'manager' reads table 'employees' 4 times along one path, exceeding the limit of 3"
+    );
+
+    // Exactly at the limit is fine, and there is no limit by default
+    let config = Config {
+        max_relationship_chain: Some(5),
+        ..Config::default()
+    };
+    assert!(generate_sql_with_config(&info, &response, &config).is_ok());
+    assert!(generate_sql_with_config(&info, &response, &Config::default()).is_ok());
+
+    // Sibling relationships don't lengthen each other's paths, and chains
+    // through other tables count every visit to the repeated one
+    let config = Config {
+        max_relationship_chain: Some(2),
+        ..Config::default()
+    };
+    let (info, response) = resolve_query("{ employees { manager { name } reports { name } } }");
+    assert!(generate_sql_with_config(&info, &response, &config).is_ok());
+    let (info, response) =
+        resolve_query("{ users { posts { author { posts { author { name } } } } } }");
+    assert_eq!(
+        generate_sql_with_config(&info, &response, &config).unwrap_err(),
+        "Field 'author' reads table 'users' 3 times along one path, exceeding the limit of 2"
+    );
+}
+
+#[test]
+fn test_multi_column_distinct_on() {
+    initialize_grasql();
+
+    let statements = generate(
+        "{ users(distinct_on: [city, name], order_by: [{ name: asc }, { city: desc }, { id: asc }]) { id name } }",
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT DISTINCT ON (t0."city", t0."name") t0."id" AS "id", t0."name" AS "name" FROM "public"."users" AS t0 ORDER BY t0."name" ASC, t0."city" DESC, t0."id" ASC"#
+    );
+
+    // A single column needs no list, and needs no order_by
+    let statements = generate("{ users(distinct_on: city) { id } }");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT DISTINCT ON (t0."city") t0."id" AS "id" FROM "public"."users" AS t0"#
+    );
+
+    // PostgreSQL requires the distinct_on columns to lead the ORDER BY
+    for query in [
+        "{ users(distinct_on: [city, name], order_by: [{ name: asc }, { id: asc }]) { id } }",
+        "{ users(distinct_on: [city, name], order_by: { city: asc }) { id } }",
+        "{ users(distinct_on: city, order_by: [{ id: asc }, { city: asc }]) { id } }",
+    ] {
+        let (info, response) = resolve_query(query);
+        assert_eq!(
+            generate_sql(&info, &response).unwrap_err(),
+            "'distinct_on' columns of 'users' must be the leading 'order_by' columns",
+            "{}",
+            query
+        );
+    }
+
+    let (info, response) = resolve_query(r#"{ users(distinct_on: "city") { id } }"#);
+    assert_eq!(
+        generate_sql(&info, &response).unwrap_err(),
+        "'distinct_on' expects a column or a list of columns"
+    );
+}
+
+#[test]
+fn test_mixed_literal_and_variable_params_follow_placeholders() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"
+        query People($name: String, $city: String, $title: String, $limit: Int) {
+            users(
+                where: {
+                    age: { _gt: 30 },
+                    name: { _eq: $name },
+                    _or: [{ city: { _eq: $city } }, { score: { _lt: 5 } }]
+                },
+                limit: $limit
+            ) {
+                id
+                posts(where: { views: { _gte: 10 }, title: { _ilike: $title } }) { id }
+            }
+        }
+        "#,
+    );
+
+    // Placeholders are numbered in the order they appear in the statement,
+    // and each binds the literal or variable written at that spot
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", (SELECT coalesce(json_agg(json_build_object('id', t1."id")), '[]') FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id" AND t1."views" >= $1 AND t1."title" ILIKE $2) AS "posts" FROM "public"."users" AS t0 WHERE t0."age" > $3 AND t0."name" = $4 AND ((t0."city" = $5) OR (t0."score" < $6)) LIMIT $7"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::Int(10)),
+            SqlParam::Variable("title".to_string()),
+            SqlParam::Literal(ParamValue::Int(30)),
+            SqlParam::Variable("name".to_string()),
+            SqlParam::Variable("city".to_string()),
+            SqlParam::Literal(ParamValue::Int(5)),
+            SqlParam::Variable("limit".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_statement_kind_for_routing() {
+    initialize_grasql();
+
+    let statements = generate("{ users { id } posts { id } }");
+    assert!(statements
+        .iter()
+        .all(|statement| statement.kind == StatementKind::Query && !statement.kind.is_write()));
+
+    let statements =
+        generate(r#"mutation { insert_posts(objects: [{ title: "Hello" }]) { affected_rows } }"#);
+    assert_eq!(statements[0].kind, StatementKind::Insert);
+    assert!(statements[0].kind.is_write());
+
+    // Update and non-`_by_pk` delete SQL isn't generated yet, but their fields classify as writes
+    let config = Config::default();
+    let update = StatementKind::of(OperationKind::Mutation, "update_users", &config);
+    assert_eq!(update, StatementKind::Update);
+    assert!(update.is_write());
+    let delete = StatementKind::of(OperationKind::Mutation, "delete_users", &config);
+    assert_eq!(delete, StatementKind::Delete);
+    assert!(delete.is_write());
+
+    // Only mutation fields are told apart by their prefix
+    assert_eq!(
+        StatementKind::of(OperationKind::Query, "update_log", &config),
+        StatementKind::Query
+    );
+    assert_eq!(
+        StatementKind::of(OperationKind::Subscription, "users", &config),
+        StatementKind::Query
+    );
+}
+
+#[test]
+fn test_order_by_relationship_aggregate() {
+    initialize_grasql();
+
+    let query = "{ users(order_by: [{ posts_aggregate: { count: desc, max: { views: asc } } }, { name: asc }]) { id } }";
+
+    // The relationship is resolved under its own name, not the aggregate's
+    let (info, _) = resolve_query(query);
+    let field_paths = info.field_paths.as_ref().unwrap();
+    let posts = FieldPath::new()
+        .with_field(intern_str("users"))
+        .with_field(intern_str("posts"));
+    assert!(field_paths.contains(&posts));
+    assert_eq!(field_paths.len(), 2);
+    assert!(info.column_usage.as_ref().unwrap()[&posts].contains(&intern_str("views")));
+
+    let statements = generate(query);
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id" FROM "public"."users" AS t0 ORDER BY (SELECT count(*) FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id") DESC, (SELECT max(t2."views") FROM "public"."posts" AS t2 WHERE t2."user_id" = t0."id") ASC, t0."name" ASC"#
+    );
+
+    let (info, response) = resolve_query(
+        "{ users(order_by: { posts_aggregate: { median: { views: asc } } }) { id } }",
+    );
+    assert_eq!(
+        generate_sql(&info, &response).unwrap_err(),
+        "Unknown aggregate 'median' in 'order_by' on 'posts_aggregate'"
+    );
+}
+
+#[test]
+fn test_max_identifier_length() {
+    initialize_grasql();
+
+    let column = "a".repeat(64);
+    let query = format!("{{ users {{ id {} }} }}", column);
+    let (info, response) = resolve_query(&query);
+
+    let config = Config {
+        max_identifier_length: Some(63),
+        ..Config::default()
+    };
+    assert_eq!(
+        generate_sql_with_config(&info, &response, &config).unwrap_err(),
+        format!(
+            "Identifier '{}' in the statement for 'users' is 64 bytes long, exceeding the limit of 63",
+            column
+        )
+    );
+
+    // Aliases are identifiers too
+    let alias = "b".repeat(64);
+    let (info, response) = resolve_query(&format!("{{ users {{ {}: id }} }}", alias));
+    assert!(generate_sql_with_config(&info, &response, &config)
+        .unwrap_err()
+        .starts_with(&format!("Identifier '{}'", alias)));
+
+    // Exactly at the limit is fine, and there is no limit by default
+    let (info, response) = resolve_query(&format!("{{ users {{ {} }} }}", "a".repeat(63)));
+    assert!(generate_sql_with_config(&info, &response, &config).is_ok());
+    let (info, response) = resolve_query(&query);
+    assert!(generate_sql_with_config(&info, &response, &Config::default()).is_ok());
+}
+
+#[test]
+fn test_nested_relationship_pagination() {
+    initialize_grasql();
+
+    // Rows are paged in an inner SELECT before they are aggregated
+    let statements =
+        generate("{ users { id posts(order_by: { id: desc }, limit: 1, offset: 2) { id } } }");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", (SELECT coalesce(json_agg(t2.item), '[]') FROM (SELECT json_build_object('id', t1."id") AS item FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id" ORDER BY t1."id" DESC LIMIT $1 OFFSET $2) AS t2) AS "posts" FROM "public"."users" AS t0"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![
+            SqlParam::Literal(ParamValue::Int(1)),
+            SqlParam::Literal(ParamValue::Int(2))
+        ]
+    );
+
+    // Negative values are rejected below the root too
+    assert_eq!(
+        parse_graphql("{ users { posts(offset: -5) { id } } }").unwrap_err(),
+        "'offset' must not be negative, got -5"
+    );
+
+    // A to-one relationship has a single row to page
+    let (info, response) = resolve_query("{ posts { author(limit: 1) { id } } }");
+    assert_eq!(
+        generate_sql(&info, &response).unwrap_err(),
+        "'limit' is not supported on to-one relationship 'author'"
+    );
+}
+
+#[test]
+fn test_nested_relationship_ordering() {
+    initialize_grasql();
+
+    let statements = generate(
+        r#"{ users { id posts(distinct_on: title, order_by: [{ title: asc }, { id: desc }], where: { published: { _eq: true } }) { id } } }"#,
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", (SELECT coalesce(json_agg(t2.item), '[]') FROM (SELECT DISTINCT ON (t1."title") json_build_object('id', t1."id") AS item FROM "public"."posts" AS t1 WHERE t1."user_id" = t0."id" AND t1."published" = $1 ORDER BY t1."title" ASC, t1."id" DESC) AS t2) AS "posts" FROM "public"."users" AS t0"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::Boolean(true))]
+    );
+
+    // The same distinct_on rule as at the root applies
+    let (info, response) =
+        resolve_query("{ users { posts(distinct_on: title, order_by: { id: desc }) { id } } }");
+    assert_eq!(
+        generate_sql(&info, &response).unwrap_err(),
+        "'distinct_on' columns of 'posts' must be the leading 'order_by' columns"
+    );
+}
+
+#[test]
+fn test_by_pk_query() {
+    initialize_grasql();
+
+    // The key arguments pick the row, and at most one is read
+    let statements = generate("{ users_by_pk(id: 1) { id name } }");
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."id" AS "id", t0."name" AS "name" FROM "public"."users" AS t0 WHERE t0."id" = $1 LIMIT 1"#
+    );
+    assert_eq!(
+        statements[0].params,
+        vec![SqlParam::Literal(ParamValue::Int(1))]
+    );
+
+    // Composite keys conjoin one equality per column
+    let statements = generate(
+        "query ($product: Int!) { orders_by_pk(order_id: 1, product_id: $product) { quantity } }",
+    );
+    assert_eq!(
+        statements[0].sql,
+        r#"SELECT t0."quantity" AS "quantity" FROM "public"."orders" AS t0 WHERE t0."order_id" = $1 AND t0."product_id" = $2 LIMIT 1"#
+    );
+
+    // Without key arguments it would read the whole table
+    let (info, response) = resolve_query("{ users_by_pk { id } }");
+    assert_eq!(
+        generate_sql(&info, &response).unwrap_err(),
+        "'users_by_pk' requires its primary key columns"
+    );
+}
+
+#[test]
+fn test_mysql_dialect_generation() {
+    initialize_grasql();
+
+    let config = Config {
+        sql_dialect: Dialect::Mysql,
+        sql_keyword_case: SqlKeywordCase::Lower,
+        null_ordering: NullOrdering::NullsLast,
+        ..Config::default()
+    };
+    let generate_mysql = |query: &str| {
+        let (info, response) = resolve_query(query);
+        generate_sql_with_config(&info, &response, &config)
+            .map(|statements| statements[0].sql.clone())
+    };
+
+    // JSON, casts, array parameters and NULL placement use MySQL syntax, and
+    // lower-casing leaves backtick-quoted names alone
+    assert_eq!(
+        generate_mysql(
+            r#"query($ids: [Int!]) {
+                users(
+                    where: { id: { _in: $ids }, name: { _cast: { String: { _eq: "x" } } } },
+                    order_by: { name: asc }
+                ) { id posts { title } }
+            }"#
+        )
+        .unwrap(),
+        "select t0.`id` as `id`, (select coalesce(json_arrayagg(json_object('title', t1.`title`)), json_array()) from `public`.`posts` as t1 where t1.`user_id` = t0.`id`) as `posts` from `public`.`users` as t0 where t0.`id` member of (cast(? as json)) and cast(t0.`name` as char) = ? order by t0.`name` is null asc, t0.`name` asc"
+    );
+
+    assert_eq!(
+        generate_mysql(
+            r#"mutation {
+                insert_users(
+                    objects: [{ id: 1, name: "John" }],
+                    on_conflict: { constraint: users_pkey, update_columns: [name] }
+                ) { affected_rows }
+            }"#
+        )
+        .unwrap(),
+        "insert into `public`.`users` as t0 (`id`, `name`) values (?, ?) on duplicate key update `name` = values(`name`)"
+    );
+
+    // What MySQL can't express is rejected rather than generated
+    assert_eq!(
+        generate_mysql(
+            r#"mutation {
+                insert_users(
+                    objects: [{ id: 1 }],
+                    on_conflict: { constraint: users_pkey, update_columns: [] }
+                ) { affected_rows }
+            }"#
+        )
+        .unwrap_err(),
+        "'on_conflict' needs 'update_columns' in the SQL dialect"
+    );
+    assert_eq!(
+        generate_mysql(r#"mutation { insert_users(objects: [{ id: 1 }]) { returning { id } } }"#)
+            .unwrap_err(),
+        "'returning' is not supported by the SQL dialect"
+    );
+}
@@ -0,0 +1,49 @@
+//! Thread-safety guarantees of cached query information
+//!
+//! `CachedQueryInfo` implements Send and Sync by hand around its raw document
+//! pointer. These tests pin that down so a field change that breaks it fails
+//! here rather than in the NIF layer. That a document can't outlive its
+//! context is checked by the compile-fail cases in `tests/ui`.
+
+use graphql_query::ast::PrintNode;
+use grasql::parser::parse_graphql;
+use grasql::types::{ResolutionRequest, ResolutionResponse};
+use grasql::CachedQueryInfo;
+use std::sync::Arc;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_cached_types_are_send_sync() {
+    assert_send_sync::<CachedQueryInfo>();
+    assert_send_sync::<Arc<CachedQueryInfo>>();
+    assert_send_sync::<ResolutionRequest>();
+    assert_send_sync::<ResolutionResponse>();
+}
+
+#[test]
+fn test_misuse_does_not_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
+
+#[test]
+fn test_document_readable_from_other_threads() {
+    let _ = grasql::types::initialize_for_test();
+
+    let (parsed, _) = parse_graphql("{ users { id posts { title } } }").unwrap();
+    let info: Arc<CachedQueryInfo> = Arc::new(parsed.into());
+    let expected = info.document().unwrap().print();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let info = Arc::clone(&info);
+            thread::spawn(move || info.document().unwrap().print())
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+}
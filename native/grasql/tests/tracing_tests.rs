@@ -0,0 +1,115 @@
+#![cfg(feature = "tracing")]
+
+use grasql::generate_query_id;
+use grasql::parser::parse_graphql;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+// Helper function to ensure GraSQL is initialized before running tests
+fn initialize_grasql() {
+    // Ignore errors if already initialized
+    let _ = grasql::types::initialize_for_test();
+}
+
+/// A span seen by `RecordingSubscriber`
+#[derive(Debug, Default, Clone)]
+struct RecordedSpan {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: HashMap<&'static str, String>,
+}
+
+impl Visit for RecordedSpan {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.insert(field.name(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name(), value.to_string());
+    }
+}
+
+/// Subscriber keeping every span with its parent and recorded fields
+#[derive(Default)]
+struct RecordingSubscriber {
+    spans: Arc<Mutex<Vec<RecordedSpan>>>,
+    entered: Mutex<Vec<u64>>,
+}
+
+impl Subscriber for RecordingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let mut span = RecordedSpan {
+            name: attrs.metadata().name(),
+            parent: self.entered.lock().unwrap().last().copied(),
+            ..RecordedSpan::default()
+        };
+        attrs.record(&mut span);
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        values.record(&mut spans[span.into_u64() as usize - 1]);
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+#[test]
+fn test_parse_emits_phase_spans() {
+    initialize_grasql();
+
+    let subscriber = RecordingSubscriber::default();
+    let spans = subscriber.spans.clone();
+
+    let query = "query Traced { users(where: { active: { _eq: true } }) { id posts { title } } }";
+    tracing::subscriber::with_default(subscriber, || parse_graphql(query).unwrap());
+    let spans = spans.lock().unwrap();
+
+    // One parse span carrying the query ID
+    let (parse_idx, parse) = spans
+        .iter()
+        .enumerate()
+        .find(|(_, span)| span.name == "grasql.parse")
+        .unwrap();
+    assert_eq!(parse.fields["query_id"], generate_query_id(query));
+    assert!(parse.fields.contains_key("duration_us"));
+
+    // Its phases, in the order they run, each timed
+    let phases: Vec<&RecordedSpan> = spans
+        .iter()
+        .filter(|span| span.name == "grasql.phase")
+        .collect();
+    assert_eq!(
+        phases
+            .iter()
+            .map(|span| span.fields["phase"].as_str())
+            .collect::<Vec<_>>(),
+        vec!["document", "extraction", "encoding"]
+    );
+    for phase in phases {
+        assert_eq!(phase.parent, Some(parse_idx as u64 + 1));
+        assert!(phase.fields["duration_us"].parse::<u64>().is_ok());
+    }
+}
@@ -0,0 +1,11 @@
+use grasql::parser::parse_graphql;
+use grasql::CachedQueryInfo;
+
+fn main() {
+    let document = {
+        let (parsed, _) = parse_graphql("{ users { id } }").unwrap();
+        let info: CachedQueryInfo = parsed.into();
+        info.document().unwrap()
+    };
+    println!("{:?}", document.definitions.len());
+}
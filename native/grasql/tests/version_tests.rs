@@ -0,0 +1,32 @@
+#[test]
+fn test_version_matches_cargo() {
+    assert_eq!(grasql::VERSION, env!("CARGO_PKG_VERSION"));
+
+    // The manifest is the source of truth for the published version
+    let manifest = include_str!("../Cargo.toml");
+    let declared = manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("version = "))
+        .map(|version| version.trim_matches('"'));
+    assert_eq!(declared, Some(grasql::VERSION));
+}
+
+#[test]
+fn test_enabled_features() {
+    let features = grasql::enabled_features();
+    assert_eq!(
+        features.contains(&"test-utils"),
+        cfg!(feature = "test-utils")
+    );
+}
+
+#[test]
+fn test_path_format_version_matches_elixir() {
+    // GraSQL.Schema rejects requests whose path encoding it does not read
+    let schema = include_str!("../../../lib/grasql/schema.ex");
+    let declared = schema
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("@path_format_version "))
+        .and_then(|version| version.parse::<u8>().ok());
+    assert_eq!(declared, Some(grasql::encoding::PATH_FORMAT_VERSION));
+}
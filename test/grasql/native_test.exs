@@ -0,0 +1,9 @@
+defmodule GraSQL.NativeTest do
+  use ExUnit.Case
+
+  describe "resolution_request/1" do
+    test "returns an error tuple for a query that is not cached" do
+      assert {:error, :cache_miss} = GraSQL.Native.resolution_request("not-a-cached-query")
+    end
+  end
+end
@@ -7,6 +7,8 @@ defmodule GraSQL.SchemaTest do
       resolution_request = {
         :query_id,
         "test_query_id",
+        :version,
+        1,
         :strings,
         ["users", "id", "name", "email", "profile", "bio", "avatar"],
         :paths,
@@ -15,11 +17,19 @@ defmodule GraSQL.SchemaTest do
         [0, 3],
         :path_types,
         [0, 1],
+        :entities,
+        [0, 4],
         :cols,
         [{0, [1, 2, 3]}, {4, [5, 6]}],
         # users (index 0) -> query (type 0)
         :ops,
-        [{0, 0}]
+        [{0, 0}],
+        :directives,
+        [],
+        :warnings,
+        [],
+        :argument_names,
+        []
       }
 
       # Call resolve
@@ -68,6 +78,8 @@ defmodule GraSQL.SchemaTest do
       resolution_request = {
         :query_id,
         "multi_op_query_id",
+        :version,
+        1,
         :strings,
         ["users", "id", "name", "posts", "title", "content"],
         :paths,
@@ -76,11 +88,19 @@ defmodule GraSQL.SchemaTest do
         [0, 3, 6],
         :path_types,
         [0, 0, 1],
+        :entities,
+        [0, 1, 0],
         :cols,
         [{0, [1, 2]}, {3, [4, 5]}],
         # users -> query, posts -> insert_mutation
         :ops,
-        [{0, 0}, {3, 1}]
+        [{0, 0}, {3, 1}],
+        :directives,
+        [],
+        :warnings,
+        [],
+        :argument_names,
+        []
       }
 
       # Call resolve
@@ -107,6 +127,8 @@ defmodule GraSQL.SchemaTest do
       resolution_request = {
         :query_id,
         "nested_query_id",
+        :version,
+        1,
         :strings,
         ["users", "id", "posts", "title", "comments", "content"],
         :paths,
@@ -116,11 +138,19 @@ defmodule GraSQL.SchemaTest do
         # users, users.posts, users.posts.comments
         :path_types,
         [0, 1, 1],
+        :entities,
+        [0, 0, 3],
         :cols,
         [{0, [1]}, {2, [3]}, {4, [5]}],
         # users -> query
         :ops,
-        [{0, 0}]
+        [{0, 0}],
+        :directives,
+        [],
+        :warnings,
+        [],
+        :argument_names,
+        []
       }
 
       # Call resolve
@@ -150,6 +180,8 @@ defmodule GraSQL.SchemaTest do
       resolution_request = {
         :query_id,
         "columns_query_id",
+        :version,
+        1,
         :strings,
         ["users", "id", "name", "email"],
         :paths,
@@ -159,11 +191,19 @@ defmodule GraSQL.SchemaTest do
         # just users table
         :path_types,
         [0],
+        :entities,
+        [0],
         :cols,
         [{0, [1, 2, 3]}],
         # users -> query
         :ops,
-        [{0, 0}]
+        [{0, 0}],
+        :directives,
+        [],
+        :warnings,
+        [],
+        :argument_names,
+        []
       }
 
       # Call resolve
@@ -182,6 +222,8 @@ defmodule GraSQL.SchemaTest do
       resolution_request = {
         :query_id,
         "empty_query_id",
+        :version,
+        1,
         :strings,
         [],
         :paths,
@@ -190,9 +232,17 @@ defmodule GraSQL.SchemaTest do
         [],
         :path_types,
         [],
+        :entities,
+        [],
         :cols,
         [],
         :ops,
+        [],
+        :directives,
+        [],
+        :warnings,
+        [],
+        :argument_names,
         []
       }
 